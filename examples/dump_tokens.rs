@@ -0,0 +1,22 @@
+// Prints every token of a source file with its position.
+// cargo run --example dump_tokens -- test-files/main.cc
+use parser::prelude::*;
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("test-files/main.cc"));
+    let mut lexer = Lexer::from_file(&path).unwrap();
+
+    loop {
+        match lexer.next() {
+            Ok(token_info) => println!("{}", token_info),
+            Err(LexerError::EndOfFileReached) => break,
+            Err(error) => {
+                eprintln!("{}", error);
+
+                std::process::exit(1);
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+// Parses a source file, reports its diagnostics and walks the tree to print what it contains.
+// cargo run --example parse_and_walk -- test-files/main.cc
+use parser::prelude::*;
+
+// Prints every function that gets defined or called, with where it happens
+struct FunctionPrinter;
+
+impl Visitor for FunctionPrinter {
+    fn enter(&mut self, node: &ParseNode) {
+        let name = node
+            .children
+            .iter()
+            .find(|child| child.kind == NodeKind::TokenClass(TokenClass::Identifier))
+            .and_then(|child| child.value.as_deref())
+            .unwrap_or_default();
+
+        match node.kind {
+            NodeKind::FunctionDefinition => {
+                println!("{}:{} defines {}", node.loc.line, node.loc.column, name)
+            }
+            NodeKind::FunctionCall => {
+                println!("{}:{} calls {}", node.loc.line, node.loc.column, name)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("test-files/main.cc"));
+    let lexer = Lexer::from_file(&path).unwrap();
+    let recovered = RecursiveDescentParser::new(lexer).parse_with_recovery();
+
+    for error in &recovered.errors {
+        eprintln!("{}", error);
+    }
+
+    recovered.tree.walk(&mut FunctionPrinter);
+
+    let counter = NodeCounter::of(&recovered.tree);
+
+    println!(
+        "{} statements, {} of them assignments",
+        counter.statements(),
+        counter.count(&NodeKind::AssignmentStatement)
+    );
+}
@@ -1,41 +1,86 @@
-use std::io::{BufRead, BufReader, Cursor, Read, Result as IOResult, Seek, SeekFrom};
+use std::{
+    collections::VecDeque,
+    io::{BufRead, BufReader, Cursor, Error as IOError, Read, Result as IOResult, Seek, SeekFrom},
+};
+use thiserror::Error as ThisError;
+
+// The lexer only ever goes back a few tokens,
+// so older checkpoints are dropped instead of kept forever
+pub const DEFAULT_MAX_CHECKPOINT_DEPTH: usize = 64;
+
+#[derive(ThisError, Debug)]
+pub enum BufferError {
+    #[error("There is no checkpoint to go back to!")]
+    NoCheckpoint,
+    #[error("Could not read the current position of the buffer!")]
+    Io(#[from] IOError),
+    #[error("Could not seek back to position {0} of the buffer!")]
+    SeekFailed(u64, #[source] IOError),
+}
 
-pub trait SeekableBufRead: BufRead + Seek {}
+// Send so a lexer (and the parser owning it) can be moved to a worker thread
+pub trait SeekableBufRead: BufRead + Seek + Send {}
 
-impl<T: AsRef<[u8]>> SeekableBufRead for Cursor<T> {}
-impl<T: Read + Seek> SeekableBufRead for BufReader<T> {}
+impl<T: AsRef<[u8]> + Send> SeekableBufRead for Cursor<T> {}
+impl<T: Read + Seek + Send> SeekableBufRead for BufReader<T> {}
 
 pub struct LexerBufferReader {
-    last_positions: Vec<u64>,
+    max_depth: usize,
+    last_positions: VecDeque<u64>,
     peeked_char: Option<char>,
     buffer: Box<dyn SeekableBufRead>,
 }
 
 impl LexerBufferReader {
     pub fn new(buffer: Box<dyn SeekableBufRead>) -> Self {
+        Self::with_max_depth(buffer, DEFAULT_MAX_CHECKPOINT_DEPTH)
+    }
+
+    pub fn with_max_depth(buffer: Box<dyn SeekableBufRead>, max_depth: usize) -> Self {
         Self {
             buffer,
+            max_depth,
             peeked_char: None,
-            last_positions: vec![],
+            last_positions: VecDeque::new(),
         }
     }
 }
 
 impl LexerBufferReader {
-    pub fn checkpoint(&mut self) -> Result<(), ()> {
-        if let Ok(pos) = self.buffer.stream_position() {
-            // if we have peeked already
-            // set the actual pos to current - 1
-            if self.peeked_char.is_some() {
-                self.last_positions.push(pos - 1);
-            } else {
-                self.last_positions.push(pos);
-            }
+    // Swaps in a new source while keeping the checkpoint allocation
+    pub fn reset(&mut self, buffer: Box<dyn SeekableBufRead>) {
+        self.buffer = buffer;
+        self.peeked_char = None;
+        self.last_positions.clear();
+    }
+
+    // Goes back to the start of the source, e.g. after a pre-pass over it
+    pub fn rewind(&mut self) -> Result<(), BufferError> {
+        self.buffer
+            .seek(SeekFrom::Start(0))
+            .map_err(|error| BufferError::SeekFailed(0, error))?;
+        self.peeked_char = None;
+        self.last_positions.clear();
 
-            Ok(())
+        Ok(())
+    }
+
+    pub fn checkpoint(&mut self) -> Result<(), BufferError> {
+        let pos = self.buffer.stream_position()?;
+
+        // if we have peeked already
+        // set the actual pos to current - 1
+        if self.peeked_char.is_some() {
+            self.last_positions.push_back(pos - 1);
         } else {
-            Err(())
+            self.last_positions.push_back(pos);
+        }
+
+        if self.last_positions.len() > self.max_depth {
+            self.last_positions.pop_front();
         }
+
+        Ok(())
     }
 
     pub fn read_line(&mut self, buf: &mut String) -> IOResult<usize> {
@@ -57,7 +102,7 @@ impl LexerBufferReader {
     }
 
     pub fn peek_char(&mut self) -> Option<&char> {
-        if !self.peeked_char.is_some() {
+        if self.peeked_char.is_none() {
             match self.read_char() {
                 Ok(char) => self.peeked_char = Some(char),
                 _ => return None,
@@ -67,16 +112,47 @@ impl LexerBufferReader {
         self.peeked_char.as_ref()
     }
 
-    pub fn back(&mut self) -> Result<u64, ()> {
-        if let Some(pos) = self.last_positions.pop() {
-            let seeked = self.buffer.seek(SeekFrom::Start(pos)).unwrap_or_default();
+    // The character after the peeked one, read and given back so neither is consumed
+    pub fn peek_second_char(&mut self) -> Option<char> {
+        self.peek_char()?;
 
-            self.peeked_char = None;
+        let mut char_buf: [u8; 1] = [0; 1];
+        self.buffer.read_exact(&mut char_buf).ok()?;
+        self.buffer.seek(SeekFrom::Current(-1)).ok()?;
 
-            Ok(seeked)
-        } else {
-            Err(())
-        }
+        Some(char_buf[0] as char)
+    }
+
+    pub fn back(&mut self) -> Result<u64, BufferError> {
+        let pos = self
+            .last_positions
+            .pop_back()
+            .ok_or(BufferError::NoCheckpoint)?;
+        let seeked = self
+            .buffer
+            .seek(SeekFrom::Start(pos))
+            .map_err(|error| BufferError::SeekFailed(pos, error))?;
+
+        self.peeked_char = None;
+
+        Ok(seeked)
+    }
+
+    // Drops the latest checkpoint without going back to it
+    pub fn discard(&mut self) -> Result<(), BufferError> {
+        self.last_positions
+            .pop_back()
+            .map(|_| ())
+            .ok_or(BufferError::NoCheckpoint)
+    }
+
+    // Keeps everything consumed so far, dropping every checkpoint
+    pub fn commit(&mut self) {
+        self.last_positions.clear();
+    }
+
+    pub fn checkpoint_depth(&self) -> usize {
+        self.last_positions.len()
     }
 }
 
@@ -119,6 +195,16 @@ mod tests {
         assert_next_line!(reader, "interesting thing");
     }
 
+    #[test]
+    fn it_can_peek_two_characters_without_consuming_them() {
+        let mut reader = LexerBufferReader::new(Box::new(Cursor::new("ab")));
+
+        assert_eq!(reader.peek_second_char(), Some('b'));
+        assert_next_char!(reader, 'a');
+        assert_eq!(reader.peek_second_char(), None);
+        assert_next_char!(reader, 'b');
+    }
+
     #[test]
     fn it_can_store_a_checkpoint_and_go_back_to_it() {
         let mut reader = LexerBufferReader::new(Box::new(Cursor::new(STRING_FIXTURE)));
@@ -232,6 +318,118 @@ mod tests {
         assert_eq!(reader.peek_char().unwrap(), &'e');
     }
 
+    #[test]
+    fn it_can_be_reset_to_a_new_buffer() {
+        let mut reader = LexerBufferReader::new(Box::new(Cursor::new(STRING_FIXTURE)));
+
+        reader.checkpoint().unwrap();
+        assert_next_char!(reader, 't');
+        assert_eq!(reader.peek_char().unwrap(), &'e');
+
+        reader.reset(Box::new(Cursor::new("new")));
+
+        assert_next_char!(reader, 'n');
+        assert!(reader.back().is_err());
+    }
+
+    #[test]
+    fn it_returns_an_error_if_there_is_no_checkpoint_to_go_back_to() {
+        let mut reader = LexerBufferReader::new(Box::new(Cursor::new(STRING_FIXTURE)));
+
+        assert!(matches!(reader.back(), Err(BufferError::NoCheckpoint)));
+    }
+
+    #[test]
+    fn it_can_discard_the_latest_checkpoint() {
+        let mut reader = LexerBufferReader::new(Box::new(Cursor::new(STRING_FIXTURE)));
+
+        reader.checkpoint().unwrap();
+        assert_next_char!(reader, 't');
+        reader.checkpoint().unwrap();
+        assert_next_char!(reader, 'e');
+
+        reader.discard().unwrap();
+
+        assert_next_char!(reader, 's');
+        reader.back().unwrap();
+        assert_next_char!(reader, 't');
+        assert!(matches!(reader.discard(), Err(BufferError::NoCheckpoint)));
+    }
+
+    #[test]
+    fn it_can_commit_all_checkpoints() {
+        let mut reader = LexerBufferReader::new(Box::new(Cursor::new(STRING_FIXTURE)));
+
+        reader.checkpoint().unwrap();
+        reader.checkpoint().unwrap();
+        reader.commit();
+
+        assert_eq!(reader.checkpoint_depth(), 0);
+        assert!(matches!(reader.back(), Err(BufferError::NoCheckpoint)));
+    }
+
+    #[test]
+    fn it_drops_the_oldest_checkpoints_past_the_max_depth() {
+        let mut reader =
+            LexerBufferReader::with_max_depth(Box::new(Cursor::new(STRING_FIXTURE)), 2);
+
+        reader.checkpoint().unwrap();
+        assert_next_char!(reader, 't');
+        reader.checkpoint().unwrap();
+        assert_next_char!(reader, 'e');
+        reader.checkpoint().unwrap();
+        assert_next_char!(reader, 's');
+
+        assert_eq!(reader.checkpoint_depth(), 2);
+
+        reader.back().unwrap();
+        assert_next_char!(reader, 's');
+        reader.back().unwrap();
+        assert_next_char!(reader, 'e');
+        assert!(matches!(reader.back(), Err(BufferError::NoCheckpoint)));
+    }
+
+    struct FailingBuffer;
+
+    impl Read for FailingBuffer {
+        fn read(&mut self, _buf: &mut [u8]) -> IOResult<usize> {
+            Ok(0)
+        }
+    }
+
+    impl BufRead for FailingBuffer {
+        fn fill_buf(&mut self) -> IOResult<&[u8]> {
+            Ok(&[])
+        }
+
+        fn consume(&mut self, _amt: usize) {}
+    }
+
+    impl Seek for FailingBuffer {
+        fn seek(&mut self, _pos: SeekFrom) -> IOResult<u64> {
+            Err(IOError::other("seek is broken"))
+        }
+    }
+
+    impl SeekableBufRead for FailingBuffer {}
+
+    #[test]
+    fn it_returns_io_errors_when_it_cannot_checkpoint() {
+        let mut reader = LexerBufferReader::new(Box::new(FailingBuffer));
+
+        assert!(matches!(reader.checkpoint(), Err(BufferError::Io(_))));
+        assert_eq!(reader.checkpoint_depth(), 0);
+    }
+
+    #[test]
+    fn it_returns_seek_errors_when_it_cannot_go_back() {
+        let mut reader = LexerBufferReader::new(Box::new(FailingBuffer));
+
+        reader.last_positions.push_back(3);
+
+        assert!(matches!(reader.back(), Err(BufferError::SeekFailed(3, _))));
+    }
+
     #[test]
     fn it_returns_none_if_cannot_peek_next_char() {
         let mut reader = LexerBufferReader::new(Box::new(Cursor::new("")));
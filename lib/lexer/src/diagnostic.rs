@@ -0,0 +1,94 @@
+use crate::position::{ColumnNumber, LineNumber};
+use std::error::Error;
+
+// Errors stop the compilation, warnings are only reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::Display),
+    strum(serialize_all = "lowercase")
+)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[cfg(not(feature = "strum"))]
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+// Where in the source a diagnostic points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: LineNumber,
+    pub column: ColumnNumber,
+}
+
+// The shape the errors of every crate share, so the driver reports them all the same way.
+// Codes are stable, L is for the lexer, P for the parser, S for the semantic checks,
+// W for warnings, R for refactorings, D for the driver and I for the invariants
+// the compiler checks on itself
+pub trait CompilerError: Error {
+    fn code(&self) -> &'static str;
+
+    // None for errors that are not about the source, like a file that can not be read
+    fn span(&self) -> Option<Span>;
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    // e.g. error[L0006]: Number 99999999999 is out of range at 1:9!, followed by its causes
+    fn render(&self) -> String {
+        let mut rendered = format!("{}[{}]: {}", self.severity(), self.code(), self);
+        let mut source = self.source();
+
+        while let Some(error) = source {
+            rendered.push_str(&format!("\n  caused by: {}", error));
+            source = error.source();
+        }
+
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::LexerError;
+
+    #[test]
+    fn it_renders_the_severity_code_and_message() {
+        let error = LexerError::NumberOutOfRange {
+            value: "99999999999".to_owned(),
+            line: LineNumber::new(1),
+            column: ColumnNumber::new(9),
+        };
+
+        assert_eq!(
+            error.render(),
+            "error[L0006]: Number 99999999999 is out of range at 1:9!"
+        );
+        assert_eq!(
+            error.span(),
+            Some(Span {
+                line: LineNumber::new(1),
+                column: ColumnNumber::new(9),
+            })
+        );
+    }
+
+    #[test]
+    fn it_has_no_span_for_errors_outside_of_the_source() {
+        let error = LexerError::CannotOpenFile("missing.cc".to_owned());
+
+        assert_eq!(error.code(), "L0003");
+        assert_eq!(error.span(), None);
+    }
+}
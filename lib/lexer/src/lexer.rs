@@ -1,9 +1,19 @@
 use thiserror::Error as ThisError;
 
-use crate::buffer::LexerBufferReader;
+use crate::buffer::{
+    BufferError, LexerBufferReader, SeekableBufRead, DEFAULT_MAX_CHECKPOINT_DEPTH,
+};
+use crate::diagnostic::{CompilerError, Span};
+use crate::metadata::{IdentifierMetadata, LiteralMetadata, TokenMetadata};
+use crate::number::NumberForm;
 use crate::operator::*;
+use crate::options::LexerOptions;
+use crate::position::{ColumnNumber, LineNumber};
+use crate::suggestion::suggest_keyword;
 use crate::token::*;
 use std::{
+    collections::VecDeque,
+    fmt::Display,
     fs::File,
     io::{BufReader, Cursor, Error as IOError},
     path::Path,
@@ -19,75 +29,751 @@ pub enum LexerError {
     CannotOpenFile(String),
     #[error("Could not go back to previous token consumption!")]
     NoCheckpointToGoBackTo,
+    #[error("Lexer buffer has failed!")]
+    Buffer(#[source] BufferError),
+    #[error("Number {value} is out of range at {line}:{column}!")]
+    NumberOutOfRange {
+        value: String,
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+    #[error("{form} number {value} is not enabled at {line}:{column}!")]
+    DisabledNumberForm {
+        value: String,
+        form: NumberForm,
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+    #[error("Line {line} is longer than the maximum of {max} characters!")]
+    LineTooLong { line: LineNumber, max: usize },
+    #[error(
+        "Operator {operator} at {line}:{column} is missing its operand at the end of the file!"
+    )]
+    OperatorAtEndOfFile {
+        operator: Operator,
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+    // reported by validate_balance, points at the closing bracket
+    #[error("Bracket `{close}` at {line}:{column} does not close `{open}` opened at {open_line}:{open_column}!")]
+    MismatchedBracket {
+        open: char,
+        open_line: LineNumber,
+        open_column: ColumnNumber,
+        close: char,
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+    #[error("Bracket `{bracket}` opened at {line}:{column} is never closed!")]
+    UnclosedBracket {
+        bracket: char,
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+    #[error("Bracket `{bracket}` at {line}:{column} has nothing to close!")]
+    UnopenedBracket {
+        bracket: char,
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+    #[error("Comment opened at {line}:{column} is never closed!")]
+    UnterminatedComment {
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+    // the line ended before the closing quote, points at the opening one
+    #[error("String opened at {line}:{column} is never closed!")]
+    UnterminatedString {
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+    #[error("{form} number {value} is malformed at {line}:{column}!")]
+    MalformedNumber {
+        value: String,
+        form: NumberForm,
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+    // made by the parser from a Token::Error, the lexer itself keeps going after one
+    #[error("{message} at {line}:{column}!")]
+    InvalidToken {
+        message: String,
+        line: LineNumber,
+        column: ColumnNumber,
+    },
+}
+
+impl LexerError {
+    // The line an error points at, None for errors that are not about the source
+    pub fn line(&self) -> Option<LineNumber> {
+        match self {
+            Self::NumberOutOfRange { line, .. }
+            | Self::DisabledNumberForm { line, .. }
+            | Self::MalformedNumber { line, .. }
+            | Self::LineTooLong { line, .. }
+            | Self::OperatorAtEndOfFile { line, .. }
+            | Self::UnterminatedComment { line, .. }
+            | Self::UnterminatedString { line, .. }
+            | Self::InvalidToken { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+
+    // Errors about a piece of the source, the rest of it can still be lexed
+    pub fn is_recoverable(&self) -> bool {
+        matches!(
+            self,
+            Self::NumberOutOfRange { .. }
+                | Self::DisabledNumberForm { .. }
+                | Self::MalformedNumber { .. }
+                | Self::LineTooLong { .. }
+                | Self::OperatorAtEndOfFile { .. }
+                | Self::UnterminatedString { .. }
+        )
+    }
+}
+
+impl CompilerError for LexerError {
+    // The stable code of the error, written out so the lexer needs no strum to have them
+    fn code(&self) -> &'static str {
+        match self {
+            Self::EndOfFileReached => "L0001",
+            Self::FailedToReadNextLine(_) => "L0002",
+            Self::CannotOpenFile(_) => "L0003",
+            Self::NoCheckpointToGoBackTo => "L0004",
+            Self::Buffer(_) => "L0005",
+            Self::NumberOutOfRange { .. } => "L0006",
+            Self::DisabledNumberForm { .. } => "L0007",
+            Self::LineTooLong { .. } => "L0008",
+            Self::OperatorAtEndOfFile { .. } => "L0009",
+            Self::MismatchedBracket { .. } => "L0010",
+            Self::UnclosedBracket { .. } => "L0011",
+            Self::UnopenedBracket { .. } => "L0012",
+            Self::UnterminatedComment { .. } => "L0013",
+            Self::InvalidToken { .. } => "L0014",
+            Self::UnterminatedString { .. } => "L0015",
+            Self::MalformedNumber { .. } => "L0016",
+        }
+    }
+
+    fn span(&self) -> Option<Span> {
+        match self {
+            Self::NumberOutOfRange { line, column, .. }
+            | Self::DisabledNumberForm { line, column, .. }
+            | Self::MalformedNumber { line, column, .. }
+            | Self::OperatorAtEndOfFile { line, column, .. }
+            | Self::MismatchedBracket { line, column, .. }
+            | Self::UnclosedBracket { line, column, .. }
+            | Self::UnopenedBracket { line, column, .. }
+            | Self::UnterminatedComment { line, column }
+            | Self::UnterminatedString { line, column }
+            | Self::InvalidToken { line, column, .. } => Some(Span {
+                line: *line,
+                column: *column,
+            }),
+            // the whole line is too long, it points at its start
+            Self::LineTooLong { line, .. } => Some(Span {
+                line: *line,
+                column: ColumnNumber::FIRST,
+            }),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct TokenInfo {
-    pub line: usize,         // Would lines exceed 4 billion? :D
-    pub start_column: usize, // Would horizontal characters exceed 4 billion? :D
+    pub line: LineNumber,           // Would lines exceed 4 billion? :D
+    pub start_column: ColumnNumber, // Would horizontal characters exceed 4 billion? :D
+    pub end_column: ColumnNumber,   // column of the last character of the token
     pub token: Token,
+    pub metadata: Option<TokenMetadata>,
+    // the /// comment lines right before the token, without the slashes
+    pub doc: Option<String>,
+}
+
+impl From<BufferError> for LexerError {
+    fn from(error: BufferError) -> Self {
+        match error {
+            BufferError::NoCheckpoint => Self::NoCheckpointToGoBackTo,
+            error => Self::Buffer(error),
+        }
+    }
+}
+
+impl Display for TokenInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{} {}", self.line, self.start_column, self.token)
+    }
+}
+
+// A binary operator that was the last token so far
+#[derive(Debug, Clone, Copy)]
+struct DanglingOperator {
+    operator: Operator,
+    line: LineNumber,
+    column: ColumnNumber,
+}
+
+// The state the lexer goes back to, restoring the newline_statements state as well so
+// going back cannot emit a stale virtual Semi. Line and column are the counters of the
+// lexer, the column is 0 before the first character of a line
+struct LexerCheckpoint {
+    line: usize,
+    column: usize,
+    paren_depth: usize,
+    ends_statement: bool,
+    line_end: Option<(LineNumber, ColumnNumber)>,
+    dangling_operator: Option<DanglingOperator>,
 }
 
 pub struct Lexer {
     line: usize,
     column: usize,
+    // where the source starts, the line and the column before its first character
+    start: (usize, usize),
     cursor: LexerBufferReader,
-    peeked: Option<TokenInfo>,
-    checkpoints: Vec<(usize, usize)>,
+    // errors are kept as well, so peeking never loses a lexing failure
+    peeked: Option<Result<TokenInfo, LexerError>>,
+    checkpoints: VecDeque<LexerCheckpoint>,
+    options: LexerOptions,
+    paren_depth: usize,
+    ends_statement: bool,
+    line_end: Option<(LineNumber, ColumnNumber)>,
+    dangling_operator: Option<DanglingOperator>,
 }
 
 impl Lexer {
     pub fn new(code: String) -> Self {
+        Self::from_buffer(Box::new(Cursor::new(code)))
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, LexerError> {
+        match File::open(Path::new(&path)) {
+            Ok(file) => Ok(Self::from_buffer(Box::new(BufReader::new(file)))),
+            _ => Err(LexerError::CannotOpenFile(path.to_owned())),
+        }
+    }
+
+    fn from_buffer(buffer: Box<dyn SeekableBufRead>) -> Self {
         Self {
             line: 1,
             column: 0,
-            cursor: LexerBufferReader::new(Box::new(Cursor::new(code))),
+            start: (1, 0),
+            cursor: LexerBufferReader::new(buffer),
             peeked: None,
-            checkpoints: vec![],
+            checkpoints: VecDeque::new(),
+            options: LexerOptions::default(),
+            paren_depth: 0,
+            ends_statement: false,
+            line_end: None,
+            dangling_operator: None,
         }
     }
 
-    pub fn from_file(path: &str) -> Result<Self, LexerError> {
-        match File::open(Path::new(&path)) {
-            Ok(file) => Ok(Self {
-                line: 1,
-                column: 0,
-                cursor: LexerBufferReader::new(Box::new(BufReader::new(file))),
-                peeked: None,
-                checkpoints: vec![],
+    pub fn with_options(mut self, options: LexerOptions) -> Self {
+        self.options = options;
+
+        self
+    }
+
+    // Lexes a part of a larger source that starts at line:column of it, so its tokens
+    // have the positions they have in the whole source
+    pub fn starting_at(mut self, line: LineNumber, column: ColumnNumber) -> Self {
+        self.start = (line.get(), column.zero_based());
+        (self.line, self.column) = self.start;
+
+        self
+    }
+}
+
+// Loading is async so callers with an event loop do not block on large files,
+// lexing itself still happens synchronously on the loaded source
+#[cfg(feature = "async")]
+impl Lexer {
+    pub async fn from_async_reader<R>(mut reader: R) -> Result<Self, LexerError>
+    where
+        R: futures::io::AsyncRead + Unpin,
+    {
+        use futures::io::AsyncReadExt;
+
+        let mut code = String::new();
+        reader.read_to_string(&mut code).await?;
+
+        Ok(Self::new(code))
+    }
+}
+
+impl Lexer {
+    // Starts lexing a new source, reusing the buffers of this lexer
+    pub fn reset(&mut self, code: String) {
+        self.line = 1;
+        self.column = 0;
+        self.start = (1, 0);
+        self.cursor.reset(Box::new(Cursor::new(code)));
+        self.peeked = None;
+        self.checkpoints.clear();
+        self.paren_depth = 0;
+        self.ends_statement = false;
+        self.line_end = None;
+        self.dangling_operator = None;
+    }
+
+    // Goes back to the start of the source, forgetting everything lexed so far
+    fn rewind(&mut self) -> Result<(), LexerError> {
+        self.cursor.rewind()?;
+        (self.line, self.column) = self.start;
+        self.peeked = None;
+        self.checkpoints.clear();
+        self.paren_depth = 0;
+        self.ends_statement = false;
+        self.line_end = None;
+        self.dangling_operator = None;
+
+        Ok(())
+    }
+}
+
+impl Lexer {
+    fn opening_bracket(token: &Token) -> Option<char> {
+        match token {
+            Token::Lparen => Some('('),
+            Token::LCurly => Some('{'),
+            Token::LBracket => Some('['),
+            _ => None,
+        }
+    }
+
+    // The bracket a token closes and the token itself, e.g. `(` and `)`
+    fn closing_bracket(token: &Token) -> Option<(char, char)> {
+        match token {
+            Token::Rparen => Some(('(', ')')),
+            Token::RCurly => Some(('{', '}')),
+            Token::RBracket => Some(('[', ']')),
+            _ => None,
+        }
+    }
+
+    fn check_balance(&mut self) -> Result<(), LexerError> {
+        let mut open: Vec<(char, LineNumber, ColumnNumber)> = vec![];
+
+        loop {
+            let token_info = match self.next() {
+                Ok(token_info) => token_info,
+                Err(LexerError::EndOfFileReached) => break,
+                // the parser reports it where it happens, what comes after can not be checked
+                Err(_) => return Ok(()),
+            };
+            let (line, column) = (token_info.line, token_info.start_column);
+
+            if let Some(bracket) = Self::opening_bracket(&token_info.token) {
+                open.push((bracket, line, column));
+
+                continue;
+            }
+
+            let Some((opening, closing)) = Self::closing_bracket(&token_info.token) else {
+                continue;
+            };
+
+            match open.pop() {
+                Some((open, ..)) if open == opening => {}
+                Some((open, open_line, open_column)) => {
+                    return Err(LexerError::MismatchedBracket {
+                        open,
+                        open_line,
+                        open_column,
+                        close: closing,
+                        line,
+                        column,
+                    })
+                }
+                None => {
+                    return Err(LexerError::UnopenedBracket {
+                        bracket: closing,
+                        line,
+                        column,
+                    })
+                }
+            }
+        }
+
+        match open.pop() {
+            Some((bracket, line, column)) => Err(LexerError::UnclosedBracket {
+                bracket,
+                line,
+                column,
             }),
-            _ => Err(LexerError::CannotOpenFile(path.to_owned())),
+            None => Ok(()),
+        }
+    }
+
+    // A fast pass over the tokens that only checks every `(`, `{` and `[` is closed by
+    // its own kind, so the most common mistake is reported precisely before parsing.
+    // Goes back to the start of the source afterwards, so it has to run before the first token
+    pub fn validate_balance(&mut self) -> Result<(), LexerError> {
+        let balance = self.check_balance();
+
+        self.rewind()?;
+
+        balance
+    }
+}
+
+impl Lexer {
+    // Skips what is left of the token an error was reported in,
+    // or of the line when the line itself is the problem
+    fn skip_after_error(&mut self, error: &LexerError) -> Result<(), LexerError> {
+        while let Some(&char) = self.cursor.peek_char() {
+            let is_separator = match error {
+                LexerError::LineTooLong { .. } => char == '\n',
+                _ => char.is_whitespace() || self.starts_token("", char),
+            };
+
+            if is_separator {
+                break;
+            }
+
+            self.cursor.read_char()?;
+            self.column += 1;
+        }
+
+        Ok(())
+    }
+
+    // Lexes the whole source without stopping at errors about it, for the error recovery
+    // of the parser and for editors working on broken files. Lexing goes on after the token
+    // an error is in, only errors about reading the source stop it
+    pub fn tokenize_with_errors(&mut self) -> (Vec<TokenInfo>, Vec<LexerError>) {
+        let mut tokens = vec![];
+        let mut errors = vec![];
+
+        loop {
+            match self.next() {
+                Ok(token_info) => tokens.push(token_info),
+                Err(LexerError::EndOfFileReached) => break,
+                Err(error) if error.is_recoverable() => {
+                    let skipped = self.skip_after_error(&error);
+
+                    errors.push(error);
+
+                    if let Err(error) = skipped {
+                        errors.push(error);
+
+                        break;
+                    }
+                }
+                Err(error) => {
+                    errors.push(error);
+
+                    break;
+                }
+            }
         }
+
+        (tokens, errors)
     }
 }
 
 impl Lexer {
-    fn checkpoint(&mut self) {
-        // we do not care if checkpoint fails
-        self.cursor.checkpoint().unwrap_or_default();
-        self.checkpoints.push((self.line, self.column));
+    fn checkpoint(&mut self) -> Result<(), LexerError> {
+        self.cursor.checkpoint()?;
+        self.checkpoints.push_back(LexerCheckpoint {
+            line: self.line,
+            column: self.column,
+            paren_depth: self.paren_depth,
+            ends_statement: self.ends_statement,
+            line_end: self.line_end,
+            dangling_operator: self.dangling_operator,
+        });
+
+        // stay in sync with the checkpoints the buffer keeps
+        if self.checkpoints.len() > DEFAULT_MAX_CHECKPOINT_DEPTH {
+            self.checkpoints.pop_front();
+        }
+
+        Ok(())
+    }
+
+    // Only the tokens that can finish an expression or a call can end a statement
+    fn track_statement_end(&mut self, token: &Token) {
+        match token {
+            Token::Lparen | Token::LBracket => self.paren_depth += 1,
+            Token::Rparen | Token::RBracket => {
+                self.paren_depth = self.paren_depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+
+        self.ends_statement = matches!(
+            token,
+            Token::Identifier(_)
+                | Token::Literal(_)
+                | Token::Number(_)
+                | Token::Boolean(_)
+                | Token::Rparen
+                | Token::RBracket
+                | Token::Operator(Operator::Increment)
+                | Token::Operator(Operator::Decrement)
+        );
+    }
+
+    fn track_dangling_operator(&mut self, token_info: &TokenInfo) {
+        self.dangling_operator = match token_info.token {
+            Token::Operator(operator) if operator.is_binary() => Some(DanglingOperator {
+                operator,
+                line: token_info.line,
+                column: token_info.start_column,
+            }),
+            _ => None,
+        };
+    }
+
+    fn take_virtual_semi(&mut self, at_end_of_file: bool) -> Option<TokenInfo> {
+        let line_end = self.line_end.take();
+
+        if !self.options.newline_statements || !self.ends_statement || self.paren_depth != 0 {
+            return None;
+        }
+
+        let (line, start_column) = match line_end {
+            Some(line_end) => line_end,
+            None if at_end_of_file => (
+                LineNumber::new(self.line),
+                ColumnNumber::new(self.column + 1),
+            ),
+            None => return None,
+        };
+
+        self.ends_statement = false;
+
+        Some(TokenInfo {
+            line,
+            start_column,
+            end_column: start_column,
+            token: Token::Semi,
+            metadata: None,
+            doc: None,
+        })
     }
 }
 
 impl Lexer {
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<TokenInfo, LexerError> {
-        if let Some(token_info) = self.peeked.take() {
-            return Ok(token_info);
+        if let Some(peeked) = self.peeked.take() {
+            return peeked;
+        }
+
+        self.checkpoint()?;
+
+        let mut doc = None;
+
+        // skip lines without tokens in a loop, so long runs of blank lines
+        // (e.g. at the end of the file) do not grow the stack
+        loop {
+            let at_end_of_file = self.cursor.peek_char().is_none();
+
+            if let Some(token_info) = self.take_virtual_semi(at_end_of_file) {
+                return Ok(token_info);
+            }
+
+            if at_end_of_file {
+                // reported once, the call after it reaches the end of the file as usual
+                return Err(match self.dangling_operator.take() {
+                    Some(DanglingOperator {
+                        operator,
+                        line,
+                        column,
+                    }) => LexerError::OperatorAtEndOfFile {
+                        operator,
+                        line,
+                        column,
+                    },
+                    None => LexerError::EndOfFileReached,
+                });
+            }
+
+            if let Some(mut token_info) = self.scan_token(&mut doc)? {
+                token_info.doc = doc;
+                self.track_statement_end(&token_info.token);
+                self.track_dangling_operator(&token_info);
+
+                return Ok(token_info);
+            }
+        }
+    }
+
+    fn check_line_length(&self) -> Result<(), LexerError> {
+        match self.options.max_line_length {
+            Some(max) if self.column > max => Err(LexerError::LineTooLong {
+                line: LineNumber::new(self.line),
+                max,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_number(&self, value: &str, line: usize, column: usize) -> Result<(), LexerError> {
+        let form = NumberForm::of(value).expect("Number tokens should have a number form!");
+        let line = LineNumber::new(line);
+        let column = ColumnNumber::new(column);
+
+        if !self.options.numbers.allows(form) {
+            return Err(LexerError::DisabledNumberForm {
+                value: value.to_owned(),
+                form,
+                line,
+                column,
+            });
+        }
+
+        if !form.fits(value) {
+            return Err(LexerError::NumberOutOfRange {
+                value: value.to_owned(),
+                line,
+                column,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Whether the peeked character starts a token of its own, ending the word before it.
+    // Operators like && and .. start with characters that can otherwise be in a word.
+    // A dot between digits is the one of a float, like 3.14
+    fn starts_token(&mut self, word: &str, next_char: char) -> bool {
+        let is_digits = !word.is_empty() && word.chars().all(|c| c.is_ascii_digit() || c == '_');
+
+        if next_char == '.'
+            && is_digits
+            && self
+                .cursor
+                .peek_second_char()
+                .is_some_and(|second| second.is_ascii_digit())
+        {
+            return false;
+        }
+
+        Token::is_special_char(next_char)
+            || Operator::from_char(next_char).is_some()
+            || self
+                .cursor
+                .peek_second_char()
+                .is_some_and(|second| Operator::from_pair(next_char, second).is_some())
+    }
+
+    fn is_doc_comment_start(&mut self) -> bool {
+        self.cursor.peek_char() == Some(&'/') && self.cursor.peek_second_char() == Some('/')
+    }
+
+    fn is_line_comment_start(&mut self) -> bool {
+        self.cursor.peek_char() == Some(&'/')
+    }
+
+    // Skips the rest of a // comment, up to the end of its line
+    fn skip_line_comment(&mut self) -> Result<(), LexerError> {
+        while let Some(&char) = self.cursor.peek_char() {
+            if char == '\n' {
+                break;
+            }
+
+            self.cursor.read_char()?;
+            self.column += 1;
+            self.check_line_length()?;
+        }
+
+        Ok(())
+    }
+
+    fn is_block_comment_start(&mut self) -> bool {
+        self.cursor.peek_char() == Some(&'*')
+    }
+
+    // Skips a /* comment, its / is read already, up to the */ that closes it.
+    // Comments can be nested, every /* in it needs a */ of its own
+    fn skip_block_comment(&mut self) -> Result<(), LexerError> {
+        let line = LineNumber::new(self.line);
+        let column = ColumnNumber::new(self.column);
+        let mut depth = 0;
+        let mut previous = '/';
+
+        while let Some(&char) = self.cursor.peek_char() {
+            self.cursor.read_char()?;
+
+            if char == '\n' {
+                self.line += 1;
+                self.column = 0;
+                previous = char;
+
+                continue;
+            }
+
+            self.column += 1;
+            self.check_line_length()?;
+
+            match (previous, char) {
+                ('/', '*') => depth += 1,
+                ('*', '/') if depth == 1 => return Ok(()),
+                ('*', '/') => depth -= 1,
+                _ => {
+                    previous = char;
+
+                    continue;
+                }
+            }
+
+            // the characters of /* and */ do not start another one, like the / of /*/
+            previous = ' ';
+        }
+
+        Err(LexerError::UnterminatedComment { line, column })
+    }
+
+    // Reads the rest of a /// comment, up to the end of its line, into doc
+    fn scan_doc_comment(&mut self, doc: &mut Option<String>) -> Result<(), LexerError> {
+        let mut line = String::new();
+
+        while let Some(&char) = self.cursor.peek_char() {
+            if char == '\n' {
+                break;
+            }
+
+            self.cursor.read_char()?;
+            self.column += 1;
+            self.check_line_length()?;
+            line.push(char);
         }
 
-        self.checkpoint();
+        // the two slashes after the first one are not part of the text
+        let text = line[2..].strip_prefix(' ').unwrap_or(&line[2..]);
 
-        if self.cursor.peek_char().is_none() {
-            return Err(LexerError::EndOfFileReached);
+        match doc {
+            Some(doc) => {
+                doc.push('\n');
+                doc.push_str(text);
+            }
+            None => *doc = Some(text.to_owned()),
         }
 
+        Ok(())
+    }
+
+    // Scans the next token on the current line, None if the line has no more tokens.
+    // Doc comments before the token are collected into doc
+    fn scan_token(&mut self, doc: &mut Option<String>) -> Result<Option<TokenInfo>, LexerError> {
         let mut in_a_string = false; // temp fix to not break out of a string if it has spaces
+        let mut string_start = (self.line, self.column);
         let mut word = String::from("");
         let mut start_column = self.column + 1;
-        let start_line = self.line;
+        let mut start_line = self.line;
 
         while let Ok(char) = self.cursor.read_char() {
             if char == '\n' {
+                self.line_end = Some((
+                    LineNumber::new(self.line),
+                    ColumnNumber::new(self.column + 1),
+                ));
                 self.line += 1;
                 self.column = 0;
 
@@ -95,12 +781,18 @@ impl Lexer {
             }
 
             self.column += 1;
-            let next_char = *self.cursor.peek_char().unwrap_or(&' ');
-            let concatanated = format!("{}{}", char, next_char);
+            self.check_line_length()?;
+
+            let next_char = self.cursor.peek_char().copied();
+            let pair = next_char.and_then(|next_char| match (char, next_char) {
+                ('-', '>') => Some(Token::Arrow),
+                (':', ':') => Some(Token::PathSeparator),
+                _ => Operator::from_pair(char, next_char).map(Token::Operator),
+            });
 
             match char {
                 c if !in_a_string && c.is_whitespace() => {
-                    if word.len() > 0 {
+                    if !word.is_empty() {
                         break;
                     }
 
@@ -108,39 +800,74 @@ impl Lexer {
 
                     continue;
                 }
-                // Check if concatanated with the next character we get an operator
-                _ if !in_a_string && next_char != ' ' && Operator::is_operator(&concatanated) => {
+                '/' if !in_a_string && self.is_doc_comment_start() => {
+                    self.scan_doc_comment(doc)?;
+                    start_column = self.column + 1;
+
+                    continue;
+                }
+                '/' if !in_a_string && self.is_block_comment_start() => {
+                    self.skip_block_comment()?;
+                    start_line = self.line;
+                    start_column = self.column + 1;
+
+                    continue;
+                }
+                '/' if !in_a_string && self.is_line_comment_start() => {
+                    self.skip_line_comment()?;
+                    start_column = self.column + 1;
+
+                    continue;
+                }
+                // Check if together with the next character we get an operator, an arrow or ::
+                _ if !in_a_string && pair.is_some() => {
                     self.column += 1;
+                    self.check_line_length()?;
 
                     self.cursor
                         .read_char()
                         .expect("We should have had a value here!");
 
-                    return Ok(TokenInfo {
-                        line: start_line,
-                        start_column,
-                        token: Token::Operator(concatanated.into()),
-                    });
+                    return Ok(Some(TokenInfo {
+                        line: LineNumber::new(start_line),
+                        start_column: ColumnNumber::new(start_column),
+                        end_column: ColumnNumber::new(start_column + 1),
+                        token: pair.expect("We checked the pair above!"),
+                        metadata: None,
+                        doc: None,
+                    }));
                 }
+                // the dot of a float is in the word already
                 c if !in_a_string
-                    && (Token::is_special_char(c) || Operator::is_operator(&c.to_string())) =>
+                    && (word.is_empty() || c != '.')
+                    && (Token::is_special_char(c) || Operator::from_char(c).is_some()) =>
                 {
-                    return Ok(TokenInfo {
-                        line: start_line,
-                        start_column,
+                    return Ok(Some(TokenInfo {
+                        line: LineNumber::new(start_line),
+                        start_column: ColumnNumber::new(start_column),
+                        end_column: ColumnNumber::new(start_column),
                         token: c.into(),
-                    });
+                        metadata: None,
+                        doc: None,
+                    }));
+                }
+                // an escaped character never ends the string, \" is a quote in it
+                '\\' if in_a_string && next_char.is_some_and(|next_char| next_char != '\n') => {
+                    word.push(char);
+                    word.push(self.cursor.read_char()?);
+                    self.column += 1;
+                    self.check_line_length()?;
                 }
                 c => {
                     word.push(c);
 
                     if c == '"' {
                         in_a_string = !in_a_string;
+                        string_start = (start_line, self.column);
                     }
 
                     if !in_a_string
-                        && (Token::is_special_char(next_char)
-                            || Operator::is_operator(&next_char.to_string()))
+                        && next_char.is_some_and(|next_char| self.starts_token(&word, next_char))
                     {
                         break;
                     }
@@ -148,52 +875,112 @@ impl Lexer {
             };
         }
 
-        if word.len() == 0 {
-            return self.next();
+        if in_a_string {
+            return Err(LexerError::UnterminatedString {
+                line: LineNumber::new(string_start.0),
+                column: ColumnNumber::new(string_start.1),
+            });
         }
 
-        return Ok(TokenInfo {
-            line: start_line,
+        if word.is_empty() {
+            return Ok(None);
+        }
+
+        let end_column = start_column + word.chars().count() - 1;
+        let token: Token = word.into();
+
+        match &token {
+            Token::Number(value) => self.check_number(value, start_line, start_column)?,
+            // digits its base does not have, a number all the same and not an identifier
+            Token::Identifier(value) => {
+                if let Some(form) = NumberForm::of_prefix(value) {
+                    return Err(LexerError::MalformedNumber {
+                        value: value.to_owned(),
+                        form,
+                        line: LineNumber::new(start_line),
+                        column: ColumnNumber::new(start_column),
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        let start_column = ColumnNumber::new(start_column);
+        let end_column = ColumnNumber::new(end_column);
+        let metadata = match &token {
+            Token::Literal(value) => Some(TokenMetadata::Literal(LiteralMetadata::new(
+                value,
+                start_column,
+                end_column,
+            ))),
+            Token::Identifier(name) if self.options.suggest_keywords => suggest_keyword(name)
+                .map(|suggestion| TokenMetadata::Identifier(IdentifierMetadata { suggestion })),
+            _ => None,
+        };
+
+        Ok(Some(TokenInfo {
+            line: LineNumber::new(start_line),
             start_column,
-            token: word.into(),
-        });
+            end_column,
+            token,
+            metadata,
+            doc: None,
+        }))
     }
 
     // Implement peek, without going to the next position
     pub fn peek(&mut self) -> Option<&TokenInfo> {
-        if self.peeked.is_some() {
-            return self.peeked.as_ref();
+        if self.peeked.is_none() {
+            self.peeked = Some(self.next());
+        }
+
+        self.peeked.as_ref().and_then(|peeked| peeked.as_ref().ok())
+    }
+
+    // Like peek, but only swallows reaching the end of the file,
+    // so callers can tell an empty remainder apart from a lexing failure
+    pub fn try_peek(&mut self) -> Result<Option<&TokenInfo>, LexerError> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.next());
         }
 
-        match self.next() {
-            Ok(token_info) => {
-                self.peeked = Some(token_info);
+        match self.peeked.take() {
+            Some(Err(LexerError::EndOfFileReached)) => {
+                self.peeked = Some(Err(LexerError::EndOfFileReached));
 
-                self.peeked.as_ref()
+                Ok(None)
+            }
+            Some(Err(error)) => Err(error),
+            peeked => {
+                self.peeked = peeked;
+
+                Ok(self.peeked.as_ref().and_then(|peeked| peeked.as_ref().ok()))
             }
-            _ => None,
         }
     }
 
     // Get back to the state before consuming current token
     pub fn back(&mut self) -> Result<(), LexerError> {
-        match self.cursor.back() {
-            Ok(_) => {
-                self.peeked = None;
-                let (old_line, old_column) = self.checkpoints.pop().unwrap();
-                self.line = old_line;
-                self.column = old_column;
-
-                Ok(())
-            }
-            _ => Err(LexerError::NoCheckpointToGoBackTo),
-        }
+        self.cursor.back()?;
+        self.peeked = None;
+
+        let checkpoint = self.checkpoints.pop_back().unwrap();
+        self.line = checkpoint.line;
+        self.column = checkpoint.column;
+        self.paren_depth = checkpoint.paren_depth;
+        self.ends_statement = checkpoint.ends_statement;
+        self.line_end = checkpoint.line_end;
+        self.dangling_operator = checkpoint.dangling_operator;
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::number::NumberOptions;
+    use rstest::rstest;
 
     macro_rules! assert_token_info {
         ($token:ident, $column:literal, $line:literal, $pattern:pat $(if $guard:expr)? $(,)?) => {
@@ -212,6 +999,16 @@ mod tests {
         };
     }
 
+    fn assert_send<T: Send>() {}
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn it_can_be_sent_between_threads() {
+        assert_send::<Lexer>();
+        assert_send_sync::<TokenInfo>();
+        assert_send_sync::<LexerError>();
+    }
+
     #[test]
     fn it_parses_if_statement() {
         let code = String::from("if (x == y) {");
@@ -237,13 +1034,118 @@ mod tests {
     }
 
     #[test]
-    fn it_can_parse_decrement() {
-        let code = String::from("a--;");
+    fn it_can_parse_index_and_slice_brackets() {
+        let code = String::from("s[i] s[1..n]");
         let mut lexer = Lexer::new(code);
 
-        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
-        assert_token_info!(lexer.next(), 2, 1, Token::Operator(x) if matches!(x, Operator::Decrement));
-        assert_token_info!(lexer.next(), 4, 1, Token::Semi);
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "s");
+        assert_token_info!(lexer.next(), 2, 1, Token::LBracket);
+        assert_token_info!(lexer.next(), 3, 1, Token::Identifier(x) if x == "i");
+        assert_token_info!(lexer.next(), 4, 1, Token::RBracket);
+        assert_token_info!(lexer.next(), 6, 1, Token::Identifier(x) if x == "s");
+        assert_token_info!(lexer.next(), 7, 1, Token::LBracket);
+        assert_token_info!(lexer.next(), 8, 1, Token::Number(x) if x == "1");
+        assert_token_info!(lexer.next(), 9, 1, Token::Operator(Operator::Range));
+        assert_token_info!(lexer.next(), 11, 1, Token::Identifier(x) if x == "n");
+        assert_token_info!(lexer.next(), 12, 1, Token::RBracket);
+    }
+
+    #[test]
+    fn it_ends_a_word_at_an_operator_that_could_be_part_of_it() {
+        let code = String::from("a&&b 1.5");
+        let mut lexer = Lexer::new(code);
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 2, 1, Token::Operator(Operator::And));
+        assert_token_info!(lexer.next(), 4, 1, Token::Identifier(x) if x == "b");
+        assert_token_info!(lexer.next(), 6, 1, Token::Number(x) if x == "1.5");
+    }
+
+    #[test]
+    fn it_lexes_dots_between_names_and_keeps_them_in_floats() {
+        let code = String::from("testing.testing_again 3.14 a.1 0..2");
+        let mut lexer = Lexer::new(code);
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "testing");
+        assert_token_info!(lexer.next(), 8, 1, Token::Dot);
+        assert_token_info!(lexer.next(), 9, 1, Token::Identifier(x) if x == "testing_again");
+        assert_token_info!(lexer.next(), 23, 1, Token::Number(x) if x == "3.14");
+        assert_token_info!(lexer.next(), 28, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 29, 1, Token::Dot);
+        assert_token_info!(lexer.next(), 30, 1, Token::Number(x) if x == "1");
+        assert_token_info!(lexer.next(), 32, 1, Token::Number(x) if x == "0");
+        assert_token_info!(lexer.next(), 33, 1, Token::Operator(Operator::Range));
+        assert_token_info!(lexer.next(), 35, 1, Token::Number(x) if x == "2");
+    }
+
+    #[test]
+    fn it_positions_a_part_of_a_source_at_its_start() {
+        let code = String::from("b(1);\n  c;");
+        let mut lexer = Lexer::new(code).starting_at(LineNumber::new(3), ColumnNumber::new(7));
+
+        lexer.validate_balance().unwrap();
+
+        assert_token_info!(lexer.next(), 7, 3, Token::Identifier(x) if x == "b");
+        assert_token_info!(lexer.next(), 8, 3, Token::Lparen);
+        assert_token_info!(lexer.next(), 9, 3, Token::Number(x) if x == "1");
+        assert_token_info!(lexer.next(), 10, 3, Token::Rparen);
+        assert_token_info!(lexer.next(), 11, 3, Token::Semi);
+        assert_token_info!(lexer.next(), 3, 4, Token::Identifier(x) if x == "c");
+    }
+
+    #[test]
+    fn it_lexes_colons_and_path_separators() {
+        let code = String::from("x: int math::sqrt a:::b");
+        let mut lexer = Lexer::new(code);
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "x");
+        assert_token_info!(lexer.next(), 2, 1, Token::Colon);
+        assert_token_info!(lexer.next(), 4, 1, Token::Type(x) if x == "int");
+        assert_token_info!(lexer.next(), 8, 1, Token::Identifier(x) if x == "math");
+        assert_token_info!(lexer.next(), 12, 1, Token::PathSeparator);
+        assert_token_info!(lexer.next(), 14, 1, Token::Identifier(x) if x == "sqrt");
+        assert_token_info!(lexer.next(), 19, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 20, 1, Token::PathSeparator);
+        assert_token_info!(lexer.next(), 22, 1, Token::Colon);
+        assert_token_info!(lexer.next(), 23, 1, Token::Identifier(x) if x == "b");
+    }
+
+    #[test]
+    fn it_lexes_the_arrow_before_a_return_type() {
+        let code = String::from("f()->int a-1>b");
+        let mut lexer = Lexer::new(code);
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "f");
+        assert_token_info!(lexer.next(), 2, 1, Token::Lparen);
+        assert_token_info!(lexer.next(), 3, 1, Token::Rparen);
+        assert_token_info!(lexer.next(), 4, 1, Token::Arrow);
+        assert_token_info!(lexer.next(), 6, 1, Token::Type(x) if x == "int");
+        assert_token_info!(lexer.next(), 10, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 11, 1, Token::Operator(Operator::Minus));
+        assert_token_info!(lexer.next(), 12, 1, Token::Number(x) if x == "1");
+        assert_token_info!(lexer.next(), 13, 1, Token::Operator(Operator::Greater));
+    }
+
+    #[test]
+    fn it_can_parse_not_and_not_equal() {
+        let code = String::from("a!=!b;");
+        let mut lexer = Lexer::new(code);
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 2, 1, Token::Operator(Operator::NotEqual));
+        assert_token_info!(lexer.next(), 4, 1, Token::Operator(Operator::Not));
+        assert_token_info!(lexer.next(), 5, 1, Token::Identifier(x) if x == "b");
+        assert_token_info!(lexer.next(), 6, 1, Token::Semi);
+    }
+
+    #[test]
+    fn it_can_parse_decrement() {
+        let code = String::from("a--;");
+        let mut lexer = Lexer::new(code);
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 2, 1, Token::Operator(x) if matches!(x, Operator::Decrement));
+        assert_token_info!(lexer.next(), 4, 1, Token::Semi);
     }
 
     #[test]
@@ -270,7 +1172,7 @@ mod tests {
         let code = String::from("string testing = \"Hello there\";");
         let mut lexer = Lexer::new(code);
 
-        assert_token_info!(lexer.next(), 1, 1, Token::Keyword(x) if x == "string");
+        assert_token_info!(lexer.next(), 1, 1, Token::Type(x) if x == "string");
         assert_token_info!(lexer.next(), 8, 1, Token::Identifier(x) if x == "testing");
         assert_token_info!(lexer.next(), 16, 1, Token::Assignment);
         assert_token_info!(lexer.next(), 18, 1, Token::Literal(x) if x == "Hello there");
@@ -282,7 +1184,7 @@ mod tests {
         let code = String::from("int testing = 33;");
         let mut lexer = Lexer::new(code);
 
-        assert_token_info!(lexer.next(), 1, 1, Token::Keyword(x) if x == "int");
+        assert_token_info!(lexer.next(), 1, 1, Token::Type(x) if x == "int");
         assert_token_info!(lexer.next(), 5, 1, Token::Identifier(x) if x == "testing");
         assert_token_info!(lexer.next(), 13, 1, Token::Assignment);
         assert_token_info!(lexer.next(), 15, 1, Token::Number(x) if x == "33");
@@ -294,7 +1196,7 @@ mod tests {
         let code = String::from("bool testing = 5 == 3.33;");
         let mut lexer = Lexer::new(code);
 
-        assert_token_info!(lexer.next(), 1, 1, Token::Keyword(x) if x == "bool");
+        assert_token_info!(lexer.next(), 1, 1, Token::Type(x) if x == "bool");
         assert_token_info!(lexer.next(), 6, 1, Token::Identifier(x) if x == "testing");
         assert_token_info!(lexer.next(), 14, 1, Token::Assignment);
         assert_token_info!(lexer.next(), 16, 1, Token::Number(x) if x == "5");
@@ -327,8 +1229,301 @@ mod tests {
                 LexerError::CannotOpenFile(x) if x == file
             ));
         } else {
-            assert!(false, "We should have failed!");
+            panic!("We should have failed!");
+        }
+    }
+
+    #[test]
+    fn it_accepts_numbers_at_the_edge_of_the_range() {
+        let code = String::from("9223372036854775807 1.5");
+        let mut lexer = Lexer::new(code);
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Number(x) if x == "9223372036854775807");
+        assert_token_info!(lexer.next(), 21, 1, Token::Number(x) if x == "1.5");
+    }
+
+    #[test]
+    fn it_throws_an_error_if_an_integer_is_out_of_range() {
+        let code = String::from("int a = 999999999999999999999999;");
+        let mut lexer = Lexer::new(code);
+
+        lexer.next().unwrap();
+        lexer.next().unwrap();
+        lexer.next().unwrap();
+
+        let error = lexer.next().unwrap_err();
+
+        assert!(matches!(
+            error,
+            LexerError::NumberOutOfRange { value, line, column }
+                if value == "999999999999999999999999" && line == 1 && column == 9
+        ));
+    }
+
+    #[test]
+    fn it_throws_an_error_if_a_float_is_out_of_range() {
+        let code = format!("\n  {}.5", "9".repeat(400));
+        let mut lexer = Lexer::new(code);
+        let error = lexer.next().unwrap_err();
+
+        assert!(matches!(
+            error,
+            LexerError::NumberOutOfRange { line, column, .. } if line == 2 && column == 3
+        ));
+    }
+
+    #[test]
+    fn it_emits_the_last_token_when_the_file_does_not_end_with_a_newline() {
+        let mut lexer = Lexer::new(String::from("int a = 3;\nb"));
+
+        for _ in 0..5 {
+            lexer.next().unwrap();
         }
+
+        assert_token_info!(lexer.next(), 1, 2, Token::Identifier(x) if x == "b");
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    #[test]
+    fn it_emits_the_last_token_when_the_file_ends_with_a_newline() {
+        let mut lexer = Lexer::new(String::from("int a = 3;\n  b\n"));
+
+        for _ in 0..5 {
+            lexer.next().unwrap();
+        }
+
+        assert_token_info!(lexer.next(), 3, 2, Token::Identifier(x) if x == "b");
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    #[test]
+    fn it_emits_the_last_operator_and_literal_at_the_end_of_the_file() {
+        let mut lexer = Lexer::new(String::from("a ==\n\"str\""));
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 3, 1, Token::Operator(Operator::Equal));
+        assert_token_info!(lexer.next(), 1, 2, Token::Literal(x) if x == "str");
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    #[test]
+    fn it_does_not_join_operators_across_lines() {
+        let mut lexer = Lexer::new(String::from("a <\n b =\n= c -\n>"));
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 3, 1, Token::Operator(Operator::Lesser));
+        assert_token_info!(lexer.next(), 2, 2, Token::Identifier(x) if x == "b");
+        assert_token_info!(lexer.next(), 4, 2, Token::Assignment);
+        assert_token_info!(lexer.next(), 1, 3, Token::Assignment);
+        assert_token_info!(lexer.next(), 3, 3, Token::Identifier(x) if x == "c");
+        assert_token_info!(lexer.next(), 5, 3, Token::Operator(Operator::Minus));
+        assert_token_info!(lexer.next(), 1, 4, Token::Operator(Operator::Greater));
+    }
+
+    #[rstest]
+    #[case("a +")]
+    #[case("a +\n\n  ")]
+    fn it_reports_a_binary_operator_at_the_end_of_the_file(#[case] code: &str) {
+        let mut lexer = Lexer::new(code.to_owned());
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(_));
+        assert_token_info!(lexer.next(), 3, 1, Token::Operator(Operator::Plus));
+        assert!(matches!(
+            lexer.next(),
+            Err(LexerError::OperatorAtEndOfFile { operator: Operator::Plus, line, column })
+                if line == 1 && column == 3
+        ));
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    #[test]
+    fn it_reports_the_operator_at_the_end_of_the_file_again_after_going_back() {
+        let mut lexer = Lexer::new(String::from("a ++\nb -"));
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(_));
+        assert_token_info!(lexer.next(), 3, 1, Token::Operator(Operator::Increment));
+        assert_token_info!(lexer.next(), 1, 2, Token::Identifier(_));
+        assert_token_info!(lexer.next(), 3, 2, Token::Operator(Operator::Minus));
+        assert!(lexer.next().is_err());
+        lexer.back().unwrap();
+
+        assert!(matches!(
+            lexer.next(),
+            Err(LexerError::OperatorAtEndOfFile {
+                operator: Operator::Minus,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn it_attaches_doc_comments_to_the_next_token() {
+        let mut lexer = Lexer::new(String::from("/// Adds one\n  ///to a\nfn add /\nb"));
+        let token_info = lexer.next().unwrap();
+
+        assert_token_info!(token_info, 1, 3, Token::Keyword(x) if x == "fn");
+        assert_eq!(token_info.doc.as_deref(), Some("Adds one\nto a"));
+
+        assert!(lexer.next().unwrap().doc.is_none());
+        assert_token_info!(lexer.next(), 8, 3, Token::Operator(Operator::Div));
+        assert_token_info!(lexer.next(), 1, 4, Token::Identifier(x) if x == "b");
+    }
+
+    #[test]
+    fn it_skips_line_comments() {
+        let mut lexer = Lexer::new(String::from(
+            "// int a = 1;\nint b = 2; // the second\n/// kept\nb //\n\"a // b\"",
+        ));
+
+        assert_token_info!(lexer.next(), 1, 2, Token::Type(x) if x == "int");
+        assert_token_info!(lexer.next(), 5, 2, Token::Identifier(x) if x == "b");
+        assert_token_info!(lexer.next(), 7, 2, Token::Assignment);
+        assert_token_info!(lexer.next(), 9, 2, Token::Number(x) if x == "2");
+        assert_token_info!(lexer.next(), 10, 2, Token::Semi);
+
+        let token_info = lexer.next().unwrap();
+
+        assert_token_info!(token_info, 1, 4, Token::Identifier(x) if x == "b");
+        assert_eq!(token_info.doc.as_deref(), Some("kept"));
+        assert_token_info!(lexer.next(), 1, 5, Token::Literal(x) if x == "a // b");
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    #[test]
+    fn it_skips_nested_block_comments_across_lines() {
+        let mut lexer = Lexer::new(String::from(
+            "int /* a */ b /* c\n /* d */ e\n */ = 2 /**/ /*/ f */;\n\"/* g */\"",
+        ));
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Type(x) if x == "int");
+        assert_token_info!(lexer.next(), 13, 1, Token::Identifier(x) if x == "b");
+        assert_token_info!(lexer.next(), 5, 3, Token::Assignment);
+        assert_token_info!(lexer.next(), 7, 3, Token::Number(x) if x == "2");
+        assert_token_info!(lexer.next(), 22, 3, Token::Semi);
+        assert_token_info!(lexer.next(), 1, 4, Token::Literal(x) if x == "/* g */");
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    #[test]
+    fn it_fails_on_block_comments_that_are_not_closed() {
+        let mut lexer = Lexer::new(String::from("a\n  /* b /* c */\nd"));
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+
+        let error = lexer.next().unwrap_err();
+
+        assert_eq!(
+            error.render(),
+            "error[L0013]: Comment opened at 2:3 is never closed!"
+        );
+    }
+
+    #[test]
+    fn it_fails_on_strings_that_are_not_closed_and_lexes_the_next_line() {
+        let mut lexer = Lexer::new(String::from("a;\nx = \"abc\\\";\nb;"));
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 2, 1, Token::Semi);
+        assert_token_info!(lexer.next(), 1, 2, Token::Identifier(x) if x == "x");
+        assert_token_info!(lexer.next(), 3, 2, Token::Assignment);
+
+        let error = lexer.next().unwrap_err();
+
+        assert_eq!(
+            error.render(),
+            "error[L0015]: String opened at 2:5 is never closed!"
+        );
+        assert!(error.is_recoverable());
+        assert_token_info!(lexer.next(), 1, 3, Token::Identifier(x) if x == "b");
+    }
+
+    #[test]
+    fn it_can_skip_many_blank_lines_without_overflowing() {
+        let code = format!("a{}b{}", "\n".repeat(200_000), "\n".repeat(200_000));
+        let mut lexer = Lexer::new(code);
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 1, 200_001, Token::Identifier(x) if x == "b");
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    fn newline_lexer(code: &str) -> Lexer {
+        Lexer::new(code.to_owned()).with_options(LexerOptions {
+            newline_statements: true,
+            ..LexerOptions::default()
+        })
+    }
+
+    #[test]
+    fn it_does_not_emit_a_virtual_semi_after_going_back() {
+        let mut lexer = newline_lexer("a\nb");
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(_));
+        lexer.back().unwrap();
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(_));
+        assert_token_info!(lexer.next(), 2, 1, Token::Semi);
+        assert_token_info!(lexer.next(), 1, 2, Token::Identifier(_));
+        lexer.back().unwrap();
+
+        assert_token_info!(lexer.next(), 1, 2, Token::Identifier(_));
+        assert_token_info!(lexer.next(), 2, 2, Token::Semi);
+    }
+
+    #[test]
+    fn it_does_not_emit_virtual_semis_by_default() {
+        let mut lexer = Lexer::new(String::from("a\nb"));
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 1, 2, Token::Identifier(x) if x == "b");
+    }
+
+    #[test]
+    fn it_emits_virtual_semis_at_the_end_of_statement_lines() {
+        let mut lexer = newline_lexer("int a = 3\nSystem.print(a)\n");
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Type(x) if x == "int");
+        assert_token_info!(lexer.next(), 5, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 7, 1, Token::Assignment);
+        assert_token_info!(lexer.next(), 9, 1, Token::Number(x) if x == "3");
+        assert_token_info!(lexer.next(), 10, 1, Token::Semi);
+        assert_token_info!(lexer.next(), 1, 2, Token::Identifier(x) if x == "System");
+        assert_token_info!(lexer.next(), 7, 2, Token::Dot);
+        assert_token_info!(lexer.next(), 8, 2, Token::Identifier(x) if x == "print");
+        assert_token_info!(lexer.next(), 13, 2, Token::Lparen);
+        assert_token_info!(lexer.next(), 14, 2, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 15, 2, Token::Rparen);
+        assert_token_info!(lexer.next(), 16, 2, Token::Semi);
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    #[test]
+    fn it_emits_a_virtual_semi_at_the_end_of_the_file() {
+        let mut lexer = newline_lexer("a++");
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 2, 1, Token::Operator(Operator::Increment));
+        assert_token_info!(lexer.next(), 4, 1, Token::Semi);
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    #[test]
+    fn it_does_not_duplicate_explicit_semis_or_break_open_statements() {
+        let mut lexer = newline_lexer("a;\nb =\n sum(a\n + b) {\n}\n");
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 2, 1, Token::Semi);
+        assert_token_info!(lexer.next(), 1, 2, Token::Identifier(x) if x == "b");
+        assert_token_info!(lexer.next(), 3, 2, Token::Assignment);
+        assert_token_info!(lexer.next(), 2, 3, Token::Identifier(x) if x == "sum");
+        assert_token_info!(lexer.next(), 5, 3, Token::Lparen);
+        assert_token_info!(lexer.next(), 6, 3, Token::Identifier(x) if x == "a");
+        assert_token_info!(lexer.next(), 2, 4, Token::Operator(Operator::Plus));
+        assert_token_info!(lexer.next(), 4, 4, Token::Identifier(x) if x == "b");
+        assert_token_info!(lexer.next(), 5, 4, Token::Rparen);
+        assert_token_info!(lexer.next(), 7, 4, Token::LCurly);
+        assert_token_info!(lexer.next(), 1, 5, Token::RCurly);
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
     }
 
     #[test]
@@ -374,6 +1569,354 @@ mod tests {
         assert_token_info!(lexer.peek(), 4, 1, Token::Lparen);
     }
 
+    #[test]
+    fn it_returns_none_when_trying_to_peek_at_the_end_of_the_file() {
+        let mut lexer = Lexer::new(String::from("  \n  "));
+
+        assert!(lexer.try_peek().unwrap().is_none());
+    }
+
+    #[test]
+    fn it_returns_lexer_errors_when_trying_to_peek() {
+        let mut lexer = Lexer::new(String::from("999999999999999999999999"));
+
+        assert!(matches!(
+            lexer.try_peek(),
+            Err(LexerError::NumberOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn it_tracks_the_last_column_of_each_token() {
+        let mut lexer = Lexer::new(String::from("sum == \"a b\";"));
+        let end_columns: Vec<usize> = (0..4)
+            .map(|_| lexer.next().unwrap().end_column.get())
+            .collect();
+
+        assert_eq!(end_columns, vec![3, 6, 12, 13]);
+    }
+
+    #[test]
+    fn it_attaches_metadata_to_literals() {
+        let mut lexer = Lexer::new(String::from("a = \"hello \\n\";"));
+
+        assert_eq!(lexer.next().unwrap().metadata, None);
+        assert_eq!(lexer.next().unwrap().metadata, None);
+        assert_eq!(
+            lexer.next().unwrap().metadata,
+            Some(TokenMetadata::Literal(LiteralMetadata {
+                byte_length: 8,
+                has_escapes: true,
+                raw_start: ColumnNumber::new(5),
+                raw_end: ColumnNumber::new(14),
+            }))
+        );
+    }
+
+    #[rstest]
+    #[case("if (a) {\n  b(c[1]);\n}", None)]
+    #[case(
+        "b(c[1);",
+        Some("error[L0010]: Bracket `)` at 1:6 does not close `[` opened at 1:4!")
+    )]
+    #[case(
+        "fn a() {\n  if (b) {\n}",
+        Some("error[L0011]: Bracket `{` opened at 1:8 is never closed!")
+    )]
+    #[case(
+        "a = 1;\n}",
+        Some("error[L0012]: Bracket `}` at 2:1 has nothing to close!")
+    )]
+    fn it_validates_the_balance_of_brackets(#[case] code: &str, #[case] error: Option<&str>) {
+        let mut lexer = Lexer::new(code.to_owned());
+
+        assert_eq!(
+            lexer.validate_balance().err().map(|error| error.render()),
+            error.map(str::to_owned)
+        );
+    }
+
+    #[test]
+    fn it_lexes_from_the_start_after_validating_the_balance() {
+        let mut lexer = Lexer::new(String::from("a(b);\n(c"));
+
+        assert!(lexer.validate_balance().is_err());
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+    }
+
+    #[test]
+    fn it_leaves_other_lexer_errors_to_the_parser_when_validating_the_balance() {
+        let mut lexer = Lexer::new(String::from("a = 99999999999999999999;\n("));
+
+        assert!(lexer.validate_balance().is_ok());
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "a");
+    }
+
+    #[test]
+    fn it_tokenizes_past_errors_about_the_source() {
+        let code = String::from("int a = 99999999999999999999;\nint b = 0b12 + 2;\nc = 1 +");
+        let (tokens, errors) = Lexer::new(code).tokenize_with_errors();
+        let lexemes: Vec<String> = tokens.iter().map(|info| info.token.lexeme()).collect();
+
+        assert_eq!(
+            lexemes,
+            ["int", "a", "=", ";", "int", "b", "=", "+", "2", ";", "c", "=", "1", "+"]
+        );
+        assert!(matches!(
+            &errors[..],
+            [
+                LexerError::NumberOutOfRange { .. },
+                LexerError::MalformedNumber { .. },
+                LexerError::OperatorAtEndOfFile { .. },
+            ]
+        ));
+    }
+
+    #[test]
+    fn it_skips_the_rest_of_a_line_that_is_too_long() {
+        let mut lexer =
+            Lexer::new(String::from("int abcdefghij = 1;\nint b;")).with_options(LexerOptions {
+                max_line_length: Some(8),
+                ..LexerOptions::default()
+            });
+        let (tokens, errors) = lexer.tokenize_with_errors();
+
+        let second_line = &tokens[1];
+
+        assert_eq!(tokens.len(), 4);
+        assert_token_info!(second_line, 1, 2, Token::Type(x) if x == "int");
+        assert!(matches!(&errors[..], [LexerError::LineTooLong { .. }]));
+    }
+
+    #[test]
+    fn it_suggests_keywords_for_near_miss_identifiers_when_enabled() {
+        let code = String::from("whle total");
+        let suggestion = |info: TokenInfo| match info.metadata {
+            Some(TokenMetadata::Identifier(metadata)) => Some(metadata.suggestion),
+            _ => None,
+        };
+        let mut lexer = Lexer::new(code.clone()).with_options(LexerOptions {
+            suggest_keywords: true,
+            ..LexerOptions::default()
+        });
+
+        assert_eq!(suggestion(lexer.next().unwrap()), Some("while"));
+        assert_eq!(suggestion(lexer.next().unwrap()), None);
+        assert_eq!(suggestion(Lexer::new(code).next().unwrap()), None);
+    }
+
+    #[test]
+    fn it_rejects_floats_in_integer_only_mode() {
+        let mut lexer = Lexer::new(String::from("a = 1;\nb = 1.5;")).with_options(LexerOptions {
+            numbers: NumberOptions::integer_only(),
+            ..LexerOptions::default()
+        });
+
+        for _ in 0..6 {
+            lexer.next().unwrap();
+        }
+
+        assert!(matches!(
+            lexer.next(),
+            Err(LexerError::DisabledNumberForm { form: NumberForm::Float, line, column, .. })
+                if line == 2 && column == 5
+        ));
+    }
+
+    #[test]
+    fn it_lexes_hex_octal_and_binary_numbers_by_default() {
+        let code = String::from("0x1F 0b101 0o17 0x8000000000000000");
+        let mut lexer = Lexer::new(code.clone());
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Number(x) if x == "0x1F");
+        assert_token_info!(lexer.next(), 6, 1, Token::Number(x) if x == "0b101");
+        assert_token_info!(lexer.next(), 12, 1, Token::Number(x) if x == "0o17");
+        assert!(matches!(
+            lexer.next(),
+            Err(LexerError::NumberOutOfRange { .. })
+        ));
+
+        let mut lexer = Lexer::new(code).with_options(LexerOptions {
+            numbers: NumberOptions {
+                hex: false,
+                ..NumberOptions::default()
+            },
+            ..LexerOptions::default()
+        });
+
+        assert!(matches!(
+            lexer.next(),
+            Err(LexerError::DisabledNumberForm {
+                form: NumberForm::Hex,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn it_fails_on_prefixed_numbers_with_digits_of_another_base() {
+        let mut lexer = Lexer::new(String::from(
+            "a = 0b102;
+b = 0x;",
+        ));
+
+        lexer.next().unwrap();
+        lexer.next().unwrap();
+
+        assert_eq!(
+            lexer.next().unwrap_err().render(),
+            "error[L0016]: Binary number 0b102 is malformed at 1:5!"
+        );
+        assert_token_info!(lexer.next(), 10, 1, Token::Semi);
+
+        lexer.next().unwrap();
+        lexer.next().unwrap();
+
+        assert!(matches!(
+            lexer.next(),
+            Err(LexerError::MalformedNumber { form: NumberForm::Hex, line, column, .. })
+                if line == 2 && column == 5
+        ));
+    }
+
+    #[test]
+    fn it_strips_digit_separators_from_numbers() {
+        let mut lexer = Lexer::new(String::from("a = 1_000_000 + 1_0.5;"));
+
+        lexer.next().unwrap();
+        lexer.next().unwrap();
+
+        assert_token_info!(lexer.next(), 5, 1, Token::Number(x) if x == "1000000");
+        assert_token_info!(lexer.next(), 15, 1, Token::Operator(Operator::Plus));
+        assert_token_info!(lexer.next(), 17, 1, Token::Number(x) if x == "10.5");
+        assert_token_info!(lexer.next(), 22, 1, Token::Semi);
+    }
+
+    #[test]
+    fn it_keeps_columns_on_a_very_long_line() {
+        let code = format!("{}{} = 1;", " ".repeat(100_000), "a".repeat(100_000));
+        let mut lexer = Lexer::new(code);
+
+        assert_token_info!(lexer.next(), 100_001, 1, Token::Identifier(name) if name.len() == 100_000);
+        assert_token_info!(lexer.next(), 200_002, 1, Token::Assignment);
+        assert_token_info!(lexer.next(), 200_004, 1, Token::Number(_));
+        assert_token_info!(lexer.next(), 200_005, 1, Token::Semi);
+    }
+
+    #[test]
+    fn it_fails_on_lines_longer_than_the_max_line_length() {
+        let code = String::from("a = 1;\nsum = 1 + 2 + 3;");
+        let mut lexer = Lexer::new(code).with_options(LexerOptions {
+            max_line_length: Some(10),
+            ..LexerOptions::default()
+        });
+
+        // the first 8 tokens fit, the `2` after them is the 11th character of line 2
+        for _ in 0..8 {
+            lexer.next().unwrap();
+        }
+
+        assert!(matches!(
+            lexer.next(),
+            Err(LexerError::LineTooLong { line, max: 10 }) if line == 2
+        ));
+    }
+
+    #[test]
+    fn it_checks_the_max_line_length_on_two_character_operators() {
+        let mut lexer = Lexer::new(String::from("ab ++")).with_options(LexerOptions {
+            max_line_length: Some(4),
+            ..LexerOptions::default()
+        });
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(_));
+        assert!(matches!(
+            lexer.next(),
+            Err(LexerError::LineTooLong { line, max: 4 }) if line == 1
+        ));
+    }
+
+    #[test]
+    fn it_keeps_lexer_errors_hit_while_peeking() {
+        let mut lexer = Lexer::new(String::from("999999999999999999999999;"));
+
+        assert!(lexer.peek().is_none());
+        assert!(lexer.peek().is_none());
+        assert!(matches!(
+            lexer.next(),
+            Err(LexerError::NumberOutOfRange { .. })
+        ));
+        assert_token_info!(lexer.next(), 25, 1, Token::Semi);
+    }
+
+    #[test]
+    fn it_can_try_to_peek_the_next_token() {
+        let mut lexer = Lexer::new(String::from("sum"));
+
+        assert_token_info!(lexer.try_peek().unwrap(), 1, 1, Token::Identifier(x) if x == "sum");
+        assert_token_info!(lexer.next(), 1, 1, Token::Identifier(x) if x == "sum");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn it_can_load_the_source_from_an_async_reader() {
+        let reader = futures::io::Cursor::new(b"if\nwhile".to_vec());
+        let mut lexer = futures::executor::block_on(Lexer::from_async_reader(reader)).unwrap();
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Keyword(x) if x == "if");
+        assert_token_info!(lexer.next(), 1, 2, Token::Keyword(x) if x == "while");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn it_fails_to_load_invalid_utf8_from_an_async_reader() {
+        let reader = futures::io::Cursor::new(vec![0xff, 0xfe]);
+        let result = futures::executor::block_on(Lexer::from_async_reader(reader));
+
+        assert!(matches!(result, Err(LexerError::FailedToReadNextLine(_))));
+    }
+
+    #[test]
+    fn it_can_be_reset_to_a_new_source() {
+        let mut lexer = Lexer::new(String::from("sum(a + b);\nint c = 3;"));
+
+        lexer.next().unwrap();
+        lexer.next().unwrap();
+        lexer.peek().unwrap();
+
+        lexer.reset(String::from("if\nwhile"));
+
+        assert_token_info!(lexer.next(), 1, 1, Token::Keyword(x) if x == "if");
+        assert_token_info!(lexer.next(), 1, 2, Token::Keyword(x) if x == "while");
+        assert!(matches!(lexer.next(), Err(LexerError::EndOfFileReached)));
+    }
+
+    #[test]
+    fn it_keeps_a_bounded_number_of_checkpoints() {
+        let code = "a ".repeat(DEFAULT_MAX_CHECKPOINT_DEPTH * 4);
+        let mut lexer = Lexer::new(code);
+
+        for _ in 0..DEFAULT_MAX_CHECKPOINT_DEPTH * 4 {
+            lexer.next().unwrap();
+        }
+
+        assert_eq!(lexer.checkpoints.len(), DEFAULT_MAX_CHECKPOINT_DEPTH);
+
+        lexer.back().unwrap();
+
+        assert_token_info!(lexer.next(), 511, 1, Token::Identifier(x) if x == "a");
+    }
+
+    #[test]
+    fn it_throws_an_error_if_there_is_no_checkpoint_to_go_back_to() {
+        let mut lexer = Lexer::new(String::from("sum"));
+
+        assert!(matches!(
+            lexer.back(),
+            Err(LexerError::NoCheckpointToGoBackTo)
+        ));
+    }
+
     #[test]
     fn it_can_get_back_to_before_consuming_the_current_token() {
         let code = String::from("sum(a + b);");
@@ -1,4 +1,10 @@
-mod buffer;
+pub mod buffer;
+pub mod diagnostic;
 pub mod lexer;
+pub mod metadata;
+pub mod number;
 pub mod operator;
+pub mod options;
+pub mod position;
+pub mod suggestion;
 pub mod token;
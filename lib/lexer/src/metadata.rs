@@ -0,0 +1,37 @@
+use crate::position::ColumnNumber;
+
+// Facts the lexer already knows about a token, so later passes
+// (constant pool, formatters) do not have to rescan the source.
+// New token kinds get their own variant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenMetadata {
+    Literal(LiteralMetadata),
+    // only with LexerOptions::suggest_keywords, for identifiers close to a keyword
+    Identifier(IdentifierMetadata),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiteralMetadata {
+    pub byte_length: usize, // of the value, without the quotes
+    pub has_escapes: bool,
+    // columns of the opening and the closing quote
+    pub raw_start: ColumnNumber,
+    pub raw_end: ColumnNumber,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentifierMetadata {
+    // the keyword the identifier is likely a typo of, e.g. `while` for `whle`
+    pub suggestion: &'static str,
+}
+
+impl LiteralMetadata {
+    pub fn new(value: &str, raw_start: ColumnNumber, raw_end: ColumnNumber) -> Self {
+        Self {
+            byte_length: value.len(),
+            has_escapes: value.contains('\\'),
+            raw_start,
+            raw_end,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// The shapes a number literal can have, the lexer decides which of them are enabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "strum", derive(strum::Display))]
+pub enum NumberForm {
+    Integer,
+    Float,
+    Hex,
+    Octal,
+    Binary,
+}
+
+// Shown by the name of the variant, like the strum derive
+#[cfg(not(feature = "strum"))]
+impl std::fmt::Display for NumberForm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl NumberForm {
+    // Recognises the form of a number literal, None if the word is not a number.
+    // Digits can be separated by _, like in 1_000_000, but a part can not start with one
+    pub fn of(word: &str) -> Option<Self> {
+        let is_digits = |part: &str, is_digit: fn(&char) -> bool| {
+            part.chars().next().as_ref().is_some_and(is_digit)
+                && part.chars().all(|c| c == '_' || is_digit(&c))
+        };
+
+        if let Some(digits) = word.strip_prefix("0x") {
+            return is_digits(digits, char::is_ascii_hexdigit).then_some(Self::Hex);
+        }
+
+        if let Some(digits) = word.strip_prefix("0o") {
+            return is_digits(digits, |c| ('0'..='7').contains(c)).then_some(Self::Octal);
+        }
+
+        if let Some(digits) = word.strip_prefix("0b") {
+            return is_digits(digits, |c| matches!(c, '0' | '1')).then_some(Self::Binary);
+        }
+
+        let is_digits = |part: &str| is_digits(part, char::is_ascii_digit);
+
+        match word.split_once('.') {
+            None if is_digits(word) => Some(Self::Integer),
+            Some((whole, fraction)) if is_digits(whole) && is_digits(fraction) => Some(Self::Float),
+            _ => None,
+        }
+    }
+
+    // The form a 0x, 0o or 0b prefix asks for, also when the digits after it do not
+    // have that form, like in 0b102
+    pub fn of_prefix(word: &str) -> Option<Self> {
+        match word.get(..2) {
+            Some("0x") => Some(Self::Hex),
+            Some("0o") => Some(Self::Octal),
+            Some("0b") => Some(Self::Binary),
+            _ => None,
+        }
+    }
+
+    // The base the digits are written in, 10 for floats too
+    pub fn radix(&self) -> u32 {
+        match self {
+            Self::Integer | Self::Float => 10,
+            Self::Hex => 16,
+            Self::Octal => 8,
+            Self::Binary => 2,
+        }
+    }
+
+    // The value of an integer form, None for floats and integers that do not fit into an i64
+    pub fn value(&self, word: &str) -> Option<i64> {
+        let word = word.replace('_', "");
+
+        match self {
+            Self::Float => None,
+            Self::Integer => word.parse().ok(),
+            // past the 0x, 0o or 0b
+            form => i64::from_str_radix(&word[2..], form.radix()).ok(),
+        }
+    }
+
+    // Integer forms have to fit into an i64 and floats into a finite f64
+    pub fn fits(&self, word: &str) -> bool {
+        match self {
+            Self::Float => word
+                .replace('_', "")
+                .parse::<f64>()
+                .is_ok_and(f64::is_finite),
+            form => form.value(word).is_some(),
+        }
+    }
+}
+
+// Which number forms the lexer accepts, by default all of them.
+// Disabled forms are still recognised, so they get a diagnostic instead of becoming identifiers
+#[derive(Debug, Clone)]
+pub struct NumberOptions {
+    pub floats: bool,
+    pub hex: bool,
+    pub octal: bool,
+    pub binary: bool,
+}
+
+impl Default for NumberOptions {
+    fn default() -> Self {
+        Self {
+            floats: true,
+            hex: true,
+            octal: true,
+            binary: true,
+        }
+    }
+}
+
+impl NumberOptions {
+    pub fn integer_only() -> Self {
+        Self {
+            floats: false,
+            hex: false,
+            octal: false,
+            binary: false,
+        }
+    }
+
+    pub fn allows(&self, form: NumberForm) -> bool {
+        match form {
+            NumberForm::Integer => true,
+            NumberForm::Float => self.floats,
+            NumberForm::Hex => self.hex,
+            NumberForm::Octal => self.octal,
+            NumberForm::Binary => self.binary,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("19", Some(NumberForm::Integer))]
+    #[case("19.5", Some(NumberForm::Float))]
+    #[case("0x1F", Some(NumberForm::Hex))]
+    #[case("0o17", Some(NumberForm::Octal))]
+    #[case("0b101", Some(NumberForm::Binary))]
+    #[case("19.", None)]
+    #[case(".5", None)]
+    #[case("1.2.3", None)]
+    #[case("0x", None)]
+    #[case("0o18", None)]
+    #[case("1_000_000", Some(NumberForm::Integer))]
+    #[case("1_000.000_1", Some(NumberForm::Float))]
+    #[case("0xFF_FF", Some(NumberForm::Hex))]
+    #[case("0b1010_", Some(NumberForm::Binary))]
+    #[case("1._5", None)]
+    #[case("0x_FF", None)]
+    #[case("_1", None)]
+    #[case("0b102", None)]
+    #[case("abc", None)]
+    fn it_recognises_number_forms(#[case] word: &str, #[case] form: Option<NumberForm>) {
+        assert_eq!(NumberForm::of(word), form);
+    }
+
+    #[rstest]
+    #[case(NumberForm::Integer, "9223372036854775807", true)]
+    #[case(NumberForm::Integer, "9223372036854775808", false)]
+    #[case(NumberForm::Hex, "0x7FFFFFFFFFFFFFFF", true)]
+    #[case(NumberForm::Hex, "0x8000000000000000", false)]
+    #[case(NumberForm::Octal, "0o777777777777777777777", true)]
+    #[case(NumberForm::Octal, "0o1000000000000000000000", false)]
+    #[case(NumberForm::Binary, "0b11", true)]
+    fn it_checks_the_range_of_each_form(
+        #[case] form: NumberForm,
+        #[case] word: &str,
+        #[case] fits: bool,
+    ) {
+        assert_eq!(form.fits(word), fits);
+    }
+
+    #[rstest]
+    #[case("255", NumberForm::Integer, 10)]
+    #[case("0xFF", NumberForm::Hex, 16)]
+    #[case("0o377", NumberForm::Octal, 8)]
+    #[case("0b11111111", NumberForm::Binary, 2)]
+    #[case("2_5_5", NumberForm::Integer, 10)]
+    #[case("0xF_F", NumberForm::Hex, 16)]
+    fn it_reads_integers_in_their_base(
+        #[case] word: &str,
+        #[case] form: NumberForm,
+        #[case] radix: u32,
+    ) {
+        assert_eq!(form.radix(), radix);
+        assert_eq!(form.value(word), Some(255));
+    }
+
+    #[rstest]
+    #[case("0x", Some(NumberForm::Hex))]
+    #[case("0o18", Some(NumberForm::Octal))]
+    #[case("0b102", Some(NumberForm::Binary))]
+    #[case("0b", Some(NumberForm::Binary))]
+    #[case("0", None)]
+    #[case("10x", None)]
+    fn it_recognises_the_form_a_prefix_asks_for(
+        #[case] word: &str,
+        #[case] form: Option<NumberForm>,
+    ) {
+        assert_eq!(NumberForm::of_prefix(word), form);
+    }
+
+    #[test]
+    fn it_only_allows_integers_in_integer_only_mode() {
+        let options = NumberOptions::integer_only();
+
+        assert!(options.allows(NumberForm::Integer));
+        assert!(!options.allows(NumberForm::Float));
+        assert!(!options.allows(NumberForm::Hex));
+    }
+}
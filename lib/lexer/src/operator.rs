@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
-#[derive(PartialEq, Eq, Debug, Hash)]
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "strum", derive(strum::EnumIter))]
 pub enum Operator {
     Plus,
     Or,
@@ -9,25 +10,95 @@ pub enum Operator {
     Mul,
     Div,
     Equal,
+    NotEqual,
+    Not,
     Lesser,
     LesserEqual,
     Greater,
     GreaterEqual,
     Increment,
     Decrement,
-    Pointer,
+    // a..b, the characters from a up to b in a slice
+    Range,
 }
 
 impl Operator {
     pub fn is_operator(op: &str) -> bool {
-        match op {
-            "+" | "-" | "/" | "*" | "==" | "<" | "<=" | ">" | ">=" | "&&" | "||" | "++" | "--"
-            | "->" => true,
-            _ => false,
+        Self::parse(op).is_some()
+    }
+
+    // Matched on the characters, so the lexer can check every character without allocating
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(Self::Plus),
+            '-' => Some(Self::Minus),
+            '/' => Some(Self::Div),
+            '*' => Some(Self::Mul),
+            '<' => Some(Self::Lesser),
+            '>' => Some(Self::Greater),
+            '!' => Some(Self::Not),
+            _ => None,
+        }
+    }
+
+    pub fn from_pair(first: char, second: char) -> Option<Self> {
+        match [first, second] {
+            ['=', '='] => Some(Self::Equal),
+            ['!', '='] => Some(Self::NotEqual),
+            ['<', '='] => Some(Self::LesserEqual),
+            ['>', '='] => Some(Self::GreaterEqual),
+            ['&', '&'] => Some(Self::And),
+            ['|', '|'] => Some(Self::Or),
+            ['+', '+'] => Some(Self::Increment),
+            ['-', '-'] => Some(Self::Decrement),
+            ['.', '.'] => Some(Self::Range),
+            _ => None,
+        }
+    }
+
+    // Operators that go between two operands. ++ and -- come after their operand and
+    // ! before it, a - can be either
+    pub fn is_binary(&self) -> bool {
+        !matches!(self, Self::Increment | Self::Decrement | Self::Not)
+    }
+
+    fn parse(op: &str) -> Option<Self> {
+        let mut chars = op.chars();
+
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(c), None, _) => Self::from_char(c),
+            (Some(first), Some(second), None) => Self::from_pair(first, second),
+            _ => None,
         }
     }
 }
 
+// The operators in declaration order, like the strum derive iterates them
+#[cfg(not(feature = "strum"))]
+impl Operator {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            Self::Plus,
+            Self::Or,
+            Self::And,
+            Self::Minus,
+            Self::Mul,
+            Self::Div,
+            Self::Equal,
+            Self::NotEqual,
+            Self::Not,
+            Self::Lesser,
+            Self::LesserEqual,
+            Self::Greater,
+            Self::GreaterEqual,
+            Self::Increment,
+            Self::Decrement,
+            Self::Range,
+        ]
+        .into_iter()
+    }
+}
+
 impl Display for Operator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let to_display = match self {
@@ -38,13 +109,15 @@ impl Display for Operator {
             Self::Mul => "*".to_owned(),
             Self::Div => "/".to_owned(),
             Self::Equal => "==".to_owned(),
+            Self::NotEqual => "!=".to_owned(),
+            Self::Not => "!".to_owned(),
             Self::Lesser => "<".to_owned(),
             Self::LesserEqual => "<=".to_owned(),
             Self::Greater => ">".to_owned(),
             Self::GreaterEqual => ">=".to_owned(),
             Self::Increment => "++".to_owned(),
             Self::Decrement => "--".to_owned(),
-            Self::Pointer => "->".to_owned(),
+            Self::Range => "..".to_owned(),
         };
 
         write!(f, "{}", to_display)
@@ -53,25 +126,7 @@ impl Display for Operator {
 
 impl From<&str> for Operator {
     fn from(word: &str) -> Self {
-        match word {
-            "==" => Self::Equal,
-            "<=" => Self::LesserEqual,
-            ">=" => Self::GreaterEqual,
-            "&&" => Self::And,
-            "||" => Self::Or,
-            "++" => Self::Increment,
-            "--" => Self::Decrement,
-            "->" => Self::Pointer,
-            word => match word.chars().next().unwrap_or(' ') {
-                '+' => Self::Plus,
-                '-' => Self::Minus,
-                '/' => Self::Div,
-                '*' => Self::Mul,
-                '>' => Self::Greater,
-                '<' => Self::Lesser,
-                _ => panic!("Please no!"),
-            },
-        }
+        Self::parse(word).expect("Please no!")
     }
 }
 
@@ -80,3 +135,31 @@ impl From<String> for Operator {
         word.as_str().into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case('=', '=', Some(Operator::Equal))]
+    #[case('.', '.', Some(Operator::Range))]
+    #[case('-', '>', None)]
+    #[case('!', '=', Some(Operator::NotEqual))]
+    #[case('+', '+', Some(Operator::Increment))]
+    #[case('+', '=', None)]
+    #[case('=', ' ', None)]
+    fn it_matches_two_character_operators(
+        #[case] first: char,
+        #[case] second: char,
+        #[case] operator: Option<Operator>,
+    ) {
+        assert_eq!(Operator::from_pair(first, second), operator);
+    }
+
+    #[test]
+    fn it_rejects_longer_words() {
+        assert!(!Operator::is_operator("+++"));
+        assert!(!Operator::is_operator(""));
+    }
+}
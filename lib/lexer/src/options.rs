@@ -0,0 +1,18 @@
+use crate::number::NumberOptions;
+
+#[derive(Debug, Clone, Default)]
+pub struct LexerOptions {
+    // Emit a virtual Semi at the end of a line whose last token can end a statement
+    // (identifier, literal, number, boolean, `)`, `++` or `--`), outside of parentheses.
+    // As in Go, an opening curly then has to stay on the line it belongs to.
+    pub newline_statements: bool,
+    // Fail with LineTooLong once a line grows past this many characters, e.g. to
+    // reject minified input early. A line is never buffered as a whole, so this is
+    // a guard against bad input rather than a memory limit
+    pub max_line_length: Option<usize>,
+    // Which number literal forms are enabled, e.g. integer-only for an intro course
+    pub numbers: NumberOptions,
+    // Attach the keyword an identifier is likely a typo of as its metadata, see
+    // suggestion::suggest_keyword. The parser points at it when the statement fails
+    pub suggest_keywords: bool,
+}
@@ -0,0 +1,95 @@
+use std::fmt::{Debug, Display};
+
+// Lines and columns are 1-based everywhere: the first character of a source is at 1:1.
+// Raw counters that start at 0 have to go through from_zero_based
+macro_rules! one_based_number {
+    ($name:ident, $what:literal) => {
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(usize);
+
+        impl $name {
+            pub const FIRST: Self = Self(1);
+
+            pub fn new(value: usize) -> Self {
+                assert!(value > 0, concat!($what, " numbers start at 1!"));
+
+                Self(value)
+            }
+
+            pub fn from_zero_based(value: usize) -> Self {
+                Self(value + 1)
+            }
+
+            pub fn get(&self) -> usize {
+                self.0
+            }
+
+            pub fn zero_based(&self) -> usize {
+                self.0 - 1
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::FIRST
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        // Printed as the plain number, so positions stay readable in debug output
+        impl Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl PartialEq<usize> for $name {
+            fn eq(&self, other: &usize) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<$name> for usize {
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.0
+            }
+        }
+    };
+}
+
+one_based_number!(LineNumber, "Line");
+one_based_number!(ColumnNumber, "Column");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_between_zero_and_one_based_values() {
+        let line = LineNumber::from_zero_based(0);
+
+        assert_eq!(line, LineNumber::FIRST);
+        assert_eq!(line.get(), 1);
+        assert_eq!(line.zero_based(), 0);
+        assert_eq!(ColumnNumber::new(7).zero_based(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "Column numbers start at 1!")]
+    fn it_rejects_a_zero_column() {
+        ColumnNumber::new(0);
+    }
+
+    #[test]
+    fn it_prints_the_plain_number() {
+        assert_eq!(
+            format!("{} {:?}", LineNumber::new(3), ColumnNumber::new(4)),
+            "3 4"
+        );
+    }
+}
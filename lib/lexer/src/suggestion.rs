@@ -0,0 +1,90 @@
+use crate::token::KEYWORDS;
+
+// Shorter words are too close to too many keywords for a suggestion to help, `i` is one edit from `if`
+const MIN_WORD_LENGTH: usize = 3;
+
+// How many single character insertions, deletions, substitutions or swaps of
+// neighbouring characters turn one word into the other
+pub fn edit_distance(from: &str, to: &str) -> usize {
+    let from: Vec<char> = from.chars().collect();
+    let to: Vec<char> = to.chars().collect();
+    let mut distances = vec![vec![0; to.len() + 1]; from.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=from.len() {
+        for j in 1..=to.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(from[i - 1] != to[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+
+            if i > 1 && j > 1 && from[i - 1] == to[j - 2] && from[i - 2] == to[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[from.len()][to.len()]
+}
+
+// The keyword an identifier is most likely a typo of, like `while` for `whle`.
+// Short words get one edit, longer ones two
+pub fn suggest_keyword(word: &str) -> Option<&'static str> {
+    let length = word.chars().count();
+
+    if length < MIN_WORD_LENGTH {
+        return None;
+    }
+
+    let max_distance = if length <= 4 { 1 } else { 2 };
+
+    KEYWORDS
+        .iter()
+        .map(|keyword| (edit_distance(word, keyword), *keyword))
+        .filter(|(distance, _)| (1..=max_distance).contains(distance))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, keyword)| keyword)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("while", "while", 0)]
+    #[case("whle", "while", 1)]
+    #[case("retrun", "return", 1)]
+    #[case("rtrn", "return", 2)]
+    #[case("", "for", 3)]
+    #[case("kitten", "sitting", 3)]
+    fn it_counts_the_edits_between_words(
+        #[case] from: &str,
+        #[case] to: &str,
+        #[case] distance: usize,
+    ) {
+        assert_eq!(edit_distance(from, to), distance);
+    }
+
+    #[rstest]
+    #[case("whle", Some("while"))]
+    #[case("retrun", Some("return"))]
+    #[case("esle", Some("else"))]
+    #[case("retun", Some("return"))]
+    #[case("whiile", Some("while"))]
+    #[case("fo", None)]
+    #[case("i", None)]
+    #[case("total", None)]
+    fn it_suggests_close_keywords(#[case] word: &str, #[case] suggestion: Option<&str>) {
+        assert_eq!(suggest_keyword(word), suggestion);
+    }
+}
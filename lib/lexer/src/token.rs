@@ -1,17 +1,24 @@
+use crate::number::NumberForm;
 use crate::operator::Operator;
+#[cfg(feature = "regex")]
 use regex::Regex;
 use std::fmt::Display;
-use strum::Display as StrumDisplay;
 
-pub const KEYWORDS: &'static [&str] = &[
-    "if", "elif", "else", "while", "for", "return", "continue", "break", "int", "bool", "string",
-    "char", "float", "fn"
+pub const KEYWORDS: &[&str] = &[
+    "if", "elif", "else", "while", "for", "return", "continue", "break", "fn", "pure",
 ];
 
-#[derive(PartialEq, Eq, Debug, StrumDisplay, Hash, Clone)]
+// Primitive types are not keywords, so the parser can require a type where one is expected
+pub const TYPES: &[&str] = &["int", "bool", "string", "char", "float"];
+
+// The id is a stable name for machine readable output, keep it when renaming variants.
+// Ordered by declaration, which is the order diagnostics list expected token classes in
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Clone)]
+#[cfg_attr(feature = "strum", derive(strum::Display, strum::EnumIter))]
 pub enum TokenClass {
     Identifier,
     Keyword,
+    Type,
     Operator,
     Literal,
     Number,
@@ -20,9 +27,15 @@ pub enum TokenClass {
     Rparen,
     LCurly,
     RCurly,
+    LBracket,
+    RBracket,
     Semi,
     Comma,
     Assignment,
+    Arrow,
+    Dot,
+    Colon,
+    PathSeparator,
     Error,
 }
 
@@ -30,26 +43,130 @@ pub enum TokenClass {
 pub enum Token {
     Identifier(String),
     Keyword(String),
+    Type(String),
     Operator(Operator),
     Literal(String),
     Number(String),
-    Boolean(String),
+    Boolean(bool),
     Lparen,
     Rparen,
     LCurly,
     RCurly,
+    LBracket,
+    RBracket,
     Semi,
     Comma,
     Assignment,
+    // -> before the return type of a function
+    Arrow,
+    // between the parts of a name, like System.print
+    Dot,
+    // before the type of a name, like x: int
+    Colon,
+    // :: between the parts of a qualified name, like math::sqrt
+    PathSeparator,
     Error(String),
 }
 
+impl TokenClass {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Identifier => "identifier",
+            Self::Keyword => "keyword",
+            Self::Type => "type",
+            Self::Operator => "operator",
+            Self::Literal => "literal",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::Lparen => "left_paren",
+            Self::Rparen => "right_paren",
+            Self::LCurly => "left_curly",
+            Self::RCurly => "right_curly",
+            Self::LBracket => "left_bracket",
+            Self::RBracket => "right_bracket",
+            Self::Semi => "semi",
+            Self::Comma => "comma",
+            Self::Assignment => "assignment",
+            Self::Arrow => "arrow",
+            Self::Dot => "dot",
+            Self::Colon => "colon",
+            Self::PathSeparator => "path_separator",
+            Self::Error => "error",
+        }
+    }
+
+    // How diagnostics name any token of the class, like Token::describe does for one token
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Identifier => "identifier",
+            Self::Keyword => "keyword",
+            Self::Type => "type",
+            Self::Operator => "operator",
+            Self::Literal => "string",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::Lparen => "`(`",
+            Self::Rparen => "`)`",
+            Self::LCurly => "`{`",
+            Self::RCurly => "`}`",
+            Self::LBracket => "`[`",
+            Self::RBracket => "`]`",
+            Self::Semi => "`;`",
+            Self::Comma => "`,`",
+            Self::Assignment => "`=`",
+            Self::Arrow => "`->`",
+            Self::Dot => "`.`",
+            Self::Colon => "`:`",
+            Self::PathSeparator => "`::`",
+            Self::Error => "invalid token",
+        }
+    }
+}
+
+// Shown by the name of the variant and iterated in declaration order, like the strum derives
+#[cfg(not(feature = "strum"))]
+impl Display for TokenClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(not(feature = "strum"))]
+impl TokenClass {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            Self::Identifier,
+            Self::Keyword,
+            Self::Type,
+            Self::Operator,
+            Self::Literal,
+            Self::Number,
+            Self::Boolean,
+            Self::Lparen,
+            Self::Rparen,
+            Self::LCurly,
+            Self::RCurly,
+            Self::LBracket,
+            Self::RBracket,
+            Self::Semi,
+            Self::Comma,
+            Self::Assignment,
+            Self::Arrow,
+            Self::Dot,
+            Self::Colon,
+            Self::PathSeparator,
+            Self::Error,
+        ]
+        .into_iter()
+    }
+}
+
 impl Token {
     pub fn is_special_char(char: char) -> bool {
-        match char {
-            ';' | '(' | ')' | '{' | '}' | '=' | ',' => true,
-            _ => false,
-        }
+        matches!(
+            char,
+            ';' | '(' | ')' | '{' | '}' | '[' | ']' | '=' | ',' | '.' | ':'
+        )
     }
 
     pub fn is_keyword(word: &str) -> bool {
@@ -59,25 +176,32 @@ impl Token {
             }
         }
 
-        return false;
+        false
+    }
+
+    pub fn is_type(word: &str) -> bool {
+        TYPES.contains(&word)
     }
 
+    #[cfg(feature = "regex")]
     pub fn is_string(word: &str) -> bool {
         let regex = Regex::new(r#"^(".*?")$"#).unwrap();
 
         regex.captures(word).is_some()
     }
 
-    pub fn is_number(word: &str) -> bool {
-        let regex = Regex::new(r#"^(\d+(\.\d+)?)$"#).unwrap();
+    // Same as the regex, quotes around anything but a new line
+    #[cfg(not(feature = "regex"))]
+    pub fn is_string(word: &str) -> bool {
+        word.len() >= 2 && word.starts_with('"') && word.ends_with('"') && !word.contains('\n')
+    }
 
-        regex.captures(word).is_some()
+    pub fn is_number(word: &str) -> bool {
+        NumberForm::of(word).is_some()
     }
 
     pub fn is_boolean(word: &str) -> bool {
-        let regex = Regex::new(r#"^true|false$"#).unwrap();
-
-        regex.captures(word).is_some()
+        matches!(word, "true" | "false")
     }
 
     pub fn is_equal_discrimnant(&self, token: &Self) -> bool {
@@ -88,6 +212,7 @@ impl Token {
         match &self {
             Self::Identifier(_) => TokenClass::Identifier,
             Self::Keyword(_) => TokenClass::Keyword,
+            Self::Type(_) => TokenClass::Type,
             Self::Operator(_) => TokenClass::Operator,
             Self::Literal(_) => TokenClass::Literal,
             Self::Number(_) => TokenClass::Number,
@@ -96,9 +221,15 @@ impl Token {
             Self::Rparen => TokenClass::Rparen,
             Self::LCurly => TokenClass::LCurly,
             Self::RCurly => TokenClass::RCurly,
+            Self::LBracket => TokenClass::LBracket,
+            Self::RBracket => TokenClass::RBracket,
             Self::Semi => TokenClass::Semi,
             Self::Comma => TokenClass::Comma,
             Self::Assignment => TokenClass::Assignment,
+            Self::Arrow => TokenClass::Arrow,
+            Self::Dot => TokenClass::Dot,
+            Self::Colon => TokenClass::Colon,
+            Self::PathSeparator => TokenClass::PathSeparator,
             Self::Error(_) => TokenClass::Error,
         }
     }
@@ -107,11 +238,12 @@ impl Token {
         match self {
             Self::Identifier(value) 
                 | Self::Keyword(value) 
+                | Self::Type(value) 
                 | Self::Literal(value) 
                 | Self::Error(value) 
                 | Self::Number(value)
-                | Self::Boolean(value)
                 => Some(value.to_owned()),
+            Self::Boolean(value) => Some(value.to_string()),
             Self::Operator(value) => Some(value.to_string()),
             _ => None,
         }
@@ -124,11 +256,60 @@ impl PartialEq<TokenClass> for Token {
     }
 }
 
+// Display is the debugging format, diagnostics use describe and lexeme
+impl Token {
+    // The token as it is written in the source, string literals with their quotes.
+    // Error tokens have no source text of their own, they give their message
+    pub fn lexeme(&self) -> String {
+        match self {
+            Self::Identifier(value)
+            | Self::Keyword(value)
+            | Self::Type(value)
+            | Self::Number(value)
+            | Self::Error(value) => value.to_owned(),
+            Self::Literal(value) => format!("\"{}\"", value),
+            Self::Operator(operator) => operator.to_string(),
+            Self::Boolean(value) => value.to_string(),
+            Self::Lparen => "(".to_owned(),
+            Self::Rparen => ")".to_owned(),
+            Self::LCurly => "{".to_owned(),
+            Self::RCurly => "}".to_owned(),
+            Self::LBracket => "[".to_owned(),
+            Self::RBracket => "]".to_owned(),
+            Self::Semi => ";".to_owned(),
+            Self::Comma => ",".to_owned(),
+            Self::Assignment => "=".to_owned(),
+            Self::Arrow => "->".to_owned(),
+            Self::Dot => ".".to_owned(),
+            Self::Colon => ":".to_owned(),
+            Self::PathSeparator => "::".to_owned(),
+        }
+    }
+
+    // How diagnostics name the token, e.g. identifier `x` or `(`
+    pub fn describe(&self) -> String {
+        let lexeme = self.lexeme();
+
+        match self {
+            Self::Identifier(_) => format!("identifier `{}`", lexeme),
+            Self::Keyword(_) => format!("keyword `{}`", lexeme),
+            Self::Type(_) => format!("type `{}`", lexeme),
+            Self::Operator(_) => format!("operator `{}`", lexeme),
+            Self::Literal(_) => format!("string `{}`", lexeme),
+            Self::Number(_) => format!("number `{}`", lexeme),
+            Self::Boolean(_) => format!("boolean `{}`", lexeme),
+            Self::Error(_) => format!("invalid token ({})", lexeme),
+            _ => format!("`{}`", lexeme),
+        }
+    }
+}
+
 impl Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let to_display = match self {
             Self::Identifier(id) => format!("IDENTIFIER: {}", id),
             Self::Keyword(key) => format!("KEYWORD: {}", key),
+            Self::Type(name) => format!("TYPE: {}", name),
             Self::Operator(operator) => format!("OPERATOR: {}", operator),
             Self::Literal(value) => format!("STRING: {}", value),
             Self::Number(value) => format!("NUMBER: {}", value),
@@ -137,9 +318,15 @@ impl Display for Token {
             Self::Rparen => ")".to_owned(),
             Self::LCurly => "{".to_owned(),
             Self::RCurly => "}".to_owned(),
+            Self::LBracket => "[".to_owned(),
+            Self::RBracket => "]".to_owned(),
             Self::Semi => ";".to_owned(),
             Self::Comma => ",".to_owned(),
             Self::Assignment => "=".to_owned(),
+            Self::Arrow => "->".to_owned(),
+            Self::Dot => ".".to_owned(),
+            Self::Colon => ":".to_owned(),
+            Self::PathSeparator => "::".to_owned(),
             Self::Error(error) => format!("Failed to convert to token: {}", error),
         };
 
@@ -156,11 +343,15 @@ impl From<char> for Token {
             ')' => Self::Rparen,
             '{' => Self::LCurly,
             '}' => Self::RCurly,
+            '[' => Self::LBracket,
+            ']' => Self::RBracket,
             '=' => Self::Assignment,
             ',' => Self::Comma,
+            '.' => Self::Dot,
+            ':' => Self::Colon,
             _ => Self::Error(format!(
                 "Failed to parse character to a token: {}",
-                c.to_string()
+                c
             )),
         }
     }
@@ -170,13 +361,20 @@ impl From<&str> for Token {
     fn from(word: &str) -> Self {
         match word {
             word if Self::is_keyword(word) => Self::Keyword(word.to_owned()),
+            word if Self::is_type(word) => Self::Type(word.to_owned()),
+            "->" => Self::Arrow,
+            "::" => Self::PathSeparator,
             word if Operator::is_operator(word) => Self::Operator(word.into()),
-            word if Self::is_boolean(word) => Self::Boolean(word.into()),
+            word if Self::is_boolean(word) => Self::Boolean(word == "true"),
             word if Self::is_string(word) => Self::Literal(word[1..word.len() - 1].into()),
-            word if Self::is_number(word) => Self::Number(word.into()),
+            // without the _ separating its digits
+            word if Self::is_number(word) => Self::Number(word.replace('_', "")),
             word if word.len() == 1 => {
                 match word.chars().next().unwrap().into() {
-                    Self::Error(_) => Self::Identifier(word.to_owned()),
+                    // letters and _ are names, other characters are not tokens
+                    Self::Error(_) if word == "_" || word.chars().all(char::is_alphanumeric) => {
+                        Self::Identifier(word.to_owned())
+                    }
                     token => token 
                 }
             },
@@ -205,6 +403,42 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[case("\"hi there\"", true)]
+    #[case("\"\"", true)]
+    #[case("\"", false)]
+    #[case("\"hi", false)]
+    #[case("\"hi\nthere\"", false)]
+    #[case("hi", false)]
+    fn it_recognizes_string_literals(#[case] word: &str, #[case] is_string: bool) {
+        assert_eq!(Token::is_string(word), is_string);
+    }
+
+    #[rstest]
+    #[case(Token::Identifier("x".to_owned()), "identifier `x`", "x")]
+    #[case(Token::Type("int".to_owned()), "type `int`", "int")]
+    #[case(Token::Arrow, "`->`", "->")]
+    #[case(Token::PathSeparator, "`::`", "::")]
+    #[case(Token::Literal("hi there".to_owned()), "string `\"hi there\"`", "\"hi there\"")]
+    #[case(Token::Boolean(true), "boolean `true`", "true")]
+    #[case(Token::Lparen, "`(`", "(")]
+    fn it_describes_tokens_for_diagnostics(
+        #[case] token: Token,
+        #[case] description: &str,
+        #[case] lexeme: &str,
+    ) {
+        assert_eq!(token.describe(), description);
+        assert_eq!(token.lexeme(), lexeme);
+    }
+
+    #[test]
+    fn it_can_create_type_tokens_from_word() {
+        for name in TYPES {
+            let token: Token = (*name).into();
+            assert_eq!(token, Token::Type((*name).to_owned()));
+        }
+    }
+
     #[rstest]
     #[case("+", Token::Operator(Operator::Plus))]
     #[case("-", Token::Operator(Operator::Minus))]
@@ -217,9 +451,12 @@ mod tests {
     #[case(">=", Token::Operator(Operator::GreaterEqual))]
     #[case("19", Token::Number("19".to_owned()))]
     #[case("19.5", Token::Number("19.5".to_owned()))]
+    #[case("0x1F", Token::Number("0x1F".to_owned()))]
+    #[case("1_000", Token::Number("1000".to_owned()))]
     #[case("testing", Token::Identifier("testing".to_owned()))]
     #[case("test", Token::Identifier("test".to_owned()))]
-    #[case("int", Token::Keyword("int".to_owned()))]
+    #[case("int", Token::Type("int".to_owned()))]
+    #[case("if", Token::Keyword("if".to_owned()))]
     #[case("testing.testing_again", Token::Identifier("testing.testing_again".to_owned()))]
     #[case("\"Hello there\"", Token::Literal("Hello there".to_owned()))]
     #[case("=", Token::Assignment)]
@@ -227,6 +464,14 @@ mod tests {
     #[case(")", Token::Rparen)]
     #[case("{", Token::LCurly)]
     #[case("}", Token::RCurly)]
+    #[case("true", Token::Boolean(true))]
+    #[case("false", Token::Boolean(false))]
+    #[case("truex", Token::Identifier("truex".to_owned()))]
+    #[case("nottrue", Token::Identifier("nottrue".to_owned()))]
+    #[case("falsey", Token::Identifier("falsey".to_owned()))]
+    #[case("x", Token::Identifier("x".to_owned()))]
+    #[case("_", Token::Identifier("_".to_owned()))]
+    #[case("@", Token::Error("Failed to parse character to a token: @".to_owned()))]
     fn it_can_create_tokens_from_word(#[case] word: &str, #[case] expected: Token) {
         let token: Token = word.into();
 
@@ -253,7 +498,8 @@ mod tests {
     #[case("+", TokenClass::Operator)]
     #[case("19", TokenClass::Number)]
     #[case("testing", TokenClass::Identifier)]
-    #[case("int", TokenClass::Keyword)]
+    #[case("int", TokenClass::Type)]
+    #[case("while", TokenClass::Keyword)]
     #[case("\"Hello there\"", TokenClass::Literal)]
     #[case("=", TokenClass::Assignment)]
     #[case("(", TokenClass::Lparen)]
@@ -267,6 +513,43 @@ mod tests {
         assert_eq!(token.to_token_class(), expected);
     }
 
+    #[rstest]
+    #[case(Token::Boolean(true), "true")]
+    #[case(Token::Boolean(false), "false")]
+    fn it_extracts_boolean_values_as_text(#[case] token: Token, #[case] expected: &str) {
+        assert_eq!(token.extract_value(), Some(expected.to_owned()));
+    }
+
+    #[rstest]
+    #[case(TokenClass::Identifier, "identifier")]
+    #[case(TokenClass::Keyword, "keyword")]
+    #[case(TokenClass::Type, "type")]
+    #[case(TokenClass::Operator, "operator")]
+    #[case(TokenClass::Literal, "literal")]
+    #[case(TokenClass::Number, "number")]
+    #[case(TokenClass::Boolean, "boolean")]
+    #[case(TokenClass::Lparen, "left_paren")]
+    #[case(TokenClass::Rparen, "right_paren")]
+    #[case(TokenClass::LCurly, "left_curly")]
+    #[case(TokenClass::RCurly, "right_curly")]
+    #[case(TokenClass::Semi, "semi")]
+    #[case(TokenClass::Comma, "comma")]
+    #[case(TokenClass::Assignment, "assignment")]
+    #[case(TokenClass::Error, "error")]
+    fn it_has_stable_token_class_ids(#[case] token_class: TokenClass, #[case] expected: &str) {
+        assert_eq!(token_class.id(), expected);
+    }
+
+    #[test]
+    fn it_has_unique_token_class_ids() {
+        #[cfg(feature = "strum")]
+        use strum::IntoEnumIterator;
+
+        let ids: std::collections::HashSet<&str> = TokenClass::iter().map(|tc| tc.id()).collect();
+
+        assert_eq!(ids.len(), TokenClass::iter().count());
+    }
+
     #[test]
     fn it_can_check_the_kind_of_the_tokens_without_the_value() {
         let token = Token::Keyword("test".to_string());
@@ -0,0 +1,158 @@
+use lexer::{
+    lexer::{Lexer, LexerError},
+    number::NumberForm,
+    operator::Operator,
+    options::LexerOptions,
+    token::Token,
+};
+use rstest::rstest;
+
+// Inputs at the edges of the scanner. Every case gives all the tokens it lexes to, or the
+// tokens before its error and the variant of that error. A change in how one of them
+// lexes is a change to the lexer, not to the case
+
+// The tokens up to the end of the file or up to the first error
+fn lex(code: &str, options: LexerOptions) -> (Vec<Token>, Option<LexerError>) {
+    let mut lexer = Lexer::new(code.to_owned()).with_options(options);
+    let mut tokens = vec![];
+
+    loop {
+        match lexer.next() {
+            Ok(token_info) => tokens.push(token_info.token),
+            Err(LexerError::EndOfFileReached) => return (tokens, None),
+            Err(error) => return (tokens, Some(error)),
+        }
+    }
+}
+
+fn identifier(name: &str) -> Token {
+    Token::Identifier(name.to_owned())
+}
+
+fn number(value: &str) -> Token {
+    Token::Number(value.to_owned())
+}
+
+fn invalid(char: &str) -> Token {
+    Token::Error(format!("Failed to parse character to a token: {}", char))
+}
+
+#[rstest]
+#[case("", vec![])]
+#[case("  // only a comment\n/* and a block */ ", vec![])]
+#[case("x++", vec![identifier("x"), Token::Operator(Operator::Increment)])]
+#[case("f->", vec![identifier("f"), Token::Arrow])]
+#[case("\"say \\\"hi\\\"\"", vec![Token::Literal("say \\\"hi\\\"".to_owned())])]
+#[case("\"a\\\"b\";", vec![Token::Literal("a\\\"b".to_owned()), Token::Semi])]
+#[case("\"a\\\\\";", vec![Token::Literal("a\\\\".to_owned()), Token::Semi])]
+#[case("0x10 0o17 0b1", vec![number("0x10"), number("0o17"), number("0b1")])]
+#[case("\"it's\"", vec![Token::Literal("it's".to_owned())])]
+#[case("\"a // b\"", vec![Token::Literal("a // b".to_owned())])]
+#[case("\\", vec![invalid("\\")])]
+#[case("a \\ b", vec![identifier("a"), invalid("\\"), identifier("b")])]
+#[case(&"a".repeat(10_000), vec![identifier(&"a".repeat(10_000))])]
+#[case("\0", vec![invalid("\0")])]
+#[case("a \0 b", vec![identifier("a"), invalid("\0"), identifier("b")])]
+#[case("\"a\0b\"", vec![Token::Literal("a\0b".to_owned())])]
+fn it_lexes_edge_cases_to_the_expected_tokens(#[case] code: &str, #[case] expected: Vec<Token>) {
+    let (tokens, error) = lex(code, LexerOptions::default());
+
+    assert_eq!(tokens, expected);
+    assert!(
+        error.is_none(),
+        "{:?} should lex, not fail with {:?}",
+        code,
+        error
+    );
+}
+
+#[rstest]
+#[case(
+    "a +",
+    vec![identifier("a"), Token::Operator(Operator::Plus)],
+    |error: &LexerError| {
+        matches!(error, LexerError::OperatorAtEndOfFile { operator: Operator::Plus, .. })
+    }
+)]
+#[case(
+    "a ==",
+    vec![identifier("a"), Token::Operator(Operator::Equal)],
+    |error: &LexerError| {
+        matches!(error, LexerError::OperatorAtEndOfFile { operator: Operator::Equal, .. })
+    }
+)]
+#[case(
+    "a/",
+    vec![identifier("a"), Token::Operator(Operator::Div)],
+    |error: &LexerError| {
+        matches!(error, LexerError::OperatorAtEndOfFile { operator: Operator::Div, .. })
+    }
+)]
+#[case(
+    "a /* b",
+    vec![identifier("a")],
+    |error: &LexerError| matches!(error, LexerError::UnterminatedComment { .. })
+)]
+#[case(
+    "x = \"abc;",
+    vec![identifier("x"), Token::Assignment],
+    |error: &LexerError| matches!(error, LexerError::UnterminatedString { .. })
+)]
+#[case(
+    "\"a\" \"b",
+    vec![Token::Literal("a".to_owned())],
+    |error: &LexerError| matches!(error, LexerError::UnterminatedString { .. })
+)]
+#[case(
+    "\"a\\\";\nb;",
+    vec![],
+    |error: &LexerError| matches!(error, LexerError::UnterminatedString { .. })
+)]
+#[case(
+    "99999999999999999999",
+    vec![],
+    |error: &LexerError| matches!(error, LexerError::NumberOutOfRange { .. })
+)]
+#[case(
+    "0b102",
+    vec![],
+    |error: &LexerError| {
+        matches!(error, LexerError::MalformedNumber { form: NumberForm::Binary, .. })
+    }
+)]
+#[case(
+    "0x;",
+    vec![],
+    |error: &LexerError| matches!(error, LexerError::MalformedNumber { form: NumberForm::Hex, .. })
+)]
+fn it_fails_on_edge_cases_with_the_expected_error(
+    #[case] code: &str,
+    #[case] expected: Vec<Token>,
+    #[case] is_expected_error: fn(&LexerError) -> bool,
+) {
+    let (tokens, error) = lex(code, LexerOptions::default());
+
+    assert_eq!(tokens, expected);
+    assert!(
+        error.as_ref().is_some_and(is_expected_error),
+        "{:?} should fail with another error than {:?}",
+        code,
+        error
+    );
+}
+
+#[test]
+fn it_fails_on_a_very_long_identifier_past_the_maximum_line_length() {
+    let options = LexerOptions {
+        max_line_length: Some(80),
+        ..LexerOptions::default()
+    };
+    let (tokens, error) = lex(&"a".repeat(10_000), options);
+
+    assert!(tokens.is_empty());
+    assert!(
+        matches!(error, Some(LexerError::LineTooLong { max: 80, .. })),
+        "{:?}",
+        error
+    );
+}
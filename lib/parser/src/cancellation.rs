@@ -0,0 +1,44 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+// Shared flag embedders can flip from another thread to abort a long parse
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_is_not_cancelled_by_default() {
+        assert!(!CancellationToken::new().is_cancelled());
+    }
+
+    #[test]
+    fn it_shares_cancellation_between_clones() {
+        let token = CancellationToken::new();
+        let cloned = token.clone();
+
+        cloned.cancel();
+
+        assert!(token.is_cancelled());
+    }
+}
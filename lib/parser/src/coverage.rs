@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+// A choice the parser makes while building a node, named after the node and the branch.
+// Add a variant for every new branch, so coverage counts it as missing until it is tested
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy, EnumIter, Display)]
+pub enum Production {
+    #[strum(serialize = "expression/parenthesized")]
+    ParenthesizedExpression,
+    #[strum(serialize = "expression/value")]
+    ValueExpression,
+    #[strum(serialize = "expression/increment")]
+    IncrementExpression,
+    #[strum(serialize = "expression/binary")]
+    BinaryExpression,
+    #[strum(serialize = "expression/call")]
+    CallExpression,
+    #[strum(serialize = "expression/index")]
+    IndexExpression,
+    #[strum(serialize = "expression/negated")]
+    NegatedExpression,
+    #[strum(serialize = "block/empty")]
+    EmptyBlock,
+    #[strum(serialize = "block/statements")]
+    Block,
+    #[strum(serialize = "statement/block")]
+    BlockStatement,
+    #[strum(serialize = "statement/condition")]
+    ConditionStatement,
+    #[strum(serialize = "statement/for_loop")]
+    ForLoopStatement,
+    #[strum(serialize = "statement/assignment")]
+    AssignmentStatement,
+    #[strum(serialize = "statement/declaration")]
+    VariableDeclaration,
+    #[strum(serialize = "statement/reassignment")]
+    Reassignment,
+    #[strum(serialize = "statement/function_call")]
+    FunctionCall,
+    #[strum(serialize = "statement/function_definition")]
+    FunctionDefinition,
+    #[strum(serialize = "statement/pure_function_definition")]
+    PureFunctionDefinition,
+    #[strum(serialize = "statement/return")]
+    ReturnStatement,
+    #[strum(serialize = "statement/implicit_return")]
+    ImplicitReturn,
+    #[strum(serialize = "argument/required")]
+    RequiredArgument,
+    #[strum(serialize = "argument/default")]
+    DefaultArgument,
+}
+
+// How often each production was taken, kept by parsers built with_coverage
+#[derive(Debug, Default, Clone)]
+pub struct Coverage {
+    hits: BTreeMap<Production, usize>,
+}
+
+impl Coverage {
+    pub fn record(&mut self, production: Production) {
+        *self.hits.entry(production).or_default() += 1;
+    }
+
+    pub fn hits(&self, production: Production) -> usize {
+        self.hits.get(&production).copied().unwrap_or_default()
+    }
+
+    pub fn uncovered(&self) -> impl Iterator<Item = Production> + '_ {
+        Production::iter().filter(|production| self.hits(*production) == 0)
+    }
+
+    // The share of productions taken at least once, between 0 and 1
+    pub fn ratio(&self) -> f64 {
+        let total = Production::iter().count();
+
+        self.hits.len() as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_productions_that_were_taken() {
+        let mut coverage = Coverage::default();
+
+        coverage.record(Production::Block);
+        coverage.record(Production::Block);
+
+        assert_eq!(coverage.hits(Production::Block), 2);
+        assert_eq!(coverage.hits(Production::EmptyBlock), 0);
+        assert_eq!(coverage.uncovered().count(), Production::iter().count() - 1);
+    }
+
+    #[test]
+    fn it_is_fully_covered_when_every_production_was_taken() {
+        let mut coverage = Coverage::default();
+
+        Production::iter().for_each(|production| coverage.record(production));
+
+        assert_eq!(coverage.ratio(), 1.0);
+        assert_eq!(coverage.uncovered().next(), None);
+    }
+}
@@ -0,0 +1,185 @@
+use lexer::token::TokenClass;
+
+use crate::{
+    parse_node::{Loc, NodeKind, ParseNode},
+    visitor::Visitor,
+};
+
+// A part of the source an editor can collapse, from the start of its first token
+// to the end of its last one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldingRange {
+    pub start: Loc,
+    pub end: Loc,
+}
+
+#[derive(Default)]
+struct FoldingRanges {
+    ranges: Vec<FoldingRange>,
+    // expressions are folded as a whole, not the ones nested in them
+    expression_depth: usize,
+}
+
+impl Visitor for FoldingRanges {
+    fn enter(&mut self, node: &ParseNode) {
+        let is_outer_expression = node.kind == NodeKind::Expression && self.expression_depth == 0;
+
+        if node.kind == NodeKind::Expression {
+            self.expression_depth += 1;
+        }
+
+        // one range per line, the first one starting there is the outermost
+        let folds = (node.kind == NodeKind::Block || is_outer_expression)
+            && node.loc.line < node.end_loc.line
+            && !self
+                .ranges
+                .iter()
+                .any(|range| range.start.line == node.loc.line);
+
+        if folds {
+            self.ranges.push(FoldingRange {
+                start: node.loc.clone(),
+                end: node.end_loc.clone(),
+            });
+        }
+    }
+
+    fn leave(&mut self, node: &ParseNode) {
+        if node.kind == NodeKind::Expression {
+            self.expression_depth -= 1;
+        }
+    }
+}
+
+// Blocks, function bodies included, and expressions that span more than one line,
+// in the order they start in
+pub fn folding_ranges(node: &ParseNode) -> Vec<FoldingRange> {
+    let mut ranges = FoldingRanges::default();
+
+    node.walk(&mut ranges);
+
+    ranges.ranges
+}
+
+// Where a bracket opens and where it closes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BracketPair {
+    pub open: Loc,
+    pub close: Loc,
+}
+
+// Every (), {} and [] of a tree, so editors can highlight the other side of a bracket
+#[derive(Debug, Default)]
+pub struct BracketPairs {
+    pairs: Vec<BracketPair>,
+    open: Vec<Loc>,
+}
+
+impl BracketPairs {
+    pub fn of(node: &ParseNode) -> Self {
+        let mut pairs = Self::default();
+
+        node.walk(&mut pairs);
+        pairs
+            .pairs
+            .sort_by_key(|pair| (pair.open.line, pair.open.column));
+
+        pairs
+    }
+
+    // The other bracket of the one at loc, whichever side it is
+    pub fn matching(&self, loc: &Loc) -> Option<&Loc> {
+        self.pairs.iter().find_map(|pair| match loc {
+            loc if loc == &pair.open => Some(&pair.close),
+            loc if loc == &pair.close => Some(&pair.open),
+            _ => None,
+        })
+    }
+
+    // Ordered by where the pairs open
+    pub fn iter(&self) -> impl Iterator<Item = &BracketPair> {
+        self.pairs.iter()
+    }
+}
+
+impl Visitor for BracketPairs {
+    fn enter(&mut self, node: &ParseNode) {
+        let NodeKind::TokenClass(token_class) = &node.kind else {
+            return;
+        };
+
+        match token_class {
+            TokenClass::Lparen | TokenClass::LCurly | TokenClass::LBracket => {
+                self.open.push(node.loc.clone())
+            }
+            TokenClass::Rparen | TokenClass::RCurly | TokenClass::RBracket => {
+                // the parser only builds trees with balanced brackets
+                if let Some(open) = self.open.pop() {
+                    self.pairs.push(BracketPair {
+                        open,
+                        close: node.loc.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    #[test]
+    fn it_folds_multi_line_blocks_and_expressions() {
+        let program = parse(
+            "fn main() -> int {\n    if (a) {\n        b(1);\n    }\n    int c = (1 +\n        2) + 3;\n    {}\n    return c;\n}",
+        );
+
+        assert_eq!(
+            folding_ranges(&program),
+            vec![
+                FoldingRange {
+                    start: Loc::new(1, 18),
+                    end: Loc::new(9, 1),
+                },
+                FoldingRange {
+                    start: Loc::new(2, 12),
+                    end: Loc::new(4, 5),
+                },
+                FoldingRange {
+                    start: Loc::new(5, 13),
+                    end: Loc::new(6, 14),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_pairs_brackets_in_source_order() {
+        let program = parse("int a = b(c[1]);\nif (a) {\n}");
+        let pairs = BracketPairs::of(&program);
+        let opens: Vec<&Loc> = pairs.iter().map(|pair| &pair.open).collect();
+
+        assert_eq!(
+            opens,
+            vec![
+                &Loc::new(1, 10),
+                &Loc::new(1, 12),
+                &Loc::new(2, 4),
+                &Loc::new(2, 8),
+            ]
+        );
+        assert_eq!(pairs.matching(&Loc::new(1, 12)), Some(&Loc::new(1, 14)));
+        assert_eq!(pairs.matching(&Loc::new(3, 1)), Some(&Loc::new(2, 8)));
+        assert_eq!(pairs.matching(&Loc::new(1, 1)), None);
+    }
+}
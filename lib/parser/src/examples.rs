@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+
+use lexer::token::TokenClass;
+
+use crate::grammar::{Grammar, NonTerminal, ProductionRule, ProductionRuleSymbol};
+
+// The smallest program that uses a production rule of the grammar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub non_terminal: NonTerminal,
+    // the position of the rule among the rules of its non terminal
+    pub rule: usize,
+    pub code: String,
+}
+
+// A lexeme of the class, the same one every time so examples stay predictable
+fn sample(token_class: &TokenClass) -> &'static str {
+    match token_class {
+        TokenClass::Identifier => "a",
+        TokenClass::Keyword => "if",
+        TokenClass::Type => "int",
+        TokenClass::Operator => "+",
+        TokenClass::Literal => "\"a\"",
+        TokenClass::Number => "1",
+        TokenClass::Boolean => "true",
+        TokenClass::Lparen => "(",
+        TokenClass::Rparen => ")",
+        TokenClass::LCurly => "{",
+        TokenClass::RCurly => "}",
+        TokenClass::LBracket => "[",
+        TokenClass::RBracket => "]",
+        TokenClass::Semi => ";",
+        TokenClass::Comma => ",",
+        TokenClass::Assignment => "=",
+        TokenClass::Arrow => "->",
+        TokenClass::Dot => ".",
+        TokenClass::Colon => ":",
+        TokenClass::PathSeparator => "::",
+        TokenClass::Error => "@",
+    }
+}
+
+type Lexemes = Vec<String>;
+
+struct Generator<'a> {
+    grammar: &'a Grammar,
+    // the fewest lexemes every non terminal can derive
+    shortest: BTreeMap<NonTerminal, Lexemes>,
+    // the fewest lexemes a program needs around a non terminal, before and after it
+    contexts: BTreeMap<NonTerminal, (Lexemes, Lexemes)>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(grammar: &'a Grammar) -> Self {
+        let mut generator = Self {
+            grammar,
+            shortest: BTreeMap::new(),
+            contexts: BTreeMap::new(),
+        };
+
+        generator.find_shortest();
+        generator.find_contexts();
+
+        generator
+    }
+
+    // The lexemes of the symbols with every non terminal derived as short as it can be,
+    // None while a non terminal has no derivation yet
+    fn expand(&self, symbols: &[ProductionRuleSymbol]) -> Option<Lexemes> {
+        let mut lexemes = vec![];
+
+        for symbol in symbols {
+            match symbol {
+                ProductionRuleSymbol::Token(token) => lexemes.push(token.lexeme()),
+                ProductionRuleSymbol::TokenClass(token_class) => {
+                    lexemes.push(sample(token_class).to_owned())
+                }
+                ProductionRuleSymbol::NonTerminal(non_terminal) => {
+                    lexemes.extend(self.shortest.get(non_terminal)?.iter().cloned())
+                }
+                ProductionRuleSymbol::Empty => {}
+            }
+        }
+
+        Some(lexemes)
+    }
+
+    fn rules(&self) -> impl Iterator<Item = (NonTerminal, usize, &'a ProductionRule)> {
+        self.grammar.iter().flat_map(|(non_terminal, rules)| {
+            rules
+                .iter()
+                .enumerate()
+                .map(|(index, rule)| (*non_terminal, index, rule))
+        })
+    }
+
+    // Shortens derivations until none of them gets shorter, the first shortest rule wins
+    fn find_shortest(&mut self) {
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for (non_terminal, _, rule) in self.rules() {
+                let Some(lexemes) = self.expand(rule) else {
+                    continue;
+                };
+                let is_shorter = self
+                    .shortest
+                    .get(&non_terminal)
+                    .is_none_or(|shortest| lexemes.len() < shortest.len());
+
+                if is_shorter {
+                    self.shortest.insert(non_terminal, lexemes);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    // Walks down from the program, a non terminal used by a rule is in the context of
+    // that rule's non terminal with the rest of the rule derived around it
+    fn find_contexts(&mut self) {
+        let mut changed = true;
+
+        self.contexts.insert(NonTerminal::Program, (vec![], vec![]));
+
+        while changed {
+            changed = false;
+
+            for (non_terminal, _, rule) in self.rules() {
+                let Some((before, after)) = self.contexts.get(&non_terminal).cloned() else {
+                    continue;
+                };
+
+                for (index, symbol) in rule.iter().enumerate() {
+                    let ProductionRuleSymbol::NonTerminal(used) = symbol else {
+                        continue;
+                    };
+                    let (Some(left), Some(right)) =
+                        (self.expand(&rule[..index]), self.expand(&rule[index + 1..]))
+                    else {
+                        continue;
+                    };
+                    let before = [before.clone(), left].concat();
+                    let after = [right, after.clone()].concat();
+                    let is_shorter =
+                        self.contexts
+                            .get(used)
+                            .is_none_or(|(old_before, old_after)| {
+                                before.len() + after.len() < old_before.len() + old_after.len()
+                            });
+
+                    if is_shorter {
+                        self.contexts.insert(*used, (before, after));
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// One example per production rule, in the order the grammar iterates them. Rules of
+// non terminals that can not be reached from the program or derive nothing have none
+pub fn examples(grammar: &Grammar) -> Vec<Example> {
+    let generator = Generator::new(grammar);
+
+    generator
+        .rules()
+        .filter_map(|(non_terminal, rule_index, rule)| {
+            let (before, after) = generator.contexts.get(&non_terminal)?;
+            let lexemes = [before.clone(), generator.expand(rule)?, after.clone()].concat();
+
+            Some(Example {
+                non_terminal,
+                rule: rule_index,
+                code: lexemes.join(" "),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(examples: &[Example], non_terminal: NonTerminal, rule: usize) -> &str {
+        examples
+            .iter()
+            .find(|example| example.non_terminal == non_terminal && example.rule == rule)
+            .map(|example| example.code.as_str())
+            .unwrap()
+    }
+
+    #[test]
+    fn it_derives_the_smallest_program_around_a_rule() {
+        let examples = examples(&Grammar::new());
+
+        assert_eq!(example(&examples, NonTerminal::Program, 0), "int a ;");
+        assert_eq!(
+            example(&examples, NonTerminal::AssignmentStatement, 0),
+            "int a = a ;"
+        );
+        assert_eq!(
+            example(&examples, NonTerminal::ExpressionPrime, 2),
+            "int a = a [ a ] ;"
+        );
+    }
+
+    #[test]
+    fn it_has_an_example_for_every_rule() {
+        let grammar = Grammar::new();
+        let rules: usize = grammar.iter().map(|(_, rules)| rules.len()).sum();
+
+        assert_eq!(examples(&grammar).len(), rules);
+    }
+}
@@ -0,0 +1,628 @@
+use std::collections::HashMap;
+
+use lexer::{
+    diagnostic::{CompilerError, Span},
+    token::TokenClass,
+};
+use strum::EnumProperty;
+use thiserror::Error as ThisError;
+
+use crate::{
+    parse_node::{Loc, NodeKind, ParseNode},
+    rename::{is_valid_name, TextEdit},
+    semantics::{is_slice, Binding, Occurrence, Symbols, INDEX_TYPE, SLICE_TYPE},
+};
+
+const BODY_INDENT: &str = "    ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extraction {
+    // declared right before the statement the expression is in
+    Variable,
+    // defined after the top level statement the expression is in, with a parameter
+    // for every variable the expression reads
+    Function,
+}
+
+// The props code is the stable code of the error, see CompilerError
+#[derive(ThisError, Debug, PartialEq, Eq, EnumProperty)]
+pub enum ExtractError {
+    #[strum(props(code = "R0005"))]
+    #[error("{0} can not be used as a name!")]
+    InvalidName(String),
+    #[strum(props(code = "R0006"))]
+    #[error("There is no expression from {}:{} to {}:{} that can be extracted!", .start.line, .start.column, .end.line, .end.column)]
+    NoExpression { start: Loc, end: Loc },
+    #[strum(props(code = "R0007"))]
+    #[error("The type of the expression at {}:{} is not known!", .0.line, .0.column)]
+    UnknownType(Loc),
+    #[strum(props(code = "R0008"))]
+    #[error("{0} is already a name in the program!")]
+    NameInUse(String),
+    #[strum(props(code = "R0009"))]
+    #[error("{name} at {}:{} is not visible where the extracted code goes!", .loc.line, .loc.column)]
+    NotVisible { name: String, loc: Loc },
+    #[strum(props(code = "R0010"))]
+    #[error("The expression at {}:{} is evaluated on every iteration of its loop, it can not be moved before it!", .0.line, .0.column)]
+    InLoopHeader(Loc),
+}
+
+impl CompilerError for ExtractError {
+    fn code(&self) -> &'static str {
+        self.get_str("code")
+            .expect("Every extract error should have a code!")
+    }
+
+    fn span(&self) -> Option<Span> {
+        match self {
+            Self::InvalidName(_) | Self::NameInUse(_) => None,
+            Self::NoExpression { start: loc, .. }
+            | Self::UnknownType(loc)
+            | Self::NotVisible { loc, .. }
+            | Self::InLoopHeader(loc) => Some(loc.into()),
+        }
+    }
+}
+
+fn position(loc: &Loc) -> (usize, usize) {
+    (loc.line.get(), loc.column.get())
+}
+
+fn is_operator(node: &ParseNode) -> bool {
+    node.kind == NodeKind::TokenClass(TokenClass::Operator)
+        && !matches!(node.value.as_deref(), Some("++" | "--"))
+}
+
+// The first operator between two operands, an operator that starts the nodes negates
+// the operand after it
+fn binary_operator(nodes: &[ParseNode]) -> Option<usize> {
+    nodes
+        .iter()
+        .skip(1)
+        .position(is_operator)
+        .map(|position| position + 1)
+}
+
+fn child_of(node: &ParseNode, token_class: TokenClass) -> Option<&ParseNode> {
+    node.children
+        .iter()
+        .find(|child| child.kind == NodeKind::TokenClass(token_class.clone()))
+}
+
+// The part of the tree that is extracted, the children of an expression or the ones
+// before its first operator, which is the value the expression starts with
+struct Selection<'a> {
+    nodes: &'a [ParseNode],
+    // the statement of a block or of the program it is in
+    statement: &'a ParseNode,
+    // the statement of the program it is in
+    item: &'a ParseNode,
+}
+
+fn select<'a>(
+    node: &'a ParseNode,
+    start: &Loc,
+    end: &Loc,
+    statement: Option<&'a ParseNode>,
+    item: Option<&'a ParseNode>,
+) -> Option<Selection<'a>> {
+    for (index, child) in node.children.iter().enumerate() {
+        if position(start) < position(&child.loc) || position(&child.end_loc) < position(end) {
+            continue;
+        }
+
+        let item = if node.kind == NodeKind::Program {
+            Some(child)
+        } else {
+            item
+        };
+        let statement = if matches!(node.kind, NodeKind::Program | NodeKind::Block) {
+            Some(child)
+        } else {
+            statement
+        };
+
+        if let (NodeKind::Expression, Some(statement), Some(item)) = (&child.kind, statement, item)
+        {
+            // the right side of an operator is not a value of its own, a + b * c can be (a + b) * c
+            let is_whole = child.loc == *start
+                && child.end_loc == *end
+                && !(index > 1 && is_operator(&node.children[index - 1]));
+            let value_end = binary_operator(&child.children).unwrap_or(child.children.len());
+            let nodes = if is_whole {
+                Some(&child.children[..])
+            } else if value_end > 0
+                && child.loc == *start
+                && child.children[value_end - 1].end_loc == *end
+            {
+                Some(&child.children[..value_end])
+            } else {
+                None
+            };
+
+            if let Some(nodes) = nodes {
+                return Some(Selection {
+                    nodes,
+                    statement,
+                    item,
+                });
+            }
+        }
+
+        return select(child, start, end, statement, item);
+    }
+
+    None
+}
+
+// The type of every declaration, the return type for functions, and whether the
+// declaration is a function defined in the program itself
+#[derive(Default)]
+struct Declarations<'a> {
+    types: HashMap<(usize, usize), &'a str>,
+    functions: HashMap<(usize, usize), bool>,
+}
+
+impl<'a> Declarations<'a> {
+    fn of(program: &'a ParseNode) -> Self {
+        let mut declarations = Self::default();
+
+        declarations.collect(program, true);
+
+        declarations
+    }
+
+    fn collect(&mut self, node: &'a ParseNode, is_top_level: bool) {
+        let identifier = child_of(node, TokenClass::Identifier);
+        let declared_type = child_of(node, TokenClass::Type).and_then(|t| t.value.as_deref());
+
+        if let (Some(identifier), Some(declared_type)) = (identifier, declared_type) {
+            if matches!(
+                node.kind,
+                NodeKind::AssignmentStatement
+                    | NodeKind::VariableDeclaration
+                    | NodeKind::Argument
+                    | NodeKind::FunctionDefinition
+            ) {
+                self.types.insert(position(&identifier.loc), declared_type);
+            }
+        }
+
+        if let (NodeKind::FunctionDefinition, Some(identifier)) = (&node.kind, identifier) {
+            self.functions
+                .insert(position(&identifier.loc), is_top_level);
+        }
+
+        for child in &node.children {
+            self.collect(child, node.kind == NodeKind::Program);
+        }
+    }
+
+    fn type_of(&self, binding: &Binding) -> Option<&'a str> {
+        match binding {
+            Binding::Declared(loc) => self.types.get(&position(loc)).copied(),
+            Binding::Free(_) => None,
+        }
+    }
+
+    fn is_function(&self, binding: &Binding) -> bool {
+        matches!(binding, Binding::Declared(loc) if self.functions.contains_key(&position(loc)))
+    }
+}
+
+struct Types<'a> {
+    symbols: &'a Symbols,
+    declarations: &'a Declarations<'a>,
+}
+
+impl Types<'_> {
+    fn of_identifier(&self, identifier: &ParseNode) -> Option<String> {
+        let occurrence = self.symbols.at(&identifier.loc)?;
+
+        self.declarations
+            .type_of(&occurrence.binding)
+            .map(str::to_owned)
+    }
+
+    // The type a value has, with the indexes and ++ or -- after it
+    fn of_value(&self, nodes: &[ParseNode]) -> Option<String> {
+        let (value, rest) = nodes.split_first()?;
+
+        if let Some(index) = rest
+            .iter()
+            .rev()
+            .find(|node| node.kind == NodeKind::IndexExpression)
+        {
+            let type_name = if is_slice(index) {
+                SLICE_TYPE
+            } else {
+                INDEX_TYPE
+            };
+
+            return Some(type_name.to_owned());
+        }
+
+        match &value.kind {
+            NodeKind::TokenClass(TokenClass::Literal) => Some(SLICE_TYPE.to_owned()),
+            NodeKind::TokenClass(TokenClass::Boolean) => Some("bool".to_owned()),
+            NodeKind::TokenClass(TokenClass::Number) => match &value.value {
+                Some(number) if number.contains('.') => Some("float".to_owned()),
+                _ => Some("int".to_owned()),
+            },
+            NodeKind::TokenClass(TokenClass::Identifier) => self.of_identifier(value),
+            NodeKind::FunctionCall => self.of_identifier(child_of(value, TokenClass::Identifier)?),
+            NodeKind::TokenClass(TokenClass::Lparen) => rest
+                .first()
+                .filter(|inner| inner.kind == NodeKind::Expression)
+                .and_then(|inner| self.of_expression(&inner.children)),
+            _ => None,
+        }
+    }
+
+    // Comparisons and logic operators give a bool, the others the type of the first value.
+    // The operators after the first one are in the expression on its right side
+    fn of_expression(&self, nodes: &[ParseNode]) -> Option<String> {
+        let mut operators = vec![];
+        let mut rest = nodes;
+
+        while let Some(operator) = binary_operator(rest) {
+            operators.push(rest[operator].value.as_deref());
+
+            match rest.get(operator + 1) {
+                Some(right) if right.kind == NodeKind::Expression => rest = &right.children,
+                _ => break,
+            }
+        }
+
+        if operators.contains(&Some("..")) {
+            return None;
+        }
+
+        if operators.iter().any(|operator| {
+            matches!(
+                operator,
+                Some("==" | "!=" | "<" | "<=" | ">" | ">=" | "&&" | "||")
+            )
+        }) {
+            return Some("bool".to_owned());
+        }
+
+        let value_end = binary_operator(nodes).unwrap_or(nodes.len());
+
+        match &nodes[..value_end] {
+            // a not gives a bool, a minus the type of the operand it negates
+            [operator, negated, ..] if is_operator(operator) => match operator.value.as_deref() {
+                Some("!") => Some("bool".to_owned()),
+                _ => self.of_expression(&negated.children),
+            },
+            value => self.of_value(value),
+        }
+    }
+}
+
+// The source as characters, locs count characters and not bytes
+struct Source(Vec<char>);
+
+impl Source {
+    fn offset(&self, loc: &Loc) -> usize {
+        let line_start = self
+            .0
+            .split_inclusive(|char| *char == '\n')
+            .take(loc.line.zero_based())
+            .map(|line| line.len())
+            .sum::<usize>();
+
+        line_start + loc.column.zero_based()
+    }
+
+    // From the start of from to the end of to
+    fn text(&self, from: &Loc, to: &Loc) -> String {
+        self.0[self.offset(from)..=self.offset(to)].iter().collect()
+    }
+
+    // What comes before from on its line up to from, and up to the end of to after it
+    fn around(&self, node: &ParseNode, from: &Loc, to: &Loc) -> (String, String) {
+        let before = self.0[self.offset(&node.loc)..self.offset(from)].iter();
+        let after = self.0[self.offset(to) + 1..=self.offset(&node.end_loc)].iter();
+
+        (before.collect(), after.collect())
+    }
+
+    // How a statement inserted before node is separated from it, on its own line
+    // with the same indent when node starts its line
+    fn separator(&self, node: &ParseNode) -> String {
+        let line_start = self.offset(&Loc::new(node.loc.line.get(), 1));
+        let indent: String = self.0[line_start..self.offset(&node.loc)].iter().collect();
+
+        if indent.chars().all(char::is_whitespace) {
+            format!("\n{}", indent)
+        } else {
+            " ".to_owned()
+        }
+    }
+}
+
+fn occurrences_in<'a>(
+    symbols: &'a Symbols,
+    start: &'a Loc,
+    end: &'a Loc,
+) -> impl Iterator<Item = &'a Occurrence> {
+    symbols.iter().filter(move |occurrence| {
+        position(start) <= position(&occurrence.loc) && position(&occurrence.loc) <= position(end)
+    })
+}
+
+// The edit that moves the expression from start to end, both inclusive, into a new variable
+// or function called name and puts the name, or a call to the function, in its place. Only
+// whole expressions and the value an expression starts with can be extracted, as the tree
+// does not say which operator binds first
+pub fn extract(
+    source: &str,
+    program: &ParseNode,
+    extraction: Extraction,
+    start: &Loc,
+    end: &Loc,
+    name: &str,
+) -> Result<TextEdit, ExtractError> {
+    if !is_valid_name(name) {
+        return Err(ExtractError::InvalidName(name.to_owned()));
+    }
+
+    let symbols = Symbols::of(program);
+
+    if symbols.iter().any(|occurrence| occurrence.name == name) {
+        return Err(ExtractError::NameInUse(name.to_owned()));
+    }
+
+    let selection =
+        select(program, start, end, None, None).ok_or_else(|| ExtractError::NoExpression {
+            start: start.clone(),
+            end: end.clone(),
+        })?;
+    let declarations = Declarations::of(program);
+    let types = Types {
+        symbols: &symbols,
+        declarations: &declarations,
+    };
+    let type_name = types
+        .of_expression(selection.nodes)
+        .ok_or_else(|| ExtractError::UnknownType(start.clone()))?;
+    let source = Source(source.chars().collect());
+    let expression = source.text(start, end);
+
+    match extraction {
+        Extraction::Variable => {
+            let statement = selection.statement;
+            let is_loop = statement.kind == NodeKind::ForLoopStatement
+                || (statement.kind == NodeKind::ConditionStatement
+                    && child_of(statement, TokenClass::Keyword)
+                        .is_some_and(|keyword| keyword.value.as_deref() == Some("while")));
+
+            if is_loop {
+                return Err(ExtractError::InLoopHeader(start.clone()));
+            }
+
+            let (before, after) = source.around(statement, start, end);
+
+            Ok(TextEdit {
+                start: statement.loc.clone(),
+                end: statement.end_loc.clone(),
+                new_text: format!(
+                    "{} {} = {};{}{}{}{}",
+                    type_name,
+                    name,
+                    expression,
+                    source.separator(statement),
+                    before,
+                    name,
+                    after
+                ),
+            })
+        }
+        Extraction::Function => {
+            let mut parameters: Vec<(&Occurrence, String)> = vec![];
+
+            for occurrence in occurrences_in(&symbols, start, end) {
+                let binding = &occurrence.binding;
+
+                if declarations.is_function(binding) {
+                    // functions defined in a block are not visible outside of it
+                    if let Binding::Declared(loc) = binding {
+                        if !declarations.functions[&position(loc)] {
+                            return Err(ExtractError::NotVisible {
+                                name: occurrence.name.clone(),
+                                loc: occurrence.loc.clone(),
+                            });
+                        }
+                    }
+                } else if matches!(binding, Binding::Declared(_))
+                    && !parameters.iter().any(|(seen, _)| &seen.binding == binding)
+                {
+                    let parameter_type = declarations
+                        .type_of(binding)
+                        .ok_or_else(|| ExtractError::UnknownType(occurrence.loc.clone()))?;
+
+                    parameters.push((occurrence, parameter_type.to_owned()));
+                }
+            }
+
+            let arguments: Vec<&str> = parameters
+                .iter()
+                .map(|(occurrence, _)| occurrence.name.as_str())
+                .collect();
+            let declared: Vec<String> = parameters
+                .iter()
+                .map(|(occurrence, parameter_type)| {
+                    format!("{} {}", parameter_type, occurrence.name)
+                })
+                .collect();
+            let item = selection.item;
+            let (before, after) = source.around(item, start, end);
+
+            Ok(TextEdit {
+                start: item.loc.clone(),
+                end: item.end_loc.clone(),
+                new_text: format!(
+                    "{}{}({}){}\n\nfn {}({}) -> {} {{\n{}{}\n}}",
+                    before,
+                    name,
+                    arguments.join(", "),
+                    after,
+                    name,
+                    declared.join(", "),
+                    type_name,
+                    BODY_INDENT,
+                    expression
+                ),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    fn extract_from(
+        code: &str,
+        extraction: Extraction,
+        start: Loc,
+        end: Loc,
+    ) -> Result<String, ExtractError> {
+        let edit = extract(code, &parse(code), extraction, &start, &end, "extracted")?;
+        let source = Source(code.chars().collect());
+        let from = source.offset(&edit.start);
+        let to = source.offset(&edit.end);
+        let edited: String = code.chars().take(from).collect::<String>()
+            + &edit.new_text
+            + &code.chars().skip(to + 1).collect::<String>();
+
+        // the edited code has to parse again
+        parse(&edited);
+
+        Ok(edited)
+    }
+
+    #[test]
+    fn it_extracts_an_expression_into_a_variable() {
+        let code = "fn f(int a) -> int {\n    int b = a * (a + 2);\n    return b;\n}";
+
+        assert_eq!(
+            extract_from(code, Extraction::Variable, Loc::new(2, 18), Loc::new(2, 22)),
+            Ok("fn f(int a) -> int {\n    int extracted = a + 2;\n    int b = a * (extracted);\n    return b;\n}".to_owned())
+        );
+        assert_eq!(
+            extract_from(code, Extraction::Variable, Loc::new(2, 13), Loc::new(2, 23)),
+            Ok("fn f(int a) -> int {\n    int extracted = a * (a + 2);\n    int b = extracted;\n    return b;\n}".to_owned())
+        );
+        assert_eq!(
+            extract_from(
+                "float f = 2.5;\nshow(-f * 2);",
+                Extraction::Variable,
+                Loc::new(2, 6),
+                Loc::new(2, 11)
+            ),
+            Ok("float f = 2.5;\nfloat extracted = -f * 2;\nshow(extracted);".to_owned())
+        );
+        assert_eq!(
+            extract_from(
+                "int a = 1;\nshow(!a);",
+                Extraction::Variable,
+                Loc::new(2, 6),
+                Loc::new(2, 7)
+            ),
+            Ok("int a = 1;\nbool extracted = !a;\nshow(extracted);".to_owned())
+        );
+    }
+
+    #[test]
+    fn it_extracts_the_value_an_expression_starts_with() {
+        let code = "string s = \"abc\";\nbool b = s[0..2] == \"ab\";";
+
+        assert_eq!(
+            extract_from(code, Extraction::Variable, Loc::new(2, 10), Loc::new(2, 16)),
+            Ok(
+                "string s = \"abc\";\nstring extracted = s[0..2];\nbool b = extracted == \"ab\";"
+                    .to_owned()
+            )
+        );
+        // the right side of == could be part of a longer left side
+        assert!(matches!(
+            extract_from(
+                "bool b = 1 + 2 == 3;",
+                Extraction::Variable,
+                Loc::new(1, 14),
+                Loc::new(1, 19)
+            ),
+            Err(ExtractError::NoExpression { .. })
+        ));
+    }
+
+    #[test]
+    fn it_extracts_an_expression_into_a_function() {
+        let code = "fn f(int a, string s) -> bool {\n    return a + len(s) > a;\n}";
+
+        assert_eq!(
+            extract_from(code, Extraction::Function, Loc::new(2, 12), Loc::new(2, 25)),
+            Ok("fn f(int a, string s) -> bool {\n    return extracted(a, s);\n}\n\nfn extracted(int a, string s) -> bool {\n    a + len(s) > a\n}".to_owned())
+        );
+    }
+
+    #[test]
+    fn it_refuses_extractions_that_change_the_program() {
+        let code = "int n = 3;\nfor (int i = 0; i < n; i = i + 1) {\n    show(i * 2);\n}";
+
+        assert_eq!(
+            extract_from(code, Extraction::Variable, Loc::new(2, 17), Loc::new(2, 21)),
+            Err(ExtractError::InLoopHeader(Loc::new(2, 17)))
+        );
+        assert_eq!(
+            extract_from(code, Extraction::Variable, Loc::new(3, 10), Loc::new(3, 14)),
+            Ok("int n = 3;\nfor (int i = 0; i < n; i = i + 1) {\n    int extracted = i * 2;\n    show(extracted);\n}".to_owned())
+        );
+        assert_eq!(
+            extract(
+                code,
+                &parse(code),
+                Extraction::Function,
+                &Loc::new(3, 10),
+                &Loc::new(3, 14),
+                "n"
+            ),
+            Err(ExtractError::NameInUse("n".to_owned()))
+        );
+        assert_eq!(
+            extract(
+                code,
+                &parse(code),
+                Extraction::Function,
+                &Loc::new(3, 10),
+                &Loc::new(3, 14),
+                "for"
+            ),
+            Err(ExtractError::InvalidName("for".to_owned()))
+        );
+    }
+
+    #[test]
+    fn it_refuses_functions_that_are_only_visible_in_a_block() {
+        let code = "{\n    int a = 1;\n}\nint b = g(2);\nfn g(int c) -> int {\n    fn h(int d) -> int { d }\n    return h(c);\n}";
+
+        assert_eq!(
+            extract_from(code, Extraction::Function, Loc::new(7, 12), Loc::new(7, 15)),
+            Err(ExtractError::NotVisible {
+                name: "h".to_owned(),
+                loc: Loc::new(7, 12),
+            })
+        );
+        assert!(extract_from(code, Extraction::Function, Loc::new(4, 9), Loc::new(4, 12)).is_ok());
+    }
+}
@@ -1,8 +1,16 @@
-use lexer::token::{Token, TokenClass, KEYWORDS};
-use std::collections::HashMap;
-use strum::{Display, EnumString};
+use crate::parse_node::NodeKind;
+use lexer::{
+    operator::Operator,
+    token::{Token, TokenClass, KEYWORDS, TYPES},
+};
+use std::collections::{BTreeMap, BTreeSet};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+use thiserror::Error as ThisError;
 
-#[derive(PartialEq, Eq, Hash, EnumString, Display)]
+// Ordered by declaration so iterating the grammar is deterministic between runs
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy, EnumString, EnumIter, Display,
+)]
 pub enum NonTerminal {
     #[strum(serialize = "P")]
     Program,
@@ -14,14 +22,58 @@ pub enum NonTerminal {
     AssignmentStatement,
     #[strum(serialize = "E")]
     Expression,
+    #[strum(serialize = "E'")]
+    ExpressionPrime,
+    #[strum(serialize = "M")]
+    Operand,
     #[strum(serialize = "K")]
     Keyword,
+    #[strum(serialize = "T")]
+    Type,
     #[strum(serialize = "V")]
     Variable,
     #[strum(serialize = "Q")]
     Conditional,
     #[strum(serialize = "F")]
     ForLoop,
+    #[strum(serialize = "U")]
+    LoopUpdate,
+    #[strum(serialize = "C")]
+    CallArguments,
+    #[strum(serialize = "C'")]
+    CallArgumentsPrime,
+}
+
+impl NonTerminal {
+    // The node the hand-written parser builds for this non terminal,
+    // None for non terminals that only shape the grammar
+    pub fn node_kind(&self) -> Option<NodeKind> {
+        match self {
+            Self::Program => Some(NodeKind::Program),
+            Self::AssignmentStatement => Some(NodeKind::AssignmentStatement),
+            Self::Expression => Some(NodeKind::Expression),
+            Self::Operand => Some(NodeKind::Expression),
+            Self::Keyword => Some(NodeKind::TokenClass(TokenClass::Keyword)),
+            Self::Type => Some(NodeKind::TokenClass(TokenClass::Type)),
+            Self::Variable => Some(NodeKind::TokenClass(TokenClass::Identifier)),
+            Self::Conditional => Some(NodeKind::ConditionStatement),
+            Self::ForLoop => Some(NodeKind::ForLoopStatement),
+            Self::Statement
+            | Self::StatementPrime
+            | Self::ExpressionPrime
+            | Self::LoopUpdate
+            | Self::CallArguments
+            | Self::CallArgumentsPrime => None,
+        }
+    }
+}
+
+#[derive(ThisError, Debug, PartialEq, Eq)]
+pub enum GrammarError {
+    #[error("Non terminal {0} has no production rules!")]
+    MissingProductionRules(NonTerminal),
+    #[error("Non terminal {0} can not be reached from the program!")]
+    UnreachableNonTerminal(NonTerminal),
 }
 
 pub enum ProductionRuleSymbol {
@@ -33,28 +85,80 @@ pub enum ProductionRuleSymbol {
 
 pub type ProductionRule = Vec<ProductionRuleSymbol>;
 pub type ProductionRules = Vec<ProductionRule>;
-pub type GrammarTable = HashMap<NonTerminal, ProductionRules>;
+pub type GrammarTable = BTreeMap<NonTerminal, ProductionRules>;
 
 pub struct Grammar {
-    grammar: HashMap<NonTerminal, ProductionRules>,
+    grammar: GrammarTable,
+}
+
+impl Default for Grammar {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Grammar {
     pub fn new() -> Self {
-        let mut grammar = HashMap::new();
+        let mut grammar = BTreeMap::new();
 
         Self::init_program_production_rules(&mut grammar);
         Self::init_statement_production_rules(&mut grammar);
+        Self::init_statement_prime_production_rules(&mut grammar);
         Self::init_assignment_statement_production_rules(&mut grammar);
         Self::init_expression_production_rules(&mut grammar);
+        Self::init_expression_prime_production_rules(&mut grammar);
+        Self::init_operand_production_rules(&mut grammar);
         Self::init_conditional_production_rules(&mut grammar);
         Self::init_for_loop_production_rules(&mut grammar);
         Self::init_keyword_production_rules(&mut grammar);
+        Self::init_type_production_rules(&mut grammar);
         Self::init_variable_production_rules(&mut grammar);
+        Self::init_call_arguments_production_rules(&mut grammar);
 
         Self { grammar }
     }
 
+    pub fn production_rules(&self, non_terminal: &NonTerminal) -> Option<&ProductionRules> {
+        self.grammar.get(non_terminal)
+    }
+
+    // Yields non terminals in declaration order, and their rules in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&NonTerminal, &ProductionRules)> {
+        self.grammar.iter()
+    }
+
+    // Verifies every non terminal is defined and reachable from the program,
+    // which catches rules being overwritten or forgotten
+    pub fn check(&self) -> Vec<GrammarError> {
+        let mut errors = vec![];
+        let mut reachable = BTreeSet::new();
+        let mut to_visit = vec![NonTerminal::Program];
+
+        while let Some(non_terminal) = to_visit.pop() {
+            if !reachable.insert(non_terminal) {
+                continue;
+            }
+
+            for rule in self.grammar.get(&non_terminal).into_iter().flatten() {
+                for symbol in rule {
+                    if let ProductionRuleSymbol::NonTerminal(next) = symbol {
+                        to_visit.push(*next);
+                    }
+                }
+            }
+        }
+
+        for non_terminal in NonTerminal::iter() {
+            if !self.grammar.contains_key(&non_terminal) {
+                errors.push(GrammarError::MissingProductionRules(non_terminal));
+            } else if !reachable.contains(&non_terminal) {
+                errors.push(GrammarError::UnreachableNonTerminal(non_terminal));
+            }
+        }
+
+        errors
+    }
+
     pub fn init_program_production_rules(table: &mut GrammarTable) {
         table.insert(
             NonTerminal::Program,
@@ -75,10 +179,35 @@ impl Grammar {
                 vec![
                     ProductionRuleSymbol::NonTerminal(NonTerminal::Variable),
                     ProductionRuleSymbol::TokenClass(TokenClass::Lparen),
-                    ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::CallArguments),
                     ProductionRuleSymbol::TokenClass(TokenClass::Rparen),
+                    ProductionRuleSymbol::TokenClass(TokenClass::Semi),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::StatementPrime),
+                ],
+                vec![
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Conditional),
                     ProductionRuleSymbol::NonTerminal(NonTerminal::StatementPrime),
                 ],
+                vec![
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::ForLoop),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::StatementPrime),
+                ],
+                vec![
+                    ProductionRuleSymbol::TokenClass(TokenClass::LCurly),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Statement),
+                    ProductionRuleSymbol::TokenClass(TokenClass::RCurly),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::StatementPrime),
+                ],
+            ],
+        );
+    }
+
+    pub fn init_statement_prime_production_rules(table: &mut GrammarTable) {
+        table.insert(
+            NonTerminal::StatementPrime,
+            vec![
+                vec![ProductionRuleSymbol::NonTerminal(NonTerminal::Statement)],
+                vec![ProductionRuleSymbol::Empty],
             ],
         );
     }
@@ -86,13 +215,20 @@ impl Grammar {
     pub fn init_assignment_statement_production_rules(table: &mut GrammarTable) {
         table.insert(
             NonTerminal::AssignmentStatement,
-            vec![vec![
-                ProductionRuleSymbol::NonTerminal(NonTerminal::Keyword),
-                ProductionRuleSymbol::NonTerminal(NonTerminal::Variable),
-                ProductionRuleSymbol::TokenClass(TokenClass::Assignment),
-                ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
-                ProductionRuleSymbol::TokenClass(TokenClass::Semi),
-            ]],
+            vec![
+                vec![
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Type),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Variable),
+                    ProductionRuleSymbol::TokenClass(TokenClass::Assignment),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
+                    ProductionRuleSymbol::TokenClass(TokenClass::Semi),
+                ],
+                vec![
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Type),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Variable),
+                    ProductionRuleSymbol::TokenClass(TokenClass::Semi),
+                ],
+            ],
         );
     }
 
@@ -113,24 +249,125 @@ impl Grammar {
 
     pub fn init_for_loop_production_rules(table: &mut GrammarTable) {
         table.insert(
-            NonTerminal::Conditional,
+            NonTerminal::ForLoop,
             vec![vec![
                 ProductionRuleSymbol::Token(Token::Keyword("for".to_owned())),
                 ProductionRuleSymbol::TokenClass(TokenClass::Lparen),
                 ProductionRuleSymbol::NonTerminal(NonTerminal::AssignmentStatement),
                 ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
                 ProductionRuleSymbol::TokenClass(TokenClass::Semi),
-                ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
+                ProductionRuleSymbol::NonTerminal(NonTerminal::LoopUpdate),
                 ProductionRuleSymbol::TokenClass(TokenClass::Rparen),
                 ProductionRuleSymbol::TokenClass(TokenClass::LCurly),
                 ProductionRuleSymbol::NonTerminal(NonTerminal::Statement),
                 ProductionRuleSymbol::TokenClass(TokenClass::RCurly),
             ]],
         );
+        table.insert(
+            NonTerminal::LoopUpdate,
+            vec![
+                vec![
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Variable),
+                    ProductionRuleSymbol::TokenClass(TokenClass::Assignment),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
+                ],
+                vec![ProductionRuleSymbol::NonTerminal(NonTerminal::Expression)],
+            ],
+        );
+    }
+
+    pub fn init_expression_production_rules(table: &mut GrammarTable) {
+        table.insert(
+            NonTerminal::Expression,
+            vec![vec![
+                ProductionRuleSymbol::NonTerminal(NonTerminal::Operand),
+                ProductionRuleSymbol::NonTerminal(NonTerminal::ExpressionPrime),
+            ]],
+        );
+    }
+
+    pub fn init_expression_prime_production_rules(table: &mut GrammarTable) {
+        table.insert(
+            NonTerminal::ExpressionPrime,
+            vec![
+                vec![ProductionRuleSymbol::Token(Token::Operator(
+                    Operator::Increment,
+                ))],
+                vec![
+                    ProductionRuleSymbol::TokenClass(TokenClass::Operator),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
+                ],
+                vec![
+                    ProductionRuleSymbol::TokenClass(TokenClass::LBracket),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
+                    ProductionRuleSymbol::TokenClass(TokenClass::RBracket),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::ExpressionPrime),
+                ],
+                vec![ProductionRuleSymbol::Empty],
+            ],
+        );
+    }
+
+    // A minus or a not only takes the operand right after it, what comes after that
+    // goes on from the negated operand
+    pub fn init_operand_production_rules(table: &mut GrammarTable) {
+        let mut production_rules = vec![
+            vec![
+                ProductionRuleSymbol::TokenClass(TokenClass::Lparen),
+                ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
+                ProductionRuleSymbol::TokenClass(TokenClass::Rparen),
+            ],
+            vec![
+                ProductionRuleSymbol::NonTerminal(NonTerminal::Variable),
+                ProductionRuleSymbol::TokenClass(TokenClass::Lparen),
+                ProductionRuleSymbol::NonTerminal(NonTerminal::CallArguments),
+                ProductionRuleSymbol::TokenClass(TokenClass::Rparen),
+            ],
+        ];
+
+        for token_class in [
+            TokenClass::Identifier,
+            TokenClass::Boolean,
+            TokenClass::Number,
+            TokenClass::Literal,
+        ] {
+            production_rules.push(vec![ProductionRuleSymbol::TokenClass(token_class)]);
+        }
+
+        for operator in [Operator::Minus, Operator::Not] {
+            production_rules.push(vec![
+                ProductionRuleSymbol::Token(Token::Operator(operator)),
+                ProductionRuleSymbol::NonTerminal(NonTerminal::Operand),
+            ]);
+        }
+
+        table.insert(NonTerminal::Operand, production_rules);
     }
 
-    pub fn init_expression_production_rules(_table: &mut GrammarTable) {
-        todo!("Implement expression production rules!");
+    pub fn init_call_arguments_production_rules(table: &mut GrammarTable) {
+        table.insert(
+            NonTerminal::CallArguments,
+            vec![
+                vec![
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::CallArgumentsPrime),
+                ],
+                vec![ProductionRuleSymbol::Empty],
+            ],
+        );
+        table.insert(
+            NonTerminal::CallArgumentsPrime,
+            vec![
+                vec![
+                    ProductionRuleSymbol::TokenClass(TokenClass::Comma),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::Expression),
+                    ProductionRuleSymbol::NonTerminal(NonTerminal::CallArgumentsPrime),
+                ],
+                // a trailing comma
+                vec![ProductionRuleSymbol::TokenClass(TokenClass::Comma)],
+                vec![ProductionRuleSymbol::Empty],
+            ],
+        );
     }
 
     pub fn init_keyword_production_rules(table: &mut GrammarTable) {
@@ -146,6 +383,15 @@ impl Grammar {
         table.insert(NonTerminal::Keyword, production_rules);
     }
 
+    pub fn init_type_production_rules(table: &mut GrammarTable) {
+        let production_rules = TYPES
+            .iter()
+            .map(|name| vec![ProductionRuleSymbol::Token(Token::Type(name.to_string()))])
+            .collect();
+
+        table.insert(NonTerminal::Type, production_rules);
+    }
+
     pub fn init_variable_production_rules(table: &mut GrammarTable) {
         table.insert(
             NonTerminal::Variable,
@@ -155,3 +401,71 @@ impl Grammar {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_defines_every_non_terminal_reachable_from_the_program() {
+        assert_eq!(Grammar::new().check(), vec![]);
+    }
+
+    #[test]
+    fn it_reports_missing_and_unreachable_non_terminals() {
+        let mut table = GrammarTable::new();
+
+        Grammar::init_program_production_rules(&mut table);
+        Grammar::init_variable_production_rules(&mut table);
+
+        let errors = Grammar { grammar: table }.check();
+
+        assert!(errors.contains(&GrammarError::MissingProductionRules(
+            NonTerminal::Statement
+        )));
+        assert!(errors.contains(&GrammarError::UnreachableNonTerminal(NonTerminal::Variable)));
+    }
+
+    #[test]
+    fn it_iterates_non_terminals_in_declaration_order() {
+        let mut table = GrammarTable::new();
+
+        Grammar::init_variable_production_rules(&mut table);
+        Grammar::init_keyword_production_rules(&mut table);
+        Grammar::init_assignment_statement_production_rules(&mut table);
+        Grammar::init_program_production_rules(&mut table);
+
+        let order: Vec<&NonTerminal> = table.keys().collect();
+
+        assert_eq!(
+            order,
+            vec![
+                &NonTerminal::Program,
+                &NonTerminal::AssignmentStatement,
+                &NonTerminal::Keyword,
+                &NonTerminal::Variable,
+            ]
+        );
+    }
+
+    #[test]
+    fn it_keeps_keyword_production_rules_in_keyword_order() {
+        let mut table = GrammarTable::new();
+
+        Grammar::init_keyword_production_rules(&mut table);
+
+        let keywords: Vec<&Token> = table[&NonTerminal::Keyword]
+            .iter()
+            .map(|rule| match &rule[..] {
+                [ProductionRuleSymbol::Token(token)] => token,
+                _ => panic!("Keyword rules should be a single token!"),
+            })
+            .collect();
+
+        assert_eq!(keywords.len(), KEYWORDS.len());
+
+        for (token, keyword) in keywords.iter().zip(KEYWORDS) {
+            assert_eq!(*token, &Token::Keyword(keyword.to_string()));
+        }
+    }
+}
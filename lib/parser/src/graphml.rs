@@ -0,0 +1,132 @@
+use crate::{
+    parse_node::{Loc, ParseNode},
+    visitor::Visitor,
+};
+
+const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="kind" for="node" attr.name="kind" attr.type="string"/>
+  <key id="value" for="node" attr.name="value" attr.type="string"/>
+  <key id="start" for="node" attr.name="start" attr.type="string"/>
+  <key id="end" for="node" attr.name="end" attr.type="string"/>
+  <key id="order" for="edge" attr.name="order" attr.type="int"/>
+  <graph id="tree" edgedefault="directed">
+"#;
+const FOOTER: &str = "  </graph>\n</graphml>\n";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn position(loc: &Loc) -> String {
+    format!("{}:{}", loc.line, loc.column)
+}
+
+// Writes the tree as GraphML for graph tooling. Nodes are numbered in the order they are
+// entered, their kind is the stable id of NodeKind and every edge keeps the position of
+// the child under its parent, as graph formats do not keep the order of edges
+#[derive(Default)]
+pub struct GraphMlExporter {
+    output: String,
+    next_id: usize,
+    // the id of every node on the path to the current one and how many children it has so far
+    parents: Vec<(usize, usize)>,
+}
+
+impl GraphMlExporter {
+    pub fn export(node: &ParseNode) -> String {
+        let mut exporter = Self::default();
+
+        node.walk(&mut exporter);
+
+        format!("{}{}{}", HEADER, exporter.output, FOOTER)
+    }
+}
+
+impl Visitor for GraphMlExporter {
+    fn enter(&mut self, node: &ParseNode) {
+        let id = self.next_id;
+
+        self.next_id += 1;
+        self.output
+            .push_str(&format!("    <node id=\"n{}\">\n", id));
+        self.output.push_str(&format!(
+            "      <data key=\"kind\">{}</data>\n",
+            node.kind.id()
+        ));
+
+        if let Some(value) = &node.value {
+            self.output.push_str(&format!(
+                "      <data key=\"value\">{}</data>\n",
+                escape(value)
+            ));
+        }
+
+        self.output.push_str(&format!(
+            "      <data key=\"start\">{}</data>\n",
+            position(&node.loc)
+        ));
+        self.output.push_str(&format!(
+            "      <data key=\"end\">{}</data>\n",
+            position(&node.end_loc)
+        ));
+        self.output.push_str("    </node>\n");
+
+        if let Some((parent, children)) = self.parents.last_mut() {
+            self.output.push_str(&format!(
+                "    <edge source=\"n{}\" target=\"n{}\">\n      <data key=\"order\">{}</data>\n    </edge>\n",
+                parent, id, children
+            ));
+            *children += 1;
+        }
+
+        self.parents.push((id, 0));
+    }
+
+    fn leave(&mut self, _node: &ParseNode) {
+        self.parents.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    #[test]
+    fn it_exports_nodes_with_their_attributes() {
+        let graph = GraphMlExporter::export(&parse("string a = \"<b>\";"));
+
+        assert!(graph.starts_with(HEADER));
+        assert!(graph.ends_with(FOOTER));
+        assert!(graph.contains(
+            "    <node id=\"n0\">\n      <data key=\"kind\">program</data>\n      <data key=\"start\">1:1</data>\n      <data key=\"end\">1:17</data>\n    </node>\n"
+        ));
+        assert!(graph.contains("<data key=\"value\">&lt;b&gt;</data>"));
+    }
+
+    #[test]
+    fn it_keeps_the_order_of_children_on_their_edges() {
+        let graph = GraphMlExporter::export(&parse("int a = 1;\nint b = 2;"));
+
+        assert_eq!(graph.matches("<node ").count(), 15);
+        assert_eq!(graph.matches("<edge ").count(), 14);
+        assert!(graph.contains(
+            "    <edge source=\"n0\" target=\"n8\">\n      <data key=\"order\">1</data>\n    </edge>\n"
+        ));
+        assert!(graph.contains(
+            "    <edge source=\"n1\" target=\"n3\">\n      <data key=\"order\">1</data>\n    </edge>\n"
+        ));
+    }
+}
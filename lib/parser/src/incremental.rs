@@ -0,0 +1,232 @@
+use lexer::{
+    lexer::Lexer,
+    options::LexerOptions,
+    position::{ColumnNumber, LineNumber},
+};
+
+use crate::{
+    parse_node::{Loc, ParseNode},
+    parsers::{ParserError, RecursiveDescentParser},
+};
+
+// Lines an editor replaced, in lines of the source the tree was parsed from: the lines
+// first to last became new_line_count lines, 0 when they were deleted. Text typed into
+// a line replaces that one line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineEdit {
+    pub first: LineNumber,
+    pub last: LineNumber,
+    pub new_line_count: usize,
+}
+
+impl LineEdit {
+    // How many lines the code after the edit moved, down when positive
+    fn line_delta(&self) -> isize {
+        self.new_line_count as isize - (self.last.get() - self.first.get() + 1) as isize
+    }
+}
+
+// How much of the tree a reparse kept
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Reparse {
+    // the top level statements taken from the previous tree, before and after the edit
+    pub reused: usize,
+    // the top level statements parsed again from the new source
+    pub reparsed: usize,
+}
+
+// Where the source goes on from loc, as a position and a byte offset. A loc past the end
+// of its line, like the one after a virtual semi, goes on at the start of the next line
+fn resume_at(source: &str, loc: &Loc) -> Option<(Loc, usize)> {
+    let mut line_start = 0;
+
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        if index < loc.line.zero_based() {
+            line_start += line.len();
+
+            continue;
+        }
+
+        let column = loc.column.zero_based();
+
+        if column >= line.len() && line.ends_with('\n') {
+            return Some((Loc::new(loc.line.get() + 1, 1), line_start + line.len()));
+        }
+
+        let offset = line_start + column.min(line.len());
+
+        return source
+            .is_char_boundary(offset)
+            .then(|| (loc.clone(), offset));
+    }
+
+    None
+}
+
+fn shift_lines(node: &mut ParseNode, delta: isize) {
+    let shift = |line: LineNumber| LineNumber::new((line.get() as isize + delta) as usize);
+
+    node.loc.line = shift(node.loc.line);
+    node.end_loc.line = shift(node.end_loc.line);
+
+    for child in &mut node.children {
+        shift_lines(child, delta);
+    }
+}
+
+// Brings a tree parsed from the source before an edit up to date with the edited source.
+// Only the top level statements the edit can change are lexed and parsed again:
+// - the ones on the edited lines
+// - the ones after them until a reparsed statement ends where one of the previous tree
+//   did, the rest of the source is the same from there. An opened comment reparses the rest
+// On an error the tree stays as it was, the new source has no tree then
+pub fn reparse(
+    tree: &mut ParseNode,
+    source: &str,
+    edit: &LineEdit,
+    options: &LexerOptions,
+) -> Result<Reparse, ParserError> {
+    let statements = &tree.children;
+    let kept_before = statements
+        .iter()
+        .take_while(|statement| statement.end_loc.line < edit.first)
+        .count();
+    // right after the last kept statement, on the line it ends on
+    let start = match kept_before.checked_sub(1) {
+        Some(index) => Loc {
+            line: statements[index].end_loc.line,
+            column: ColumnNumber::new(statements[index].end_loc.column.get() + 1),
+        },
+        None => Loc::default(),
+    };
+    let (kept_before, start, offset) = match resume_at(source, &start) {
+        Some((start, offset)) => (kept_before, start, offset),
+        None => (0, Loc::default(), 0),
+    };
+    let lexer = Lexer::new(source[offset..].to_owned())
+        .with_options(options.clone())
+        .starting_at(start.line, start.column);
+    let mut parser = RecursiveDescentParser::new(lexer);
+    let delta = edit.line_delta();
+    let last_edited_line = edit.first.get() as isize + edit.new_line_count as isize - 1;
+    let mut reparsed = vec![];
+    // the statement of the previous tree the reparsed ones line up with
+    let mut aligned = None;
+
+    while let Some(statement) = parser.next_statement()? {
+        let end = statement.end_loc.clone();
+
+        reparsed.push(statement);
+
+        if end.line.get() as isize <= last_edited_line {
+            continue;
+        }
+
+        aligned = statements.iter().position(|old| {
+            old.loc.line > edit.last
+                && old.end_loc.column == end.column
+                && old.end_loc.line.get() as isize + delta == end.line.get() as isize
+        });
+
+        if aligned.is_some() {
+            break;
+        }
+    }
+
+    let mut children = std::mem::take(&mut tree.children);
+    let after: Vec<ParseNode> = match aligned {
+        Some(index) => children.drain(index + 1..).collect(),
+        None => vec![],
+    };
+    let progress = Reparse {
+        reused: kept_before + after.len(),
+        reparsed: reparsed.len(),
+    };
+
+    children.truncate(kept_before);
+    tree.loc = Loc::default();
+    tree.end_loc = Loc::default();
+
+    for statement in children.into_iter().chain(reparsed) {
+        tree.add_child(statement);
+    }
+
+    for mut statement in after {
+        shift_lines(&mut statement, delta);
+        tree.add_child(statement);
+    }
+
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(code: &str) -> ParseNode {
+        RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+            .parse()
+            .unwrap()
+    }
+
+    fn edit(first: usize, last: usize, new_line_count: usize) -> LineEdit {
+        LineEdit {
+            first: LineNumber::new(first),
+            last: LineNumber::new(last),
+            new_line_count,
+        }
+    }
+
+    #[test]
+    fn it_only_reparses_the_statements_around_the_edit() {
+        let mut tree = parse("a(1);\nb(2);\nc(3);\nd(4);\ne(5);\n");
+        let source = "a(1);\nb(2);\nint x = 1;\nint y = 2;\nd(4);\ne(5);\n";
+        let progress = reparse(&mut tree, source, &edit(3, 3, 2), &LexerOptions::default());
+
+        assert_eq!(
+            progress.unwrap(),
+            Reparse {
+                reused: 3,
+                reparsed: 3,
+            }
+        );
+        assert_eq!(
+            format!("{:?}", tree.verbose()),
+            format!("{:?}", parse(source).verbose())
+        );
+    }
+
+    #[test]
+    fn it_reparses_the_rest_of_the_source_after_an_opened_comment() {
+        let mut tree = parse("a(1);\nb(2);\nc(3);\nd(4);\n");
+        let source = "a(1);\nb(2); /*\nc(3);\nd(4);\n*/\n";
+        let progress = reparse(&mut tree, source, &edit(2, 4, 4), &LexerOptions::default());
+
+        assert_eq!(
+            progress.unwrap(),
+            Reparse {
+                reused: 1,
+                reparsed: 1,
+            }
+        );
+        assert_eq!(tree.children.len(), 2);
+    }
+
+    #[test]
+    fn it_keeps_the_tree_when_the_edited_source_does_not_parse() {
+        let code = "a(1);\nb(2);\n";
+        let mut tree = parse(code);
+        let progress = reparse(
+            &mut tree,
+            "a(1);\nb(;\n",
+            &edit(2, 2, 1),
+            &LexerOptions::default(),
+        );
+
+        assert!(progress.is_err());
+        assert_eq!(
+            format!("{:?}", tree.verbose()),
+            format!("{:?}", parse(code).verbose())
+        );
+    }
+}
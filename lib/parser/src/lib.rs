@@ -1,3 +1,16 @@
+pub mod cancellation;
+pub mod coverage;
+pub mod editor;
+pub mod examples;
+pub mod extract;
 pub mod grammar;
+pub mod graphml;
+pub mod incremental;
+pub mod options;
 pub mod parse_node;
 pub mod parsers;
+pub mod prelude;
+pub mod rename;
+pub mod self_check;
+pub mod semantics;
+pub mod visitor;
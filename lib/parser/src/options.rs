@@ -0,0 +1,30 @@
+use lexer::token::{Token, TYPES};
+
+// Keywords a statement can start with, the parser resynchronizes on them after an error
+pub const STATEMENT_KEYWORDS: &[&str] = &["if", "while", "for", "fn", "pure", "return"];
+
+#[derive(Debug)]
+pub struct ParserOptions {
+    // After an error, parse_with_recovery skips tokens until one of these.
+    // A `;` or `}` ends the broken statement and is skipped as well,
+    // any other token starts the next statement and is kept
+    pub synchronization_tokens: Vec<Token>,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        let mut synchronization_tokens = vec![Token::Semi, Token::RCurly];
+
+        synchronization_tokens.extend(
+            STATEMENT_KEYWORDS
+                .iter()
+                .map(|keyword| Token::Keyword(keyword.to_string())),
+        );
+        // an assignment starts with its type
+        synchronization_tokens.extend(TYPES.iter().map(|name| Token::Type(name.to_string())));
+
+        Self {
+            synchronization_tokens,
+        }
+    }
+}
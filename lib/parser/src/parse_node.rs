@@ -1,30 +1,72 @@
-use lexer::token::TokenClass;
-use strum::Display;
+use lexer::{
+    diagnostic::Span,
+    position::{ColumnNumber, LineNumber},
+    token::TokenClass,
+};
+use std::fmt::{Debug, Display as FmtDisplay};
+use strum::{Display, EnumProperty};
 
-#[derive(Debug, PartialEq, Eq, Display)]
+// The props id is a stable name for machine readable output, keep it when renaming variants
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Display, EnumProperty)]
 pub enum NodeKind {
+    #[strum(props(id = "block"))]
     Block,
+    #[strum(props(id = "program"))]
     Program,
+    #[strum(props(id = "expression"))]
     Expression,
+    // [i] or [a..b] after the string it takes a character or a part of
+    #[strum(props(id = "index_expression"))]
+    IndexExpression,
 
     // Statements
+    #[strum(props(id = "statement"))]
     Statement,
+    #[strum(props(id = "for_loop_statement"))]
     ForLoopStatement,
+    #[strum(props(id = "return_statement"))]
     ReturnStatement,
+    #[strum(props(id = "control_flow_block"))]
     ControlFlowBlock,
+    #[strum(props(id = "condition_statement"))]
     ConditionStatement,
+    #[strum(props(id = "assignment_statement"))]
     AssignmentStatement,
+    #[strum(props(id = "variable_declaration"))]
+    VariableDeclaration,
+    // a block on its own, its declarations go out of scope at its end
+    #[strum(props(id = "block_statement"))]
+    BlockStatement,
 
     // Functions
+    #[strum(props(id = "argument"))]
     Argument,
+    #[strum(props(id = "arguments"))]
     Arguments,
+    #[strum(props(id = "function_call"))]
     FunctionCall,
+    #[strum(props(id = "function_definition"))]
     FunctionDefinition,
+    // the /// comment of a function or declaration, always its last child
+    #[strum(props(id = "doc_comment"))]
+    DocComment,
 
     // Token classes
     TokenClass(TokenClass),
 }
 
+impl NodeKind {
+    // Token class nodes share the id of their token class
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::TokenClass(token_class) => token_class.id(),
+            kind => kind
+                .get_str("id")
+                .expect("Every node kind should have an id!"),
+        }
+    }
+}
+
 impl From<&TokenClass> for NodeKind {
     fn from(token_class: &TokenClass) -> Self {
         Self::TokenClass(token_class.clone())
@@ -37,15 +79,35 @@ impl From<TokenClass> for NodeKind {
     }
 }
 
-#[derive(Debug, Clone)]
+// Defaults to 1:1, the start of the source
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Loc {
-    pub line: usize,
-    pub column: usize,
+    pub line: LineNumber,
+    pub column: ColumnNumber,
+}
+
+impl Loc {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self {
+            line: LineNumber::new(line),
+            column: ColumnNumber::new(column),
+        }
+    }
+}
+
+impl From<&Loc> for Span {
+    fn from(loc: &Loc) -> Self {
+        Self {
+            line: loc.line,
+            column: loc.column,
+        }
+    }
 }
 
-#[derive(Debug)]
 pub struct ParseNode {
     pub loc: Loc,
+    // where the last character of the node is, so a whole statement can be highlighted
+    pub end_loc: Loc,
     pub kind: NodeKind,
     pub value: Option<String>,
     pub children: Vec<Self>,
@@ -53,32 +115,219 @@ pub struct ParseNode {
 
 impl ParseNode {
     pub fn add_child(&mut self, node: ParseNode) {
-        if self.children.len() == 0 {
+        if self.children.is_empty() {
             self.loc = node.loc.clone();
         }
 
+        self.end_loc = node.end_loc.clone();
         self.children.push(node);
     }
 
+    pub fn doc(&self) -> Option<&str> {
+        self.children
+            .iter()
+            .find(|child| child.kind == NodeKind::DocComment)
+            .and_then(|child| child.value.as_deref())
+    }
+
     pub fn print_tree(&self) {
-        self.inner_print_tree(0)
+        print!("{}", self)
     }
 
-    fn inner_print_tree(&self, padding: i32) {
-        let pad_str: String = (0..padding).map(|_| " ").collect();
-        let kind = match &self.kind {
+    // Wraps the node so Debug prints every field, like the derived implementation did
+    pub fn verbose(&self) -> VerboseParseNode<'_> {
+        VerboseParseNode(self)
+    }
+
+    fn kind_name(&self) -> String {
+        match &self.kind {
             NodeKind::TokenClass(tk) => tk.to_string(),
             v => v.to_string(),
-        };
+        }
+    }
+
+    fn fmt_header(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind_name())?;
 
         if let Some(value) = &self.value {
-            println!("{}{}: {}", pad_str, kind, value);
+            write!(f, " {:?}", value)?;
+        }
+
+        write!(f, " @ {}:{}", self.loc.line, self.loc.column)
+    }
+
+    fn inner_fmt_debug_tree(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        padding: usize,
+    ) -> std::fmt::Result {
+        write!(f, "{:padding$}", "")?;
+        self.fmt_header(f)?;
+        writeln!(f)?;
+
+        for child in &self.children {
+            child.inner_fmt_debug_tree(f, padding + 2)?;
+        }
+
+        Ok(())
+    }
+
+    fn inner_fmt_tree(&self, f: &mut std::fmt::Formatter<'_>, padding: i32) -> std::fmt::Result {
+        let pad_str: String = (0..padding).map(|_| " ").collect();
+        let kind = self.kind_name();
+
+        if let Some(value) = &self.value {
+            writeln!(f, "{}{}: {}", pad_str, kind, value)?;
         } else {
-            println!("{}{}", pad_str, kind);
+            writeln!(f, "{}{}", pad_str, kind)?;
         }
 
         for child in &self.children {
-            child.inner_print_tree(padding + 2);
+            child.inner_fmt_tree(f, padding + 2)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Compact form: kind, value and line:column per node.
+// {:?} keeps a node on one line, {:#?} prints one node per line
+impl Debug for ParseNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return self.inner_fmt_debug_tree(f, 0);
         }
+
+        self.fmt_header(f)?;
+
+        if !self.children.is_empty() {
+            write!(f, " [")?;
+
+            for (index, child) in self.children.iter().enumerate() {
+                if index != 0 {
+                    write!(f, ", ")?;
+                }
+
+                write!(f, "{:?}", child)?;
+            }
+
+            write!(f, "]")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct VerboseParseNode<'a>(&'a ParseNode);
+
+impl Debug for VerboseParseNode<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let children: Vec<VerboseParseNode> = self.0.children.iter().map(|c| c.verbose()).collect();
+
+        f.debug_struct("ParseNode")
+            .field("loc", &self.0.loc)
+            .field("end_loc", &self.0.end_loc)
+            .field("kind", &self.0.kind)
+            .field("value", &self.0.value)
+            .field("children", &children)
+            .finish()
+    }
+}
+
+impl FmtDisplay for ParseNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner_fmt_tree(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(kind: TokenClass, value: Option<&str>, column: usize) -> ParseNode {
+        ParseNode {
+            loc: Loc::new(1, column),
+            end_loc: Loc::new(1, column + value.map_or(0, |v| v.len().saturating_sub(1))),
+            kind: kind.into(),
+            value: value.map(|v| v.to_owned()),
+            children: vec![],
+        }
+    }
+
+    fn statement() -> ParseNode {
+        let mut statement = ParseNode {
+            loc: Loc::default(),
+            end_loc: Loc::default(),
+            kind: NodeKind::FunctionCall,
+            value: None,
+            children: vec![],
+        };
+
+        statement.add_child(leaf(TokenClass::Identifier, Some("print"), 1));
+        statement.add_child(leaf(TokenClass::Semi, None, 6));
+
+        statement
+    }
+
+    #[test]
+    fn it_has_stable_node_kind_ids() {
+        let ids = [
+            (NodeKind::Block, "block"),
+            (NodeKind::Program, "program"),
+            (NodeKind::Expression, "expression"),
+            (NodeKind::IndexExpression, "index_expression"),
+            (NodeKind::Statement, "statement"),
+            (NodeKind::ForLoopStatement, "for_loop_statement"),
+            (NodeKind::ReturnStatement, "return_statement"),
+            (NodeKind::ControlFlowBlock, "control_flow_block"),
+            (NodeKind::ConditionStatement, "condition_statement"),
+            (NodeKind::AssignmentStatement, "assignment_statement"),
+            (NodeKind::VariableDeclaration, "variable_declaration"),
+            (NodeKind::BlockStatement, "block_statement"),
+            (NodeKind::Argument, "argument"),
+            (NodeKind::Arguments, "arguments"),
+            (NodeKind::FunctionCall, "function_call"),
+            (NodeKind::FunctionDefinition, "function_definition"),
+            (NodeKind::DocComment, "doc_comment"),
+            (NodeKind::TokenClass(TokenClass::LCurly), "left_curly"),
+        ];
+
+        for (kind, id) in ids {
+            assert_eq!(kind.id(), id);
+        }
+    }
+
+    #[test]
+    fn it_prints_a_compact_debug_on_one_line() {
+        assert_eq!(
+            format!("{:?}", statement()),
+            r#"FunctionCall @ 1:1 [Identifier "print" @ 1:1, Semi @ 1:6]"#
+        );
+    }
+
+    #[test]
+    fn it_prints_a_compact_debug_tree_with_the_alternate_flag() {
+        assert_eq!(
+            format!("{:#?}", statement()),
+            "FunctionCall @ 1:1\n  Identifier \"print\" @ 1:1\n  Semi @ 1:6\n"
+        );
+    }
+
+    #[test]
+    fn it_ends_where_its_last_child_ends() {
+        let statement = statement();
+
+        assert_eq!(statement.loc, Loc::new(1, 1));
+        assert_eq!(statement.end_loc, Loc::new(1, 6));
+    }
+
+    #[test]
+    fn it_keeps_the_verbose_debug_output() {
+        let node = leaf(TokenClass::Semi, None, 6);
+
+        assert_eq!(
+            format!("{:?}", node.verbose()),
+            "ParseNode { loc: Loc { line: 1, column: 6 }, end_loc: Loc { line: 1, column: 6 }, kind: TokenClass(Semi), value: None, children: [] }"
+        );
     }
 }
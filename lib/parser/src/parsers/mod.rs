@@ -1,5 +1,12 @@
-use crate::parse_node::ParseNode;
-use lexer::lexer::LexerError;
+use crate::parse_node::{Loc, ParseNode};
+use lexer::{
+    diagnostic::{CompilerError, Span},
+    lexer::LexerError,
+    position::LineNumber,
+    token::{Token, TokenClass},
+};
+use std::{collections::BTreeSet, fmt::Display};
+use strum::EnumProperty;
 use thiserror::Error as ThisError;
 
 mod push_down_automata_predictive_parser;
@@ -9,10 +16,197 @@ pub use recursive_descent_parser::RecursiveDescentParser;
 
 pub type ParserResult = Result<ParseNode, ParserError>;
 
-#[derive(ThisError, Debug)]
+// How many tokens the window keeps on each side of a parse error
+pub const TOKEN_WINDOW_SIZE: usize = 3;
+
+// The tokens around a parse error, so users can see what the parser actually saw.
+// Errors box it, so results of the parser stay small
+#[derive(Debug, Default)]
+pub struct TokenWindow {
+    pub previous: Vec<String>,
+    pub next: Vec<String>,
+    // where the token the parser failed on starts, None at the end of the file
+    pub loc: Option<Loc>,
+}
+
+impl Display for TokenWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "  previous tokens: [{}]", self.previous.join(", "))?;
+        write!(f, "  next tokens: [{}]", self.next.join(", "))
+    }
+}
+
+// What the parser would have accepted instead of the token it got. Kept sorted by token
+// class and without duplicates, so errors read the same whichever order the tokens were tried in
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExpectedTokens(BTreeSet<(TokenClass, String)>);
+
+impl ExpectedTokens {
+    pub fn of_classes<'a>(classes: impl IntoIterator<Item = &'a TokenClass>) -> Self {
+        Self(
+            classes
+                .into_iter()
+                .map(|class| (class.clone(), class.describe().to_owned()))
+                .collect(),
+        )
+    }
+
+    pub fn of_token(token: &Token) -> Self {
+        Self(BTreeSet::from([(token.to_token_class(), token.describe())]))
+    }
+
+    pub fn contains(&self, class: &TokenClass) -> bool {
+        self.0.iter().any(|(expected, _)| expected == class)
+    }
+}
+
+// e.g. `;` or one of: identifier, number, `(`
+impl Display for ExpectedTokens {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let descriptions: Vec<&str> = self
+            .0
+            .iter()
+            .map(|(_, description)| description.as_str())
+            .collect();
+
+        match &descriptions[..] {
+            [description] => write!(f, "{}", description),
+            descriptions => write!(f, "one of: {}", descriptions.join(", ")),
+        }
+    }
+}
+
+// An identifier of the failed statement that is likely a misspelled keyword
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordSuggestion {
+    pub word: String,
+    pub keyword: &'static str,
+}
+
+// e.g. did you mean `while` instead of `whle`?
+impl Display for KeywordSuggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "did you mean `{}` instead of `{}`?",
+            self.keyword, self.word
+        )
+    }
+}
+
+// How much of the source parse_with_recovery had to skip, for grading error quality
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RecoveryStats {
+    pub recoveries: usize,
+    pub skipped_tokens: usize,
+}
+
+// The program parsed around the broken statements, with an error per broken statement
+#[derive(Debug)]
+pub struct RecoveredParse {
+    pub tree: ParseNode,
+    pub errors: Vec<ParserError>,
+    pub stats: RecoveryStats,
+}
+
+// The props code is the stable code of the error, lexer errors keep their own code
+#[derive(ThisError, Debug, EnumProperty)]
 pub enum ParserError {
     #[error("Lexer has failed!")]
     LexerError(#[from] LexerError),
-    #[error("Unexpected token: {0} actual was: {1}!")]
-    UnexpectedToken(String, String),
+    #[strum(props(code = "P0001"))]
+    #[error("Unexpected token: {1}, expected {0}!\n{2}")]
+    UnexpectedToken(ExpectedTokens, String, Box<TokenWindow>),
+    #[strum(props(code = "P0002"))]
+    #[error("Unexpected end of file, expected {0}!\n{1}")]
+    UnexpectedEndOfFile(ExpectedTokens, Box<TokenWindow>),
+    #[strum(props(code = "P0003"))]
+    #[error("Type `{0}` can not be used as a value in a condition!\n{1}")]
+    TypeInCondition(String, Box<TokenWindow>),
+    #[strum(props(code = "P0004"))]
+    #[error("Parsing was cancelled!")]
+    Cancelled,
+    #[strum(props(code = "P0005"))]
+    #[error("Initializer of `{0}` can not contain {1}, it has to end with a `;`!\n{2}")]
+    InvalidInitializer(String, String, Box<TokenWindow>),
+    // an unexpected token in a statement with an identifier close to a keyword,
+    // only with LexerOptions::suggest_keywords
+    #[strum(props(code = "P0006"))]
+    #[error("Unexpected token: {1}, expected {0}, {2}\n{3}")]
+    MisspelledKeyword(ExpectedTokens, String, KeywordSuggestion, Box<TokenWindow>),
+}
+
+impl ParserError {
+    // The line of the token the error is about, None when there is no such token
+    pub fn line(&self) -> Option<LineNumber> {
+        match self {
+            Self::LexerError(error) => error.line(),
+            error => error.window()?.loc.as_ref().map(|loc| loc.line),
+        }
+    }
+
+    // The tokens around the error, None for errors that are not about a token
+    fn window(&self) -> Option<&TokenWindow> {
+        match self {
+            Self::UnexpectedToken(_, _, window)
+            | Self::UnexpectedEndOfFile(_, window)
+            | Self::TypeInCondition(_, window)
+            | Self::InvalidInitializer(_, _, window)
+            | Self::MisspelledKeyword(_, _, _, window) => Some(window),
+            Self::LexerError(_) | Self::Cancelled => None,
+        }
+    }
+}
+
+impl CompilerError for ParserError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::LexerError(error) => error.code(),
+            error => error
+                .get_str("code")
+                .expect("Every parser error should have a code!"),
+        }
+    }
+
+    fn span(&self) -> Option<Span> {
+        match self {
+            Self::LexerError(error) => error.span(),
+            error => error.window()?.loc.as_ref().map(Span::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_sorts_and_deduplicates_expected_tokens() {
+        let expected = ExpectedTokens::of_classes(&[
+            TokenClass::Lparen,
+            TokenClass::Number,
+            TokenClass::Identifier,
+            TokenClass::Number,
+        ]);
+
+        assert_eq!(expected.to_string(), "one of: identifier, number, `(`");
+    }
+
+    #[test]
+    fn it_renders_keyword_suggestions_as_a_question() {
+        let suggestion = KeywordSuggestion {
+            word: "whle".to_owned(),
+            keyword: "while",
+        };
+
+        assert_eq!(
+            suggestion.to_string(),
+            "did you mean `while` instead of `whle`?"
+        );
+    }
+
+    #[test]
+    fn it_names_a_single_expected_token_on_its_own() {
+        assert_eq!(ExpectedTokens::of_token(&Token::Semi).to_string(), "`;`");
+    }
 }
@@ -1,11 +1,11 @@
-use std::collections::HashMap;
-
 use lexer::lexer::Lexer;
 
+#[allow(dead_code)]
 struct PushDownAutomataPredictiveParser {
     lexer: Lexer,
 }
 
+#[allow(dead_code)]
 impl PushDownAutomataPredictiveParser {
     pub fn new(lexer: Lexer) -> Self {
         Self { lexer }
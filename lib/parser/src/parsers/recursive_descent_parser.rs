@@ -1,103 +1,217 @@
-use crate::parse_node::{Loc, NodeKind, ParseNode};
+use crate::{
+    cancellation::CancellationToken,
+    coverage::{Coverage, Production},
+    options::ParserOptions,
+    parse_node::{Loc, NodeKind, ParseNode},
+};
 use lexer::{
-    lexer::Lexer,
+    lexer::{Lexer, LexerError, TokenInfo},
+    metadata::TokenMetadata,
     operator::Operator,
     token::{Token, TokenClass},
 };
+use std::collections::VecDeque;
 
-use super::{ParserError, ParserResult};
+use super::{
+    ExpectedTokens, KeywordSuggestion, ParserError, ParserResult, RecoveredParse, RecoveryStats,
+    TokenWindow, TOKEN_WINDOW_SIZE,
+};
 
 pub struct RecursiveDescentParser {
     lexer: Lexer,
+    cancellation: Option<CancellationToken>,
+    // the last consumed tokens, shown in parse errors
+    history: VecDeque<String>,
+    consumed: usize,
+    // set while parsing the condition of an if, elif or while
+    in_condition: bool,
+    // the last identifier of the current statement that looks like a misspelled keyword
+    suggestion: Option<KeywordSuggestion>,
+    coverage: Option<Coverage>,
+    options: ParserOptions,
 }
 
 impl RecursiveDescentParser {
     pub fn new(lexer: Lexer) -> Self {
-        Self { lexer }
+        Self {
+            lexer,
+            cancellation: None,
+            history: VecDeque::with_capacity(TOKEN_WINDOW_SIZE),
+            consumed: 0,
+            in_condition: false,
+            suggestion: None,
+            coverage: None,
+            options: ParserOptions::default(),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn from_async_reader<R>(reader: R) -> Result<Self, ParserError>
+    where
+        R: futures::io::AsyncRead + Unpin,
+    {
+        Ok(Self::new(Lexer::from_async_reader(reader).await?))
+    }
+
+    // Parses a new document with this parser, reusing its lexer buffers and options
+    // and keeping its cancellation token and coverage
+    pub fn reset(&mut self, code: String) {
+        self.lexer.reset(code);
+        self.history.clear();
+        self.consumed = 0;
+        self.in_condition = false;
+        self.suggestion = None;
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+
+        self
+    }
+
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+
+        self
+    }
+
+    // Records which productions the parses take, see coverage()
+    pub fn with_coverage(mut self) -> Self {
+        self.coverage = Some(Coverage::default());
+
+        self
+    }
+
+    // The productions taken by every parse since with_coverage, None without it
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
+    fn cover(&mut self, production: Production) {
+        if let Some(coverage) = &mut self.coverage {
+            coverage.record(production);
+        }
     }
 }
 
 impl RecursiveDescentParser {
     fn eat(&mut self, token: &TokenClass) -> ParserResult {
-        let peeked = self.lexer.peek();
-        let mut node = None;
-        let mut actual_token = String::from("Unknown");
-
-        if let Some(token_info) = peeked {
-            actual_token = token_info.token.to_string();
-
-            if &token_info.token == token {
-                node = Some(ParseNode {
-                    loc: Loc {
-                        line: token_info.line,
-                        column: token_info.start_column,
-                    },
-                    value: token_info.token.extract_value(),
-                    kind: token.into(),
-                    children: vec![],
-                });
-            }
+        match self.try_eat(token)? {
+            Some(node) => Ok(node),
+            None => Err(self.unexpected_token(ExpectedTokens::of_classes([token]))),
         }
+    }
 
-        if let Some(node) = node {
-            self.lexer.next()?;
+    // Consumes the next token if it is of the given class, a mismatch is not an error
+    fn try_eat(&mut self, token: &TokenClass) -> Result<Option<ParseNode>, ParserError> {
+        match self.lexer.try_peek()? {
+            Some(token_info) if &token_info.token == token => {
+                let token_info = self.lexer.next()?;
+                let mut node = self.consume(token_info, token.into());
 
-            Ok(node)
-        } else {
-            Err(ParserError::UnexpectedToken(
-                token.to_string(),
-                actual_token,
-            ))
+                if *token == TokenClass::Identifier {
+                    self.eat_members(&mut node)?;
+                }
+
+                Ok(Some(node))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // V -> id(.id)*, the parts of a name like System.print make one identifier
+    fn eat_members(&mut self, identifier: &mut ParseNode) -> Result<(), ParserError> {
+        while self.try_eat(&TokenClass::Dot)?.is_some() {
+            let member = self.eat(&TokenClass::Identifier)?;
+
+            identifier.value = Some(format!(
+                "{}.{}",
+                identifier.value.as_deref().unwrap_or_default(),
+                member.value.as_deref().unwrap_or_default()
+            ));
+            identifier.end_loc = member.end_loc;
         }
+
+        Ok(())
     }
 
     fn eat_any_of(&mut self, tokens: &[TokenClass]) -> ParserResult {
         for token in tokens {
-            if let Ok(node) = self.eat(&token) {
+            if let Some(node) = self.try_eat(token)? {
                 return Ok(node);
             }
         }
 
-        let mut buffer = String::from("");
+        Err(self.unexpected_token(ExpectedTokens::of_classes(tokens)))
+    }
 
-        for token in tokens {
-            if buffer.len() != 0 {
-                buffer.push_str(" or ");
+    fn eat_exact(&mut self, token: &Token) -> Result<ParseNode, ParserError> {
+        match self.lexer.try_peek()? {
+            Some(token_info) if &token_info.token == token => {
+                let token_info = self.lexer.next()?;
+                let kind = token_info.token.to_token_class().into();
+
+                Ok(self.consume(token_info, kind))
             }
+            _ => Err(self.unexpected_token(ExpectedTokens::of_token(token))),
+        }
+    }
 
-            buffer.push_str(&token.to_string());
+    fn consume(&mut self, token_info: TokenInfo, kind: NodeKind) -> ParseNode {
+        if self.history.len() == TOKEN_WINDOW_SIZE {
+            self.history.pop_front();
         }
 
-        let mut actual_token = String::from("Unknown");
+        self.history.push_back(Self::describe(&token_info));
+        self.consumed += 1;
 
-        if let Some(token_info) = self.lexer.peek() {
-            actual_token = token_info.token.to_string();
+        if let Some(suggestion) = Self::keyword_suggestion(&token_info) {
+            self.suggestion = Some(suggestion);
         }
 
-        Err(ParserError::UnexpectedToken(buffer, actual_token))
+        ParseNode {
+            loc: Loc {
+                line: token_info.line,
+                column: token_info.start_column,
+            },
+            end_loc: Loc {
+                line: token_info.line,
+                column: token_info.end_column,
+            },
+            value: token_info.token.extract_value(),
+            kind,
+            children: vec![],
+        }
     }
 
-    fn eat_exact(&mut self, token: &Token) -> Result<ParseNode, ParserError> {
-        let token_info = self.lexer.next()?;
+    // The doc comment of the token the parser is at, as a node for the statement it starts
+    fn peek_doc(&mut self) -> Option<ParseNode> {
+        let token_info = self.lexer.peek()?;
+        let loc = Loc {
+            line: token_info.line,
+            column: token_info.start_column,
+        };
 
-        if &token_info.token == token {
-            Ok(ParseNode {
-                loc: Loc {
-                    line: token_info.line,
-                    column: token_info.start_column,
-                },
-                value: token_info.token.extract_value(),
-                kind: token_info.token.to_token_class().into(),
-                children: vec![],
-            })
-        } else {
-            Err(ParserError::UnexpectedToken(
-                token.to_string(),
-                token_info.token.to_string(),
-            ))
+        Some(ParseNode {
+            loc: loc.clone(),
+            end_loc: loc,
+            kind: NodeKind::DocComment,
+            value: Some(token_info.doc.clone()?),
+            children: vec![],
+        })
+    }
+
+    // Pushed without add_child, so the statement still ends where its code does
+    fn attach_doc(statement: &mut ParseNode, doc: Option<ParseNode>) {
+        if let Some(doc) = doc {
+            statement.children.push(doc);
         }
     }
 
+    fn is_at_end(&mut self) -> Result<bool, ParserError> {
+        Ok(self.lexer.try_peek()?.is_none())
+    }
+
     fn is_next_exact(&mut self, token: &Token) -> bool {
         if let Some(token_info) = self.lexer.peek() {
             &token_info.token == token
@@ -114,6 +228,16 @@ impl RecursiveDescentParser {
         }
     }
 
+    fn is_next_binary_operator(&mut self) -> bool {
+        matches!(
+            self.lexer.peek(),
+            Some(TokenInfo {
+                token: Token::Operator(operator),
+                ..
+            }) if operator.is_binary()
+        )
+    }
+
     fn is_next_exact_any_of(&mut self, tokens: &[Token]) -> bool {
         for token in tokens {
             if self.is_next_exact(token) {
@@ -121,7 +245,7 @@ impl RecursiveDescentParser {
             }
         }
 
-        return false;
+        false
     }
 
     fn is_next_any_of(&mut self, tokens: &[TokenClass]) -> bool {
@@ -131,38 +255,222 @@ impl RecursiveDescentParser {
             }
         }
 
-        return false;
+        false
     }
 }
 
 impl RecursiveDescentParser {
-    fn parse_expression(&mut self) -> ParserResult {
+    // A token of the window, with where it starts
+    fn describe(token_info: &TokenInfo) -> String {
+        format!(
+            "{}:{} {}",
+            token_info.line,
+            token_info.start_column,
+            token_info.token.describe()
+        )
+    }
+
+    fn keyword_suggestion(token_info: &TokenInfo) -> Option<KeywordSuggestion> {
+        match &token_info.metadata {
+            Some(TokenMetadata::Identifier(metadata)) => Some(KeywordSuggestion {
+                word: token_info.token.lexeme(),
+                keyword: metadata.suggestion,
+            }),
+            _ => None,
+        }
+    }
+
+    // A token the lexer could not make sense of, reported as the lexer error it is
+    // instead of as a token that does not fit
+    fn invalid_token(&mut self) -> Option<ParserError> {
+        match self.lexer.peek()? {
+            TokenInfo {
+                token: Token::Error(message),
+                line,
+                start_column,
+                ..
+            } => Some(ParserError::LexerError(LexerError::InvalidToken {
+                message: message.clone(),
+                line: *line,
+                column: *start_column,
+            })),
+            _ => None,
+        }
+    }
+
+    // The token the parser failed on is the likeliest typo, then the ones before it
+    fn unexpected_token(&mut self, expected: ExpectedTokens) -> ParserError {
+        if let Some(error) = self.invalid_token() {
+            return error;
+        }
+
+        let (actual, suggestion) = match self.lexer.peek() {
+            Some(token_info) => (
+                token_info.token.describe(),
+                Self::keyword_suggestion(token_info),
+            ),
+            None => (String::from("Unknown"), None),
+        };
+
+        match suggestion.or_else(|| self.suggestion.take()) {
+            Some(suggestion) => {
+                ParserError::MisspelledKeyword(expected, actual, suggestion, self.token_window())
+            }
+            None => ParserError::UnexpectedToken(expected, actual, self.token_window()),
+        }
+    }
+
+    fn unexpected_end_of_file(&mut self, expected: ExpectedTokens) -> ParserError {
+        ParserError::UnexpectedEndOfFile(expected, self.token_window())
+    }
+
+    fn invalid_initializer(&mut self, variable: String) -> ParserError {
+        let actual = match self.lexer.peek() {
+            Some(token_info) => token_info.token.describe(),
+            None => String::from("Unknown"),
+        };
+
+        ParserError::InvalidInitializer(variable, actual, self.token_window())
+    }
+
+    fn type_in_condition(&mut self) -> ParserError {
+        let type_name = match self.lexer.peek() {
+            Some(token_info) => token_info.token.lexeme(),
+            None => String::from("Unknown"),
+        };
+
+        ParserError::TypeInCondition(type_name, self.token_window())
+    }
+
+    // Lexes up to TOKEN_WINDOW_SIZE tokens ahead and goes back to where the parser stopped
+    fn token_window(&mut self) -> Box<TokenWindow> {
+        let mut next = vec![];
+        let mut loc = None;
+        let mut scanned = 0;
+
+        while next.len() < TOKEN_WINDOW_SIZE {
+            // every call leaves a checkpoint behind, even the failing ones
+            scanned += 1;
+
+            match self.lexer.next() {
+                Ok(token_info) => {
+                    if next.is_empty() {
+                        loc = Some(Loc {
+                            line: token_info.line,
+                            column: token_info.start_column,
+                        });
+                    }
+
+                    next.push(Self::describe(&token_info));
+                }
+                Err(_) => break,
+            }
+        }
+
+        for _ in 0..scanned {
+            if self.lexer.back().is_err() {
+                break;
+            }
+        }
+
+        Box::new(TokenWindow {
+            previous: self.history.iter().cloned().collect(),
+            next,
+            loc,
+        })
+    }
+}
+
+impl RecursiveDescentParser {
+    // Checked between statements, so a cancelled parse stops at the next boundary
+    fn check_cancelled(&self) -> Result<(), ParserError> {
+        match &self.cancellation {
+            Some(cancellation) if cancellation.is_cancelled() => Err(ParserError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl RecursiveDescentParser {
+    // The value an expression starts with and the minuses and nots before it, the
+    // indexes and operators after it are up to the expression
+    fn parse_operand(&mut self) -> ParserResult {
         let mut expression = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::Expression,
             value: None,
             children: vec![],
         };
+        let values = [
+            TokenClass::Identifier,
+            TokenClass::Boolean,
+            TokenClass::Number,
+            TokenClass::Literal,
+        ];
+
+        // a minus or a not only takes the operand right after it, -5 + b is (-5) + b
+        if self.is_next_exact_any_of(&[
+            Token::Operator(Operator::Minus),
+            Token::Operator(Operator::Not),
+        ]) {
+            self.cover(Production::NegatedExpression);
+            expression.add_child(self.eat(&TokenClass::Operator)?);
+            expression.add_child(self.parse_operand()?);
+
+            return Ok(expression);
+        }
 
         if self.is_next(&TokenClass::Lparen) {
+            self.cover(Production::ParenthesizedExpression);
             let l_paren = self.eat(&TokenClass::Lparen)?;
             expression.loc = l_paren.loc.clone();
 
             expression.add_child(l_paren);
             expression.add_child(self.parse_expression()?);
             expression.add_child(self.eat(&TokenClass::Rparen)?);
+        } else if self.in_condition && self.is_next(&TokenClass::Type) {
+            return Err(self.type_in_condition());
+        } else if self.is_next_any_of(&values) {
+            let value = self.eat_any_of(&values)?;
+
+            if value.kind == NodeKind::TokenClass(TokenClass::Identifier)
+                && self.is_next(&TokenClass::Lparen)
+            {
+                self.cover(Production::CallExpression);
+                expression.add_child(self.parse_call(value)?);
+            } else {
+                self.cover(Production::ValueExpression);
+                expression.add_child(value);
+            }
         } else {
-            expression.add_child(self.eat_any_of(&[
-                TokenClass::Identifier,
-                TokenClass::Boolean,
-                TokenClass::Number,
-                TokenClass::Literal,
-            ])?);
+            // a failing lexer is the real problem, not the token it could not produce
+            let at_end = self.lexer.try_peek()?.is_none();
+            let expected = ExpectedTokens::of_classes(values.iter().chain([&TokenClass::Lparen]));
+
+            if at_end {
+                return Err(self.unexpected_end_of_file(expected));
+            }
+
+            return Err(self.unexpected_token(expected));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_expression(&mut self) -> ParserResult {
+        let mut expression = self.parse_operand()?;
+
+        while self.is_next(&TokenClass::LBracket) {
+            self.cover(Production::IndexExpression);
+            expression.add_child(self.parse_index()?);
         }
 
         if self.is_next_exact(&Token::Operator(Operator::Increment)) {
+            self.cover(Production::IncrementExpression);
             expression.add_child(self.eat(&TokenClass::Operator)?);
-        } else if self.is_next(&TokenClass::Operator) {
+        } else if self.is_next_binary_operator() {
+            self.cover(Production::BinaryExpression);
             expression.add_child(self.eat(&TokenClass::Operator)?);
             expression.add_child(self.parse_expression()?);
         }
@@ -170,9 +478,46 @@ impl RecursiveDescentParser {
         Ok(expression)
     }
 
+    // s[i] is the character at i, s[a..b] the characters from a up to b.
+    // A slice is an index whose expression is a range
+    fn parse_index(&mut self) -> ParserResult {
+        let mut index = ParseNode {
+            loc: Loc::default(),
+            end_loc: Loc::default(),
+            kind: NodeKind::IndexExpression,
+            value: None,
+            children: vec![],
+        };
+
+        index.add_child(self.eat(&TokenClass::LBracket)?);
+        index.add_child(self.parse_expression()?);
+        index.add_child(self.eat(&TokenClass::RBracket)?);
+
+        Ok(index)
+    }
+
+    // An expression that decides a branch or a loop, types are not values there
+    fn parse_condition(&mut self) -> ParserResult {
+        self.in_condition = true;
+        let condition = self.parse_expression();
+        self.in_condition = false;
+
+        condition
+    }
+
     fn parse_block(&mut self) -> ParserResult {
+        self.parse_block_with(false)
+    }
+
+    // A function body can end with an expression without a `;`, the value it returns
+    fn parse_function_body(&mut self) -> ParserResult {
+        self.parse_block_with(true)
+    }
+
+    fn parse_block_with(&mut self, trailing_expression: bool) -> ParserResult {
         let mut block = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::Block,
             value: None,
             children: vec![],
@@ -180,8 +525,27 @@ impl RecursiveDescentParser {
 
         block.add_child(self.eat(&TokenClass::LCurly)?);
 
+        // an empty block goes straight to the closing curly
+        if self.is_next(&TokenClass::RCurly) {
+            self.cover(Production::EmptyBlock);
+        } else {
+            self.cover(Production::Block);
+        }
+
         while !self.is_next(&TokenClass::RCurly) {
-            block.add_child(self.parse_statement()?);
+            if self.is_at_end()? {
+                return Err(
+                    self.unexpected_end_of_file(ExpectedTokens::of_classes([&TokenClass::RCurly]))
+                );
+            }
+
+            self.check_cancelled()?;
+
+            if trailing_expression && !self.is_next_any_of(&Self::statement_starts()) {
+                block.add_child(self.parse_expression_statement()?);
+            } else {
+                block.add_child(self.parse_statement()?);
+            }
         }
 
         block.add_child(self.eat(&TokenClass::RCurly)?);
@@ -191,14 +555,15 @@ impl RecursiveDescentParser {
 
     fn parse_control_flow_block(&mut self) -> ParserResult {
         let mut block = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::ControlFlowBlock,
             value: None,
             children: vec![],
         };
 
         block.add_child(self.eat(&TokenClass::Lparen)?);
-        block.add_child(self.parse_expression()?);
+        block.add_child(self.parse_condition()?);
         block.add_child(self.eat(&TokenClass::Rparen)?);
         block.add_child(self.parse_block()?);
 
@@ -207,32 +572,66 @@ impl RecursiveDescentParser {
 
     fn parse_for_loop_statement(&mut self) -> ParserResult {
         let mut statement = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::ForLoopStatement,
             value: None,
             children: vec![],
         };
 
+        self.cover(Production::ForLoopStatement);
         statement.add_child(self.eat(&TokenClass::Keyword)?);
         statement.add_child(self.eat(&TokenClass::Lparen)?);
         statement.add_child(self.parse_assignment_statement()?);
-        statement.add_child(self.parse_expression()?);
+        statement.add_child(self.parse_condition()?);
         statement.add_child(self.eat(&TokenClass::Semi)?);
-        statement.add_child(self.parse_expression()?);
+        statement.add_child(self.parse_loop_update()?);
         statement.add_child(self.eat(&TokenClass::Rparen)?);
         statement.add_child(self.parse_block()?);
 
         Ok(statement)
     }
 
+    // The last clause of a for loop, an expression like i++ or a new value for
+    // a variable like i = next(i), which is an assignment without a type
+    fn parse_loop_update(&mut self) -> ParserResult {
+        let mut expression = self.parse_expression()?;
+
+        let is_variable = matches!(
+            &expression.children[..],
+            [variable] if variable.kind == NodeKind::TokenClass(TokenClass::Identifier)
+        );
+
+        if !is_variable || !self.is_next(&TokenClass::Assignment) {
+            return Ok(expression);
+        }
+
+        let mut statement = ParseNode {
+            loc: Loc::default(),
+            end_loc: Loc::default(),
+            kind: NodeKind::AssignmentStatement,
+            value: None,
+            children: vec![],
+        };
+
+        self.cover(Production::Reassignment);
+        statement.add_child(expression.children.remove(0));
+        statement.add_child(self.eat(&TokenClass::Assignment)?);
+        statement.add_child(self.parse_expression()?);
+
+        Ok(statement)
+    }
+
     fn parse_condition_statement(&mut self) -> ParserResult {
         let mut statement = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::ConditionStatement,
             value: None,
             children: vec![],
         };
 
+        self.cover(Production::ConditionStatement);
         statement.add_child(self.eat(&TokenClass::Keyword)?);
         statement.add_child(self.parse_control_flow_block()?);
 
@@ -241,42 +640,98 @@ impl RecursiveDescentParser {
 
     fn parse_assignment_statement(&mut self) -> ParserResult {
         let mut statement = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::AssignmentStatement,
             value: None,
             children: vec![],
         };
+        let doc = self.peek_doc();
 
-        statement.add_child(self.eat(&TokenClass::Keyword)?);
-        statement.add_child(self.eat(&TokenClass::Identifier)?);
+        statement.add_child(self.eat(&TokenClass::Type)?);
+
+        let variable = self.eat(&TokenClass::Identifier)?;
+        let name = variable.value.clone().unwrap_or_default();
+
+        statement.add_child(variable);
+
+        // without an initializer it only declares the variable
+        if self.is_next(&TokenClass::Semi) {
+            self.cover(Production::VariableDeclaration);
+            statement.kind = NodeKind::VariableDeclaration;
+            statement.add_child(self.eat(&TokenClass::Semi)?);
+            Self::attach_doc(&mut statement, doc);
+
+            return Ok(statement);
+        }
+
+        self.cover(Production::AssignmentStatement);
         statement.add_child(self.eat(&TokenClass::Assignment)?);
 
-        while !self.is_next(&TokenClass::Semi) {
-            statement.add_child(self.parse_expression()?);
+        // these start the next statement or end a block, parsing them as
+        // values would only lead to errors about the statements after them
+        let statement_starts = [
+            TokenClass::Type,
+            TokenClass::Keyword,
+            TokenClass::LCurly,
+            TokenClass::RCurly,
+        ];
+
+        if self.is_next_any_of(&statement_starts) {
+            return Err(self.invalid_initializer(name));
+        }
+
+        statement.add_child(self.parse_expression()?);
+
+        if self.is_next_any_of(&statement_starts) {
+            return Err(self.invalid_initializer(name));
         }
 
         statement.add_child(self.eat(&TokenClass::Semi)?);
+        Self::attach_doc(&mut statement, doc);
 
         Ok(statement)
     }
 
+    // Parameter and call argument lists may be empty and may end with a comma,
+    // so after an item comes a comma or the closing paren. Anything else is an
+    // error that names both
+    fn parse_list_separator(&mut self, list: &mut ParseNode) -> Result<(), ParserError> {
+        if !self.is_next(&TokenClass::Rparen) {
+            list.add_child(self.eat_any_of(&[TokenClass::Comma, TokenClass::Rparen])?);
+        }
+
+        Ok(())
+    }
+
     fn parse_argument(&mut self) -> ParserResult {
         let mut statement = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::Argument,
             value: None,
             children: vec![],
         };
 
-        statement.add_child(self.eat(&TokenClass::Keyword)?);
+        statement.add_child(self.eat(&TokenClass::Type)?);
         statement.add_child(self.eat(&TokenClass::Identifier)?);
 
+        // a default value, used when a call leaves the argument out
+        if self.is_next(&TokenClass::Assignment) {
+            self.cover(Production::DefaultArgument);
+            statement.add_child(self.eat(&TokenClass::Assignment)?);
+            statement.add_child(self.parse_expression()?);
+        } else {
+            self.cover(Production::RequiredArgument);
+        }
+
         Ok(statement)
     }
 
     fn parse_arguments(&mut self) -> ParserResult {
         let mut statement = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::Arguments,
             value: None,
             children: vec![],
@@ -286,11 +741,7 @@ impl RecursiveDescentParser {
 
         while !self.is_next(&TokenClass::Rparen) {
             statement.add_child(self.parse_argument()?);
-
-            // if the next character is not a ), then we have more arguments
-            if !self.is_next(&TokenClass::Rparen) {
-                statement.add_child(self.eat(&TokenClass::Comma)?);
-            }
+            self.parse_list_separator(&mut statement)?;
         }
 
         statement.add_child(self.eat(&TokenClass::Rparen)?);
@@ -300,30 +751,42 @@ impl RecursiveDescentParser {
 
     fn parse_function_definition(&mut self) -> ParserResult {
         let mut statement = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::FunctionDefinition,
             value: None,
             children: vec![],
         };
+        let doc = self.peek_doc();
+
+        if self.is_next_exact(&Token::Keyword("pure".to_owned())) {
+            self.cover(Production::PureFunctionDefinition);
+            statement.add_child(self.eat_exact(&Token::Keyword("pure".to_owned()))?);
+        } else {
+            self.cover(Production::FunctionDefinition);
+        }
 
         statement.add_child(self.eat_exact(&Token::Keyword("fn".to_owned()))?);
         statement.add_child(self.eat(&TokenClass::Identifier)?);
         statement.add_child(self.parse_arguments()?);
-        statement.add_child(self.eat_exact(&Token::Operator(Operator::Pointer))?);
-        statement.add_child(self.eat(&TokenClass::Keyword)?);
-        statement.add_child(self.parse_block()?);
+        statement.add_child(self.eat(&TokenClass::Arrow)?);
+        statement.add_child(self.eat(&TokenClass::Type)?);
+        statement.add_child(self.parse_function_body()?);
+        Self::attach_doc(&mut statement, doc);
 
         Ok(statement)
     }
 
     fn parse_return_statement(&mut self) -> ParserResult {
         let mut statement = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::ReturnStatement,
             value: None,
             children: vec![],
         };
 
+        self.cover(Production::ReturnStatement);
         statement.add_child(self.eat_exact(&Token::Keyword("return".to_owned()))?);
 
         while !self.is_next(&TokenClass::Semi) {
@@ -340,6 +803,10 @@ impl RecursiveDescentParser {
             Token::Keyword("if".to_owned()),
             Token::Keyword("while".to_owned()),
         ];
+        let function_definitions = [
+            Token::Keyword("fn".to_owned()),
+            Token::Keyword("pure".to_owned()),
+        ];
 
         match true {
             _ if self.is_next_exact_any_of(&conditional_statements) => {
@@ -348,7 +815,7 @@ impl RecursiveDescentParser {
             _ if self.is_next_exact(&Token::Keyword("for".to_owned())) => {
                 self.parse_for_loop_statement()
             }
-            _ if self.is_next_exact(&Token::Keyword("fn".to_owned())) => {
+            _ if self.is_next_exact_any_of(&function_definitions) => {
                 self.parse_function_definition()
             }
             _ if self.is_next_exact(&Token::Keyword("return".to_owned())) => {
@@ -358,25 +825,117 @@ impl RecursiveDescentParser {
         }
     }
 
+    // The arguments of a call to the already consumed callee, shared by
+    // call statements and calls inside of expressions
+    fn parse_call(&mut self, callee: ParseNode) -> ParserResult {
+        let mut call = ParseNode {
+            loc: Loc::default(),
+            end_loc: Loc::default(),
+            kind: NodeKind::FunctionCall,
+            value: None,
+            children: vec![],
+        };
+
+        call.add_child(callee);
+        call.add_child(self.eat(&TokenClass::Lparen)?);
+
+        while !self.is_next(&TokenClass::Rparen) {
+            call.add_child(self.parse_expression()?);
+            self.parse_list_separator(&mut call)?;
+        }
+
+        call.add_child(self.eat(&TokenClass::Rparen)?);
+
+        Ok(call)
+    }
+
     fn parse_function_call_statement(&mut self) -> ParserResult {
+        self.cover(Production::FunctionCall);
+
+        let callee = self.eat(&TokenClass::Identifier)?;
+        let mut statement = self.parse_call(callee)?;
+
+        statement.add_child(self.eat(&TokenClass::Semi)?);
+
+        Ok(statement)
+    }
+
+    fn parse_block_statement(&mut self) -> ParserResult {
         let mut statement = ParseNode {
-            loc: Loc { line: 1, column: 1 },
-            kind: NodeKind::FunctionCall,
+            loc: Loc::default(),
+            end_loc: Loc::default(),
+            kind: NodeKind::BlockStatement,
             value: None,
             children: vec![],
         };
 
-        statement.add_child(self.eat(&TokenClass::Identifier)?);
-        statement.add_child(self.eat(&TokenClass::Lparen)?);
-        statement.add_child(self.parse_expression()?);
-        statement.add_child(self.eat(&TokenClass::Rparen)?);
+        self.cover(Production::BlockStatement);
+        statement.add_child(self.parse_block()?);
+
+        Ok(statement)
+    }
+
+    // The token classes statements other than calls start with
+    fn statement_starts() -> [TokenClass; 3] {
+        [TokenClass::LCurly, TokenClass::Type, TokenClass::Keyword]
+    }
+
+    // Either a call statement or, right before the closing curly of a function body,
+    // the value the function returns, which is a return statement without `return` and `;`
+    fn parse_expression_statement(&mut self) -> ParserResult {
+        self.suggestion = None;
+
+        let mut expression = self.parse_expression()?;
+
+        if self.is_next(&TokenClass::RCurly) {
+            let mut statement = ParseNode {
+                loc: Loc::default(),
+                end_loc: Loc::default(),
+                kind: NodeKind::ReturnStatement,
+                value: None,
+                children: vec![],
+            };
+
+            self.cover(Production::ImplicitReturn);
+            statement.add_child(expression);
+
+            return Ok(statement);
+        }
+
+        let is_call = matches!(
+            &expression.children[..],
+            [call] if call.kind == NodeKind::FunctionCall
+        );
+
+        if !is_call || !self.is_next(&TokenClass::Semi) {
+            self.lexer.try_peek()?;
+
+            let expected = if is_call {
+                ExpectedTokens::of_classes([&TokenClass::Semi, &TokenClass::RCurly])
+            } else {
+                ExpectedTokens::of_classes([&TokenClass::RCurly])
+            };
+
+            return Err(self.unexpected_token(expected));
+        }
+
+        self.cover(Production::FunctionCall);
+
+        let mut statement = expression.children.remove(0);
+
         statement.add_child(self.eat(&TokenClass::Semi)?);
 
         Ok(statement)
     }
 
     fn parse_statement(&mut self) -> ParserResult {
-        if self.is_next(&TokenClass::Keyword) {
+        self.suggestion = None;
+
+        if self.is_next(&TokenClass::LCurly) {
+            self.parse_block_statement()
+        } else if self.is_next(&TokenClass::Type) {
+            self.parse_assignment_statement()
+        } else if self.is_next(&TokenClass::Keyword) {
             self.parse_keyword_statement()
         } else {
             self.parse_function_call_statement()
@@ -385,13 +944,16 @@ impl RecursiveDescentParser {
 
     fn parse_program(&mut self) -> ParserResult {
         let mut root = ParseNode {
-            loc: Loc { line: 1, column: 1 },
+            loc: Loc::default(),
+            end_loc: Loc::default(),
             kind: NodeKind::Program,
             value: None,
             children: vec![],
         };
 
-        while let Some(_) = self.lexer.peek() {
+        // empty or whitespace only input results in an empty program
+        while !self.is_at_end()? {
+            self.check_cancelled()?;
             root.add_child(self.parse_statement()?);
         }
 
@@ -399,10 +961,144 @@ impl RecursiveDescentParser {
     }
 }
 
+impl RecursiveDescentParser {
+    // Errors that only affect the statement they are in, a failing buffer or a
+    // cancelled parse would fail the same way on every following statement
+    fn is_recoverable(error: &ParserError) -> bool {
+        matches!(
+            error,
+            ParserError::UnexpectedToken(..)
+                | ParserError::MisspelledKeyword(..)
+                | ParserError::UnexpectedEndOfFile(..)
+                | ParserError::TypeInCondition(..)
+                | ParserError::InvalidInitializer(..)
+                | ParserError::LexerError(LexerError::NumberOutOfRange { .. })
+                | ParserError::LexerError(LexerError::OperatorAtEndOfFile { .. })
+                | ParserError::LexerError(LexerError::InvalidToken { .. })
+        )
+    }
+
+    // Skips to the next synchronization token, returns how many tokens were skipped.
+    // Blocks are skipped as a whole, so their statements and closing curly do not
+    // produce errors of their own
+    fn synchronize(&mut self, consumed_before: usize) -> Result<usize, ParserError> {
+        let mut skipped = 0;
+        let mut depth = 0;
+        // always make progress, a statement that fails on its first token would fail forever
+        let mut must_skip = self.consumed == consumed_before;
+
+        while let Some(token_info) = self.lexer.try_peek()? {
+            let token = &token_info.token;
+
+            if !must_skip && depth == 0 && self.options.synchronization_tokens.contains(token) {
+                if token == &Token::Semi || token == &Token::RCurly {
+                    self.lexer.next()?;
+                }
+
+                return Ok(skipped);
+            }
+
+            let closes_block = match token {
+                Token::LCurly => {
+                    depth += 1;
+
+                    false
+                }
+                Token::RCurly if depth > 0 => {
+                    depth -= 1;
+
+                    depth == 0
+                }
+                _ => false,
+            };
+
+            self.lexer.next()?;
+            skipped += 1;
+            must_skip = false;
+
+            if closes_block {
+                return Ok(skipped);
+            }
+        }
+
+        Ok(skipped)
+    }
+
+    // Parses the whole program, skipping broken statements instead of stopping at the
+    // first error. Cancellation and reaching the end of the file still stop the parse
+    pub fn parse_with_recovery(&mut self) -> RecoveredParse {
+        let mut tree = ParseNode {
+            loc: Loc::default(),
+            end_loc: Loc::default(),
+            kind: NodeKind::Program,
+            value: None,
+            children: vec![],
+        };
+        let mut errors = vec![];
+        let mut stats = RecoveryStats::default();
+
+        loop {
+            let consumed_before = self.consumed;
+            let result = self.check_cancelled().and_then(|_| self.is_at_end());
+
+            let error = match result {
+                Ok(true) => break,
+                Ok(false) => match self.parse_statement() {
+                    Ok(statement) => {
+                        tree.add_child(statement);
+
+                        continue;
+                    }
+                    Err(error) => error,
+                },
+                Err(error) => error,
+            };
+
+            let recoverable = Self::is_recoverable(&error);
+            errors.push(error);
+
+            if !recoverable {
+                break;
+            }
+
+            stats.recoveries += 1;
+
+            match self.synchronize(consumed_before) {
+                Ok(skipped) => stats.skipped_tokens += skipped,
+                Err(error) => {
+                    let recoverable = Self::is_recoverable(&error);
+                    errors.push(error);
+
+                    if !recoverable {
+                        break;
+                    }
+                }
+            }
+        }
+
+        RecoveredParse {
+            tree,
+            errors,
+            stats,
+        }
+    }
+}
+
 impl RecursiveDescentParser {
     // create entire parse tree for now
     // TODO: make it streamable, we parse one at a time, for performance reasons
     pub fn parse(&mut self) -> ParserResult {
         self.parse_program()
     }
+
+    // The next top level statement, None at the end of the file. Lets incremental
+    // reparsing stop once its statements line up with the previous tree again
+    pub(crate) fn next_statement(&mut self) -> Result<Option<ParseNode>, ParserError> {
+        if self.is_at_end()? {
+            return Ok(None);
+        }
+
+        self.check_cancelled()?;
+        self.parse_statement().map(Some)
+    }
 }
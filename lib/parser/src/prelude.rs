@@ -0,0 +1,10 @@
+// What most users of the parser need, e.g. use parser::prelude::*;
+pub use crate::{
+    parse_node::{Loc, NodeKind, ParseNode},
+    parsers::{ParserError, RecoveredParse, RecursiveDescentParser},
+    visitor::{NodeCounter, Visitor},
+};
+pub use lexer::{
+    lexer::{Lexer, LexerError, TokenInfo},
+    token::{Token, TokenClass},
+};
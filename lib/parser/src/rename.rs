@@ -0,0 +1,207 @@
+use lexer::{
+    diagnostic::{CompilerError, Span},
+    token::Token,
+};
+use strum::EnumProperty;
+use thiserror::Error as ThisError;
+
+use crate::{
+    parse_node::{Loc, ParseNode},
+    semantics::{Binding, Symbols},
+};
+
+// The props code is the stable code of the error, see CompilerError
+#[derive(ThisError, Debug, PartialEq, Eq, EnumProperty)]
+pub enum RenameError {
+    #[strum(props(code = "R0001"))]
+    #[error("{0} can not be used as a name!")]
+    InvalidName(String),
+    #[strum(props(code = "R0002"))]
+    #[error("There is no name to rename at {}:{}!", .0.line, .0.column)]
+    NoSymbol(Loc),
+    #[strum(props(code = "R0003"))]
+    #[error("{name} at {}:{} is not declared in the program, it can not be renamed!", .loc.line, .loc.column)]
+    UndeclaredSymbol { name: String, loc: Loc },
+    #[strum(props(code = "R0004"))]
+    #[error("Renaming {name} to {new_name} changes what the name at {}:{} refers to!", .loc.line, .loc.column)]
+    Conflict {
+        name: String,
+        new_name: String,
+        loc: Loc,
+    },
+}
+
+impl CompilerError for RenameError {
+    fn code(&self) -> &'static str {
+        self.get_str("code")
+            .expect("Every rename error should have a code!")
+    }
+
+    fn span(&self) -> Option<Span> {
+        match self {
+            Self::InvalidName(_) => None,
+            Self::NoSymbol(loc)
+            | Self::UndeclaredSymbol { loc, .. }
+            | Self::Conflict { loc, .. } => Some(loc.into()),
+        }
+    }
+}
+
+// Replaces the source from start to end, both inclusive, with new_text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub start: Loc,
+    pub end: Loc,
+    pub new_text: String,
+}
+
+// A name a variable or function can be declared with, dotted names are only for built-ins
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    matches!(Token::from(name.to_owned()), Token::Identifier(_)) && !name.contains('.')
+}
+
+// The edits that rename the variable or function named at loc, its declaration and
+// every use of it. Fails instead of renaming when the new name would be captured by
+// another declaration or would capture a name that refers to something else now
+pub fn rename(program: &ParseNode, at: &Loc, new_name: &str) -> Result<Vec<TextEdit>, RenameError> {
+    if !is_valid_name(new_name) {
+        return Err(RenameError::InvalidName(new_name.to_owned()));
+    }
+
+    let symbols = Symbols::of(program);
+    let occurrence = symbols
+        .at(at)
+        .ok_or_else(|| RenameError::NoSymbol(at.clone()))?;
+
+    if let Binding::Free(name) = &occurrence.binding {
+        return Err(RenameError::UndeclaredSymbol {
+            name: name.clone(),
+            loc: occurrence.loc.clone(),
+        });
+    }
+
+    let references: Vec<Loc> = symbols
+        .references(&occurrence.binding)
+        .map(|reference| reference.loc.clone())
+        .collect();
+    let renamed = Symbols::with_renamed(program, &references, new_name);
+
+    // every name has to refer to the same declaration as before
+    for (before, after) in symbols.iter().zip(renamed.iter()) {
+        if before.binding != after.binding {
+            return Err(RenameError::Conflict {
+                name: occurrence.name.clone(),
+                new_name: new_name.to_owned(),
+                loc: before.loc.clone(),
+            });
+        }
+    }
+
+    Ok(symbols
+        .references(&occurrence.binding)
+        .map(|reference| TextEdit {
+            start: reference.loc.clone(),
+            end: reference.end_loc.clone(),
+            new_text: new_name.to_owned(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    fn edited_locs(edits: &[TextEdit]) -> Vec<(Loc, Loc)> {
+        edits
+            .iter()
+            .map(|edit| (edit.start.clone(), edit.end.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn it_renames_a_variable_and_its_uses() {
+        let program = parse("int total = 1;\nSystem.print(total);\n{\n    int total = 2;\n}");
+        let edits = rename(&program, &Loc::new(2, 16), "sum").unwrap();
+
+        assert_eq!(
+            edited_locs(&edits),
+            vec![
+                (Loc::new(1, 5), Loc::new(1, 9)),
+                (Loc::new(2, 14), Loc::new(2, 18)),
+            ]
+        );
+        assert!(edits.iter().all(|edit| edit.new_text == "sum"));
+    }
+
+    #[test]
+    fn it_renames_functions_called_before_their_definition() {
+        let program =
+            parse("fn main() -> int {\n    return add(1);\n}\nfn add(int a) -> int { a }");
+        let edits = rename(&program, &Loc::new(4, 4), "plus").unwrap();
+
+        assert_eq!(
+            edited_locs(&edits),
+            vec![
+                (Loc::new(2, 12), Loc::new(2, 14)),
+                (Loc::new(4, 4), Loc::new(4, 6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_refuses_renames_that_change_what_names_refer_to() {
+        let program = parse(
+            "int a = 1;\nint b = 2;\nfn f(int c) -> int {\n    int d = a + c;\n    return d;\n}",
+        );
+
+        // a use of a would be captured by the parameter c
+        assert_eq!(
+            rename(&program, &Loc::new(1, 5), "c"),
+            Err(RenameError::Conflict {
+                name: "a".to_owned(),
+                new_name: "c".to_owned(),
+                loc: Loc::new(4, 13),
+            })
+        );
+        // the parameter would capture the use of a
+        assert!(matches!(
+            rename(&program, &Loc::new(3, 10), "a"),
+            Err(RenameError::Conflict { .. })
+        ));
+        // declaring b again in the same scope would merge the variables
+        assert!(matches!(
+            rename(&program, &Loc::new(1, 5), "b"),
+            Err(RenameError::Conflict { .. })
+        ));
+        assert!(rename(&program, &Loc::new(1, 5), "e").is_ok());
+    }
+
+    #[test]
+    fn it_refuses_invalid_names_and_undeclared_symbols() {
+        let program = parse("System.print(x);");
+
+        assert_eq!(
+            rename(&program, &Loc::new(1, 1), "while"),
+            Err(RenameError::InvalidName("while".to_owned()))
+        );
+        assert_eq!(
+            rename(&program, &Loc::new(1, 14), "y"),
+            Err(RenameError::UndeclaredSymbol {
+                name: "x".to_owned(),
+                loc: Loc::new(1, 14),
+            })
+        );
+        assert_eq!(
+            rename(&program, &Loc::new(3, 1), "y"),
+            Err(RenameError::NoSymbol(Loc::new(3, 1)))
+        );
+    }
+}
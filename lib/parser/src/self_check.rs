@@ -0,0 +1,170 @@
+use crate::{
+    grammar::{Grammar, GrammarError, NonTerminal, ProductionRuleSymbol},
+    parse_node::NodeKind,
+};
+use lexer::{
+    diagnostic::{CompilerError, Span},
+    lexer::Lexer,
+    operator::Operator,
+    token::{Token, TokenClass, KEYWORDS, TYPES},
+};
+use std::collections::HashSet;
+use strum::{EnumProperty, IntoEnumIterator};
+use thiserror::Error as ThisError;
+
+// Invariants the compiler relies on without the type system enforcing them.
+// The props code is the stable code of the error, see CompilerError
+#[derive(ThisError, Debug, PartialEq, Eq, EnumProperty)]
+pub enum InvariantError {
+    #[strum(props(code = "I0001"))]
+    #[error("Grammar is incomplete: {0}")]
+    Grammar(GrammarError),
+    #[strum(props(code = "I0002"))]
+    #[error("Operator {0} is not scanned as itself by the lexer!")]
+    UnscannedOperator(Operator),
+    #[strum(props(code = "I0003"))]
+    #[error("Token class {0} is not mapped to a node kind of its own!")]
+    UnmappedTokenClass(TokenClass),
+    #[strum(props(code = "I0004"))]
+    #[error("Non terminal {0} derives tokens of another class than its node kind!")]
+    MismatchedNonTerminal(NonTerminal),
+    #[strum(props(code = "I0005"))]
+    #[error("Word {0} is not lexed as a {1}!")]
+    UnsyncedWord(String, TokenClass),
+}
+
+impl CompilerError for InvariantError {
+    fn code(&self) -> &'static str {
+        self.get_str("code")
+            .expect("Every invariant error should have a code!")
+    }
+
+    fn span(&self) -> Option<Span> {
+        None
+    }
+}
+
+// The single token of code, None when it lexes into something else
+fn lex_single(code: &str) -> Option<Token> {
+    let mut lexer = Lexer::new(code.to_owned());
+    let token = lexer.next().ok()?.token;
+
+    match lexer.next() {
+        Ok(info) if info.token == Token::Semi => Some(token),
+        _ => None,
+    }
+}
+
+fn check_grammar(errors: &mut Vec<InvariantError>) {
+    errors.extend(
+        Grammar::new()
+            .check()
+            .into_iter()
+            .map(InvariantError::Grammar),
+    );
+}
+
+// Every operator has to come back out of the lexer when it stands between two values
+fn check_operators(errors: &mut Vec<InvariantError>) {
+    for operator in Operator::iter() {
+        let mut lexer = Lexer::new(format!("a {} b;", operator));
+        let scanned = lexer
+            .next()
+            .and_then(|_| lexer.next())
+            .ok()
+            .map(|info| info.token);
+
+        if !Operator::is_operator(&operator.to_string())
+            || scanned != Some(Token::Operator(operator))
+        {
+            errors.push(InvariantError::UnscannedOperator(operator));
+        }
+    }
+}
+
+fn check_token_classes(errors: &mut Vec<InvariantError>) {
+    let mut ids = HashSet::new();
+
+    for token_class in TokenClass::iter() {
+        let kind = NodeKind::from(&token_class);
+
+        if kind.id() != token_class.id() || !ids.insert(token_class.id()) {
+            errors.push(InvariantError::UnmappedTokenClass(token_class));
+        }
+    }
+
+    let grammar = Grammar::new();
+
+    for (non_terminal, rules) in grammar.iter() {
+        let Some(NodeKind::TokenClass(token_class)) = non_terminal.node_kind() else {
+            continue;
+        };
+
+        let derives_its_class = rules.iter().all(|rule| match &rule[..] {
+            [ProductionRuleSymbol::Token(token)] => token.to_token_class() == token_class,
+            [ProductionRuleSymbol::TokenClass(class)] => *class == token_class,
+            _ => false,
+        });
+
+        if !derives_its_class {
+            errors.push(InvariantError::MismatchedNonTerminal(*non_terminal));
+        }
+    }
+}
+
+// The keyword and type lists drive both the lexer and the grammar, so they have to agree
+fn check_words(errors: &mut Vec<InvariantError>) {
+    let words = KEYWORDS
+        .iter()
+        .map(|word| (word, TokenClass::Keyword))
+        .chain(TYPES.iter().map(|word| (word, TokenClass::Type)));
+
+    for (word, token_class) in words {
+        let lexed = lex_single(&format!("{};", word)).map(|token| token.to_token_class());
+
+        if lexed != Some(token_class.clone()) {
+            errors.push(InvariantError::UnsyncedWord(word.to_string(), token_class));
+        }
+    }
+}
+
+// Runs every check, an empty list means the compiler is consistent with itself
+pub fn self_check() -> Vec<InvariantError> {
+    let mut errors = vec![];
+
+    check_grammar(&mut errors);
+    check_operators(&mut errors);
+    check_token_classes(&mut errors);
+    check_words(&mut errors);
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_compiler_consistent() {
+        assert_eq!(self_check(), vec![]);
+    }
+
+    #[test]
+    fn it_lexes_single_tokens() {
+        assert_eq!(
+            lex_single("while;"),
+            Some(Token::Keyword("while".to_owned()))
+        );
+        assert_eq!(lex_single("a b;"), None);
+    }
+
+    #[test]
+    fn it_renders_invariant_errors_with_their_code() {
+        let error = InvariantError::UnsyncedWord("elif".to_owned(), TokenClass::Keyword);
+
+        assert_eq!(
+            error.render(),
+            "error[I0005]: Word elif is not lexed as a Keyword!"
+        );
+    }
+}
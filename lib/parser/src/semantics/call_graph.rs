@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::parse_node::{Loc, NodeKind, ParseNode};
+
+use super::{function_name, SemanticWarning};
+
+pub const ENTRY_FUNCTION: &str = "main";
+
+// Which functions call which, built from the definitions and calls of a program.
+// Calls outside of any function run when the program starts, like main does
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    // defined functions and where they are defined, in definition order
+    functions: Vec<(String, Loc)>,
+    calls: BTreeMap<String, BTreeSet<String>>,
+    top_level_calls: BTreeSet<String>,
+}
+
+impl CallGraph {
+    pub fn of(program: &ParseNode) -> Self {
+        let mut graph = Self::default();
+
+        graph.collect(program, None);
+
+        graph
+    }
+
+    fn collect<'a>(&mut self, node: &'a ParseNode, caller: Option<&'a str>) {
+        let mut caller = caller;
+
+        match node.kind {
+            NodeKind::FunctionDefinition => {
+                if let Some(name) = function_name(node) {
+                    self.functions.push((name.to_owned(), node.loc.clone()));
+                    self.calls.entry(name.to_owned()).or_default();
+                    caller = Some(name);
+                }
+            }
+            NodeKind::FunctionCall => {
+                if let Some(callee) = function_name(node) {
+                    let callees = match caller {
+                        Some(caller) => self.calls.entry(caller.to_owned()).or_default(),
+                        None => &mut self.top_level_calls,
+                    };
+
+                    callees.insert(callee.to_owned());
+                }
+            }
+            _ => {}
+        }
+
+        for child in &node.children {
+            self.collect(child, caller);
+        }
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = &str> {
+        self.functions.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn callees(&self, function: &str) -> impl Iterator<Item = &str> {
+        self.calls
+            .get(function)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    // Functions main or the top level code end up calling, directly or not
+    pub fn reachable(&self) -> BTreeSet<&str> {
+        let mut reachable = BTreeSet::new();
+        let mut to_visit: Vec<&str> = self.top_level_calls.iter().map(String::as_str).collect();
+
+        to_visit.push(ENTRY_FUNCTION);
+
+        while let Some(function) = to_visit.pop() {
+            if reachable.insert(function) {
+                to_visit.extend(self.callees(function));
+            }
+        }
+
+        reachable
+    }
+
+    pub fn warnings(&self) -> Vec<SemanticWarning> {
+        let reachable = self.reachable();
+
+        self.functions
+            .iter()
+            .filter(|(name, _)| !reachable.contains(name.as_str()))
+            .map(|(name, loc)| SemanticWarning::UnreachableFunction {
+                function: name.clone(),
+                loc: loc.clone(),
+            })
+            .collect()
+    }
+
+    // Graphviz source, callees that are not defined in the program are drawn dashed
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph calls {\n");
+        let defined: BTreeSet<&str> = self.functions().collect();
+        let mut undefined = BTreeSet::new();
+
+        for function in self.functions() {
+            dot.push_str(&format!("  \"{}\";\n", function));
+        }
+
+        let top_level = self
+            .top_level_calls
+            .iter()
+            .map(|callee| ("<program>", callee));
+        let calls = self.functions().flat_map(|caller| {
+            self.calls[caller]
+                .iter()
+                .map(move |callee| (caller, callee))
+        });
+
+        for (caller, callee) in top_level.chain(calls) {
+            if !defined.contains(callee.as_str()) {
+                undefined.insert(callee.as_str());
+            }
+
+            dot.push_str(&format!("  \"{}\" -> \"{}\";\n", caller, callee));
+        }
+
+        for callee in undefined {
+            dot.push_str(&format!("  \"{}\" [style=dashed];\n", callee));
+        }
+
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::{diagnostic::CompilerError, lexer::Lexer};
+
+    fn graph(code: &str) -> CallGraph {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        CallGraph::of(&parser.parse().unwrap())
+    }
+
+    #[test]
+    fn it_warns_about_functions_main_never_calls() {
+        let graph = graph(
+            "fn helper() -> int { return 1; }\n\
+             fn unused() -> int { helper(); return 2; }\n\
+             fn main() -> int { helper(); return 0; }",
+        );
+
+        assert_eq!(graph.callees("unused").collect::<Vec<_>>(), vec!["helper"]);
+        assert!(matches!(
+            &graph.warnings()[..],
+            [SemanticWarning::UnreachableFunction { function, loc }]
+                if function == "unused" && loc.line == 2
+        ));
+    }
+
+    #[test]
+    fn it_reports_unreachable_functions_as_warnings() {
+        let graph = graph("fn unused() -> int { return 2; }");
+
+        assert_eq!(
+            graph.warnings()[0].render(),
+            "warning[W0001]: Function unused at 1:1 is never called from main!"
+        );
+    }
+
+    #[test]
+    fn it_treats_top_level_calls_as_reachable() {
+        let graph = graph("fn greet() -> int { System.print(1); return 0; }\ngreet();");
+
+        assert!(graph.warnings().is_empty());
+        assert!(graph.reachable().contains("System.print"));
+    }
+
+    #[test]
+    fn it_exports_the_graph_as_dot() {
+        let graph = graph("fn main() -> int { System.print(1); return 0; }");
+
+        assert_eq!(
+            graph.to_dot(),
+            "digraph calls {\n  \"main\";\n  \"main\" -> \"System.print\";\n  \"System.print\" [style=dashed];\n}\n"
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use lexer::{number::NumberForm, token::TokenClass};
+use thiserror::Error as ThisError;
+
+use crate::parse_node::{Loc, NodeKind, ParseNode};
+
+// The value of a constant expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+}
+
+impl Constant {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Int(_) => "int",
+            Self::Float(_) => "float",
+            Self::Bool(_) => "bool",
+            Self::String(_) => "string",
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Int(value) => Some(*value as f64),
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+// Why an expression has no constant value, at the node that stopped the evaluation.
+// Reads as the end of a sentence about the expression, see SemanticError::InvalidDefault
+#[derive(ThisError, Debug, Clone, PartialEq)]
+pub enum ConstEvalError {
+    #[error("is not a constant")]
+    NotConstant { loc: Loc },
+    #[error("overflows an int with {operator}")]
+    Overflow { operator: String, loc: Loc },
+    #[error("divides by zero")]
+    DivisionByZero { loc: Loc },
+    #[error("applies {operator} to {}", .operands.join(" and "))]
+    TypeMismatch {
+        operator: String,
+        operands: Vec<&'static str>,
+        loc: Loc,
+    },
+}
+
+impl ConstEvalError {
+    pub fn loc(&self) -> &Loc {
+        match self {
+            Self::NotConstant { loc }
+            | Self::Overflow { loc, .. }
+            | Self::DivisionByZero { loc }
+            | Self::TypeMismatch { loc, .. } => loc,
+        }
+    }
+}
+
+fn not_constant(node: &ParseNode) -> ConstEvalError {
+    ConstEvalError::NotConstant {
+        loc: node.loc.clone(),
+    }
+}
+
+fn literal(node: &ParseNode) -> Result<Constant, ConstEvalError> {
+    let value = node.value.as_deref().unwrap_or_default();
+
+    match &node.kind {
+        NodeKind::TokenClass(TokenClass::Boolean) => Ok(Constant::Bool(value == "true")),
+        NodeKind::TokenClass(TokenClass::Literal) => Ok(Constant::String(value.to_owned())),
+        // the lexer only makes numbers that fit
+        NodeKind::TokenClass(TokenClass::Number) => match NumberForm::of(value) {
+            Some(NumberForm::Float) => value.parse().map(Constant::Float).ok(),
+            Some(form) => form.value(value).map(Constant::Int),
+            None => None,
+        }
+        .ok_or_else(|| not_constant(node)),
+        _ => Err(not_constant(node)),
+    }
+}
+
+fn unary(operator: &ParseNode, operand: Constant) -> Result<Constant, ConstEvalError> {
+    let symbol = operator.value.as_deref().unwrap_or_default();
+
+    match (symbol, operand) {
+        ("!", Constant::Bool(value)) => Ok(Constant::Bool(!value)),
+        ("-", Constant::Float(value)) => Ok(Constant::Float(-value)),
+        ("-", Constant::Int(value)) => {
+            value
+                .checked_neg()
+                .map(Constant::Int)
+                .ok_or_else(|| ConstEvalError::Overflow {
+                    operator: symbol.to_owned(),
+                    loc: operator.loc.clone(),
+                })
+        }
+        (_, operand) => Err(ConstEvalError::TypeMismatch {
+            operator: symbol.to_owned(),
+            operands: vec![operand.type_name()],
+            loc: operator.loc.clone(),
+        }),
+    }
+}
+
+fn integer(
+    operator: &ParseNode,
+    left: i64,
+    right: i64,
+) -> Option<Result<Constant, ConstEvalError>> {
+    let symbol = operator.value.as_deref().unwrap_or_default();
+    let result = match symbol {
+        "+" => left.checked_add(right),
+        "-" => left.checked_sub(right),
+        "*" => left.checked_mul(right),
+        "/" if right == 0 => {
+            return Some(Err(ConstEvalError::DivisionByZero {
+                loc: operator.loc.clone(),
+            }))
+        }
+        // rounds towards zero, i64::MIN / -1 is the one that overflows
+        "/" => left.checked_div(right),
+        "==" => return Some(Ok(Constant::Bool(left == right))),
+        "!=" => return Some(Ok(Constant::Bool(left != right))),
+        "<" => return Some(Ok(Constant::Bool(left < right))),
+        "<=" => return Some(Ok(Constant::Bool(left <= right))),
+        ">" => return Some(Ok(Constant::Bool(left > right))),
+        ">=" => return Some(Ok(Constant::Bool(left >= right))),
+        _ => return None,
+    };
+
+    Some(
+        result
+            .map(Constant::Int)
+            .ok_or_else(|| ConstEvalError::Overflow {
+                operator: symbol.to_owned(),
+                loc: operator.loc.clone(),
+            }),
+    )
+}
+
+fn float(operator: &ParseNode, left: f64, right: f64) -> Option<Result<Constant, ConstEvalError>> {
+    let value = match operator.value.as_deref().unwrap_or_default() {
+        "+" => left + right,
+        "-" => left - right,
+        "*" => left * right,
+        "/" if right == 0.0 => {
+            return Some(Err(ConstEvalError::DivisionByZero {
+                loc: operator.loc.clone(),
+            }))
+        }
+        "/" => left / right,
+        "==" => return Some(Ok(Constant::Bool(left == right))),
+        "!=" => return Some(Ok(Constant::Bool(left != right))),
+        "<" => return Some(Ok(Constant::Bool(left < right))),
+        "<=" => return Some(Ok(Constant::Bool(left <= right))),
+        ">" => return Some(Ok(Constant::Bool(left > right))),
+        ">=" => return Some(Ok(Constant::Bool(left >= right))),
+        _ => return None,
+    };
+
+    Some(Ok(Constant::Float(value)))
+}
+
+// Ints stay ints, they only become floats next to a float
+fn binary(
+    operator: &ParseNode,
+    left: Constant,
+    right: Constant,
+) -> Result<Constant, ConstEvalError> {
+    let symbol = operator.value.as_deref().unwrap_or_default();
+    let result = match (symbol, &left, &right) {
+        (_, Constant::Int(left), Constant::Int(right)) => integer(operator, *left, *right),
+        ("+", Constant::String(left), Constant::String(right)) => {
+            Some(Ok(Constant::String(format!("{}{}", left, right))))
+        }
+        ("&&", Constant::Bool(left), Constant::Bool(right)) => {
+            Some(Ok(Constant::Bool(*left && *right)))
+        }
+        ("||", Constant::Bool(left), Constant::Bool(right)) => {
+            Some(Ok(Constant::Bool(*left || *right)))
+        }
+        ("==" | "!=", Constant::Bool(_), Constant::Bool(_))
+        | ("==" | "!=", Constant::String(_), Constant::String(_)) => {
+            Some(Ok(Constant::Bool((left == right) == (symbol == "=="))))
+        }
+        _ => match (left.as_float(), right.as_float()) {
+            (Some(left), Some(right)) => float(operator, left, right),
+            _ => None,
+        },
+    };
+
+    result.unwrap_or_else(|| {
+        Err(ConstEvalError::TypeMismatch {
+            operator: symbol.to_owned(),
+            operands: vec![left.type_name(), right.type_name()],
+            loc: operator.loc.clone(),
+        })
+    })
+}
+
+// How tightly an operator takes its operands, left to right between equals. The ones
+// without a rule here take the loosest and binary reports them
+fn precedence(operator: &ParseNode) -> u8 {
+    match operator.value.as_deref().unwrap_or_default() {
+        "*" | "/" => 6,
+        "+" | "-" => 5,
+        "<" | "<=" | ">" | ">=" => 4,
+        "==" | "!=" => 3,
+        "&&" => 2,
+        "||" => 1,
+        _ => 0,
+    }
+}
+
+// Replaces the last two values with the operator applied to them
+fn reduce(values: &mut Vec<Constant>, operator: &ParseNode) -> Result<(), ConstEvalError> {
+    match (values.pop(), values.pop()) {
+        (Some(right), Some(left)) => {
+            values.push(binary(operator, left, right)?);
+
+            Ok(())
+        }
+        _ => Err(not_constant(operator)),
+    }
+}
+
+// The value an expression starts with, negated when it starts with a - or a !, and the
+// nodes after it
+fn operand(expression: &ParseNode) -> Result<(Constant, &[ParseNode]), ConstEvalError> {
+    match &expression.children[..] {
+        [operator, negated, rest @ ..]
+            if operator.kind == NodeKind::TokenClass(TokenClass::Operator)
+                && negated.kind == NodeKind::Expression =>
+        {
+            Ok((unary(operator, evaluate(negated)?)?, rest))
+        }
+        [l_paren, inner, _, rest @ ..]
+            if l_paren.kind == NodeKind::TokenClass(TokenClass::Lparen) =>
+        {
+            Ok((evaluate(inner)?, rest))
+        }
+        [value, rest @ ..] => Ok((literal(value)?, rest)),
+        [] => Err(not_constant(expression)),
+    }
+}
+
+// The value of an expression made of literals and operators. The parse tree nests every
+// operator to the right, so the chain is taken apart into its operands and operators and
+// grouped again by precedence: 2 * 3 + 1 is (2 * 3) + 1
+pub fn evaluate(expression: &ParseNode) -> Result<Constant, ConstEvalError> {
+    let mut values = vec![];
+    let mut operators: Vec<&ParseNode> = vec![];
+    let mut expression = expression;
+
+    loop {
+        let (value, rest) = operand(expression)?;
+
+        values.push(value);
+
+        match rest {
+            [] => break,
+            [operator, right]
+                if operator.kind == NodeKind::TokenClass(TokenClass::Operator)
+                    && right.kind == NodeKind::Expression =>
+            {
+                while let Some(last) = operators.last() {
+                    if precedence(last) < precedence(operator) {
+                        break;
+                    }
+
+                    reduce(&mut values, last)?;
+                    operators.pop();
+                }
+
+                operators.push(operator);
+                expression = right;
+            }
+            // indexes, calls and ++ need values at run time
+            [node, ..] => return Err(not_constant(node)),
+        }
+    }
+
+    while let Some(operator) = operators.pop() {
+        reduce(&mut values, operator)?;
+    }
+
+    values.pop().ok_or_else(|| not_constant(expression))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+    use rstest::rstest;
+
+    // The initializer of the only statement
+    fn evaluate_code(code: &str) -> Result<Constant, ConstEvalError> {
+        let program = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+            .parse()
+            .unwrap();
+
+        evaluate(&program.children[0].children[3])
+    }
+
+    #[rstest]
+    #[case("int a = 1 + 2 * 3;", Constant::Int(7))]
+    #[case("int a = 2 * 3 + 1;", Constant::Int(7))]
+    #[case("int a = (2 * 3) + 1;", Constant::Int(7))]
+    #[case("int a = -7 / 2;", Constant::Int(-3))]
+    #[case("int a = -1 + 2;", Constant::Int(1))]
+    #[case("int a = 1 - 2 - 3;", Constant::Int(-4))]
+    #[case("int a = 8 / 2 * 2;", Constant::Int(8))]
+    #[case("int a = 0x10 - 1_000;", Constant::Int(-984))]
+    #[case("float a = 1 / 4.0;", Constant::Float(0.25))]
+    #[case("bool a = !false && 1 < 1.5;", Constant::Bool(true))]
+    #[case("bool a = 1 + 2 * 3 == 7 || false && false;", Constant::Bool(true))]
+    #[case("bool a = \"a\" != \"b\";", Constant::Bool(true))]
+    #[case("bool a = 9007199254740993 > 9007199254740992;", Constant::Bool(true))]
+    #[case("string a = \"a\" + \"b\";", Constant::String("ab".to_owned()))]
+    fn it_evaluates_constant_expressions(#[case] code: &str, #[case] constant: Constant) {
+        assert_eq!(evaluate_code(code), Ok(constant));
+    }
+
+    #[rstest]
+    #[case("int a = 9223372036854775807 + 1;", "overflows an int with +", 1, 29)]
+    #[case("int a = 1 + 2 / (3 - 3);", "divides by zero", 1, 15)]
+    #[case("float a = 1.5 / 0;", "divides by zero", 1, 15)]
+    #[case("int a = 1 + true;", "applies + to int and bool", 1, 11)]
+    #[case("bool a = !1;", "applies ! to int", 1, 10)]
+    #[case("int a = 1 + b;", "is not a constant", 1, 13)]
+    #[case("int a = len(\"a\");", "is not a constant", 1, 9)]
+    #[case("char a = \"ab\"[0];", "is not a constant", 1, 14)]
+    fn it_explains_why_an_expression_is_not_constant(
+        #[case] code: &str,
+        #[case] message: &str,
+        #[case] line: usize,
+        #[case] column: usize,
+    ) {
+        let error = evaluate_code(code).unwrap_err();
+
+        assert_eq!(error.to_string(), message);
+        assert_eq!(error.loc(), &Loc::new(line, column));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::parse_node::{NodeKind, ParseNode};
+
+use super::{evaluate, function_name, ConstEvalError, SemanticError, SemanticResult};
+
+// A parameter and its default value, if it has one
+struct Parameter<'a> {
+    node: &'a ParseNode,
+    default: Option<&'a ParseNode>,
+}
+
+impl Parameter<'_> {
+    fn name(&self) -> &str {
+        function_name(self.node).unwrap_or_default()
+    }
+}
+
+fn parameters(definition: &ParseNode) -> Vec<Parameter<'_>> {
+    definition
+        .children
+        .iter()
+        .filter(|child| child.kind == NodeKind::Arguments)
+        .flat_map(|arguments| arguments.children.iter())
+        .filter(|child| child.kind == NodeKind::Argument)
+        .map(|node| Parameter {
+            node,
+            default: node
+                .children
+                .iter()
+                .find(|child| child.kind == NodeKind::Expression),
+        })
+        .collect()
+}
+
+fn find_definition<'a>(node: &'a ParseNode, name: &str) -> Option<&'a ParseNode> {
+    if node.kind == NodeKind::FunctionDefinition && function_name(node) == Some(name) {
+        return Some(node);
+    }
+
+    node.children
+        .iter()
+        .find_map(|child| find_definition(child, name))
+}
+
+// The arguments a call ends up with: the ones it passes, followed by the defaults
+// of the trailing parameters it leaves out. None when the function is not defined
+// in the program or the call leaves out a parameter without a default
+pub fn resolve_arguments<'a>(
+    program: &'a ParseNode,
+    call: &'a ParseNode,
+) -> Option<Vec<&'a ParseNode>> {
+    let definition = find_definition(program, function_name(call)?)?;
+    let mut arguments: Vec<&ParseNode> = call
+        .children
+        .iter()
+        .filter(|child| child.kind == NodeKind::Expression)
+        .collect();
+
+    for parameter in parameters(definition).iter().skip(arguments.len()) {
+        arguments.push(parameter.default?);
+    }
+
+    Some(arguments)
+}
+
+fn check_definition(definition: &ParseNode, errors: &mut Vec<SemanticError>) {
+    let function = function_name(definition).unwrap_or_default();
+    let mut seen_default = false;
+
+    // defaults are constants, so they can be evaluated once where the function is defined
+    for parameter in parameters(definition) {
+        match parameter
+            .default
+            .map(|default| (default, evaluate(default)))
+        {
+            Some((default, Err(ConstEvalError::NotConstant { .. }))) => {
+                errors.push(SemanticError::NonConstantDefault {
+                    function: function.to_owned(),
+                    parameter: parameter.name().to_owned(),
+                    loc: default.loc.clone(),
+                });
+            }
+            Some((_, Err(error))) => {
+                errors.push(SemanticError::InvalidDefault {
+                    function: function.to_owned(),
+                    parameter: parameter.name().to_owned(),
+                    loc: error.loc().clone(),
+                    error,
+                });
+            }
+            None if seen_default => {
+                errors.push(SemanticError::RequiredParameterAfterDefault {
+                    function: function.to_owned(),
+                    parameter: parameter.name().to_owned(),
+                    loc: parameter.node.loc.clone(),
+                });
+            }
+            _ => {}
+        }
+
+        seen_default |= parameter.default.is_some();
+    }
+}
+
+fn check_call(program: &ParseNode, call: &ParseNode, errors: &mut Vec<SemanticError>) {
+    let Some(function) = function_name(call) else {
+        return;
+    };
+    let Some(definition) = find_definition(program, function) else {
+        return;
+    };
+
+    if resolve_arguments(program, call).is_none() {
+        let parameters = parameters(definition);
+
+        errors.push(SemanticError::MissingArguments {
+            function: function.to_owned(),
+            required: parameters.iter().filter(|p| p.default.is_none()).count(),
+            found: call
+                .children
+                .iter()
+                .filter(|child| child.kind == NodeKind::Expression)
+                .count(),
+            loc: call.loc.clone(),
+        });
+    }
+}
+
+fn check_node(program: &ParseNode, node: &ParseNode, errors: &mut Vec<SemanticError>) {
+    match node.kind {
+        NodeKind::FunctionDefinition => check_definition(node, errors),
+        NodeKind::FunctionCall => check_call(program, node, errors),
+        _ => {}
+    }
+
+    for child in &node.children {
+        check_node(program, child, errors);
+    }
+}
+
+// Default values have to be constants, every parameter after one with a default
+// needs a default too, and calls have to pass every parameter without one
+pub fn check_defaults(program: &ParseNode) -> SemanticResult {
+    let mut errors = vec![];
+
+    check_node(program, program, &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    #[test]
+    fn it_fills_in_the_defaults_of_missing_trailing_arguments() {
+        let program = parse("fn scale(int a, int b = 5) -> int { return a * b; } scale(2);");
+        let arguments = resolve_arguments(&program, &program.children[1]).unwrap();
+
+        let values: Vec<Option<&str>> = arguments
+            .iter()
+            .map(|argument| argument.children[0].value.as_deref())
+            .collect();
+
+        assert_eq!(values, vec![Some("2"), Some("5")]);
+        assert!(check_defaults(&program).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_defaults_that_are_not_constant() {
+        let program = parse("fn scale(int a, int b = a + 1) -> int { return a * b; }");
+        let errors = check_defaults(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::NonConstantDefault { function, parameter, loc }]
+                if function == "scale" && parameter == "b" && loc.column == 25
+        ));
+    }
+
+    #[test]
+    fn it_rejects_defaults_that_fail_to_evaluate() {
+        let program = parse("fn scale(int a, int b = 10 / (5 - 5)) -> int { return a * b; }");
+        let errors = check_defaults(&program).unwrap_err();
+
+        assert_eq!(
+            errors[0].to_string(),
+            "Default value of b in scale divides by zero at 1:28!"
+        );
+    }
+
+    #[test]
+    fn it_rejects_required_parameters_after_defaults() {
+        let program = parse("fn scale(int a = 1, int b) -> int { return a * b; }");
+        let errors = check_defaults(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::RequiredParameterAfterDefault { parameter, .. }] if parameter == "b"
+        ));
+    }
+
+    #[test]
+    fn it_rejects_calls_that_leave_out_required_arguments() {
+        let program = parse("fn add(int a, int b, int c = 3) -> int { return a + b; } add(1);");
+        let errors = check_defaults(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::MissingArguments { function, required: 2, found: 1, .. }]
+                if function == "add"
+        ));
+        assert!(resolve_arguments(&program, &program.children[1]).is_none());
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+
+use lexer::token::TokenClass;
+
+use crate::parse_node::{NodeKind, ParseNode};
+
+use super::{function_name, SemanticError, SemanticResult};
+
+// Variables that are declared but not assigned on every path that gets here.
+// Branches work on a copy, they might not run, so what they assign does not count after them
+#[derive(Clone, Default)]
+struct Unassigned<'a>(HashSet<&'a str>);
+
+fn check_uses(node: &ParseNode, unassigned: &Unassigned, errors: &mut Vec<SemanticError>) {
+    // the callee names a function, only the arguments use variables
+    if node.kind == NodeKind::FunctionCall {
+        check_expressions(node, unassigned, errors);
+
+        return;
+    }
+
+    if node.kind == NodeKind::TokenClass(TokenClass::Identifier) {
+        if let Some(variable) = node.value.as_deref() {
+            if unassigned.0.contains(variable) {
+                errors.push(SemanticError::UseBeforeAssignment {
+                    variable: variable.to_owned(),
+                    loc: node.loc.clone(),
+                });
+            }
+        }
+    }
+
+    for child in &node.children {
+        check_uses(child, unassigned, errors);
+    }
+}
+
+fn check_expressions(node: &ParseNode, unassigned: &Unassigned, errors: &mut Vec<SemanticError>) {
+    for child in node
+        .children
+        .iter()
+        .filter(|c| c.kind == NodeKind::Expression)
+    {
+        check_uses(child, unassigned, errors);
+    }
+}
+
+fn check_statement<'a>(
+    node: &'a ParseNode,
+    unassigned: &mut Unassigned<'a>,
+    errors: &mut Vec<SemanticError>,
+) {
+    match node.kind {
+        NodeKind::VariableDeclaration => {
+            if let Some(variable) = function_name(node) {
+                unassigned.0.insert(variable);
+            }
+        }
+        NodeKind::AssignmentStatement => {
+            check_expressions(node, unassigned, errors);
+
+            if let Some(variable) = function_name(node) {
+                unassigned.0.remove(variable);
+            }
+        }
+        NodeKind::FunctionCall | NodeKind::ReturnStatement => {
+            check_expressions(node, unassigned, errors);
+        }
+        NodeKind::ControlFlowBlock => {
+            check_expressions(node, unassigned, errors);
+            check_statements(node, &mut unassigned.clone(), errors);
+        }
+        NodeKind::ForLoopStatement => {
+            let mut in_loop = unassigned.clone();
+
+            for child in &node.children {
+                match child.kind {
+                    NodeKind::Expression => check_uses(child, &in_loop, errors),
+                    _ => check_statement(child, &mut in_loop, errors),
+                }
+            }
+        }
+        NodeKind::BlockStatement => {
+            let mut in_block = unassigned.clone();
+
+            check_statements(node, &mut in_block, errors);
+
+            // the block always runs, so what it assigns stays assigned,
+            // but what it declares is gone after it
+            unassigned
+                .0
+                .retain(|variable| in_block.0.contains(variable));
+        }
+        NodeKind::FunctionDefinition => {
+            check_statements(node, &mut unassigned.clone(), errors);
+        }
+        _ => check_statements(node, unassigned, errors),
+    }
+}
+
+fn check_statements<'a>(
+    node: &'a ParseNode,
+    unassigned: &mut Unassigned<'a>,
+    errors: &mut Vec<SemanticError>,
+) {
+    for child in &node.children {
+        check_statement(child, unassigned, errors);
+    }
+}
+
+// Declared variables have to be assigned on every path before they are used
+pub fn check_definite_assignment(program: &ParseNode) -> SemanticResult {
+    let mut errors = vec![];
+
+    check_statements(program, &mut Unassigned::default(), &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    #[test]
+    fn it_accepts_variables_assigned_before_their_use() {
+        let program = parse("int x;\nint x = 3;\nSystem.print(x);");
+
+        assert!(check_definite_assignment(&program).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_using_a_declared_variable_before_it_is_assigned() {
+        let program = parse("int x;\nSystem.print(x);");
+        let errors = check_definite_assignment(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::UseBeforeAssignment { variable, loc }]
+                if variable == "x" && loc.line == 2 && loc.column == 14
+        ));
+    }
+
+    #[test]
+    fn it_keeps_assignments_of_block_statements_after_them() {
+        let program = parse("int x;\n{\n    int y;\n    int x = 3;\n}\nSystem.print(x);");
+
+        assert!(check_definite_assignment(&program).is_ok());
+    }
+
+    #[test]
+    fn it_checks_uses_inside_of_block_statements() {
+        let program = parse("int x;\n{\n    System.print(x);\n}");
+        let errors = check_definite_assignment(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::UseBeforeAssignment { loc, .. }] if loc.line == 3
+        ));
+    }
+
+    #[test]
+    fn it_checks_the_arguments_of_calls_but_not_their_callee() {
+        let program = parse("int len;\nint x;\nint y = len(x);");
+        let errors = check_definite_assignment(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::UseBeforeAssignment { variable, .. }] if variable == "x"
+        ));
+    }
+
+    #[test]
+    fn it_does_not_count_assignments_in_branches_that_might_not_run() {
+        let program =
+            parse("int x;\nif (a) {\n    int x = 3;\n    System.print(x);\n}\nSystem.print(x);");
+        let errors = check_definite_assignment(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::UseBeforeAssignment { loc, .. }] if loc.line == 6
+        ));
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use lexer::token::TokenClass;
+
+use crate::parse_node::{NodeKind, ParseNode};
+
+use super::{function_name, SemanticError, SemanticResult};
+
+pub const INDEX_TYPE: &str = "char";
+pub const SLICE_TYPE: &str = "string";
+
+// The declared type of every variable and parameter seen so far
+type Types<'a> = HashMap<&'a str, &'a str>;
+
+fn declared_type(node: &ParseNode) -> Option<&str> {
+    node.children
+        .iter()
+        .find(|child| child.kind == NodeKind::TokenClass(TokenClass::Type))
+        .and_then(|child| child.value.as_deref())
+}
+
+// s[a..b] is a slice, its expression has a range at the top
+pub(crate) fn is_slice(index: &ParseNode) -> bool {
+    index
+        .children
+        .iter()
+        .filter(|child| child.kind == NodeKind::Expression)
+        .flat_map(|expression| &expression.children)
+        .any(|child| {
+            child.kind == NodeKind::TokenClass(TokenClass::Operator)
+                && child.value.as_deref() == Some("..")
+        })
+}
+
+// The type an expression has when it is a single indexed value, like s[0] or s[1..3]
+fn indexed_type(expression: &ParseNode) -> Option<&'static str> {
+    match &expression.children[..] {
+        [_, rest @ ..] if !rest.is_empty() => {
+            let mut type_name = None;
+
+            for index in rest {
+                if index.kind != NodeKind::IndexExpression {
+                    return None;
+                }
+
+                type_name = Some(if is_slice(index) {
+                    SLICE_TYPE
+                } else {
+                    INDEX_TYPE
+                });
+            }
+
+            type_name
+        }
+        _ => None,
+    }
+}
+
+// Only strings can be indexed for now
+fn check_indexed_values(expression: &ParseNode, types: &Types, errors: &mut Vec<SemanticError>) {
+    for pair in expression.children.windows(2) {
+        let [value, index] = pair else {
+            continue;
+        };
+
+        if index.kind != NodeKind::IndexExpression
+            || value.kind != NodeKind::TokenClass(TokenClass::Identifier)
+        {
+            continue;
+        }
+
+        let Some(variable) = value.value.as_deref() else {
+            continue;
+        };
+
+        match types.get(variable) {
+            Some(&variable_type) if variable_type != SLICE_TYPE => {
+                errors.push(SemanticError::IndexOfNonString {
+                    variable: variable.to_owned(),
+                    variable_type: variable_type.to_owned(),
+                    loc: value.loc.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    for child in &expression.children {
+        check_indexed_values(child, types, errors);
+    }
+}
+
+fn check_node<'a>(node: &'a ParseNode, types: &mut Types<'a>, errors: &mut Vec<SemanticError>) {
+    if node.kind == NodeKind::Expression {
+        check_indexed_values(node, types, errors);
+
+        return;
+    }
+
+    if matches!(
+        node.kind,
+        NodeKind::AssignmentStatement | NodeKind::VariableDeclaration | NodeKind::Argument
+    ) {
+        if let (Some(variable), Some(variable_type)) = (function_name(node), declared_type(node)) {
+            types.insert(variable, variable_type);
+
+            let initializer = node
+                .children
+                .iter()
+                .find(|child| child.kind == NodeKind::Expression);
+
+            if let Some(expected) = initializer.and_then(indexed_type) {
+                if expected != variable_type {
+                    errors.push(SemanticError::IndexTypeMismatch {
+                        variable: variable.to_owned(),
+                        expected: expected.to_owned(),
+                        found: variable_type.to_owned(),
+                        loc: node.loc.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    for child in &node.children {
+        check_node(child, types, errors);
+    }
+}
+
+// Indexing a string gives the char at that index and slicing it with a range gives a string.
+// Values of other declared types can not be indexed
+pub fn check_indexing(program: &ParseNode) -> SemanticResult {
+    let mut errors = vec![];
+
+    check_node(program, &mut Types::new(), &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    #[test]
+    fn it_accepts_a_char_from_an_index_and_a_string_from_a_slice() {
+        let program = parse(
+            "string s = \"abc\";\nchar c = s[0];\nstring t = s[0..2];\nint n = len(s[1..3]);",
+        );
+
+        assert!(check_indexing(&program).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_an_index_assigned_to_a_string() {
+        let program = parse("string s = \"abc\";\nstring c = s[0];");
+        let errors = check_indexing(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::IndexTypeMismatch { variable, expected, found, loc }]
+                if variable == "c" && expected == "char" && found == "string" && loc.line == 2
+        ));
+    }
+
+    #[test]
+    fn it_rejects_a_slice_assigned_to_a_char() {
+        let program = parse("string s = \"abc\";\nchar c = s[0..1];");
+        let errors = check_indexing(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::IndexTypeMismatch { expected, found, .. }]
+                if expected == "string" && found == "char"
+        ));
+    }
+
+    #[test]
+    fn it_rejects_indexing_a_value_that_is_not_a_string() {
+        let program = parse("fn first(int n) -> char {\n    char c = n[0];\n    return c;\n}");
+        let errors = check_indexing(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::IndexOfNonString { variable, variable_type, loc }]
+                if variable == "n" && variable_type == "int" && loc.line == 2
+        ));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::parse_node::{Loc, NodeKind, ParseNode};
+use lexer::{
+    diagnostic::{CompilerError, Severity, Span},
+    token::TokenClass,
+};
+use strum::EnumProperty;
+use thiserror::Error as ThisError;
+
+mod call_graph;
+mod const_eval;
+mod defaults;
+mod definite_assignment;
+mod indexing;
+mod print;
+mod purity;
+mod symbols;
+
+pub use call_graph::{CallGraph, ENTRY_FUNCTION};
+pub use const_eval::{evaluate, ConstEvalError, Constant};
+pub use defaults::{check_defaults, resolve_arguments};
+pub use definite_assignment::check_definite_assignment;
+pub(crate) use indexing::is_slice;
+pub use indexing::{check_indexing, INDEX_TYPE, SLICE_TYPE};
+pub use print::{check_print_arguments, PLACEHOLDER, PRINT_FUNCTION};
+pub use purity::check_purity;
+pub use symbols::{Binding, Occurrence, Symbols};
+
+pub const BUILTIN_FUNCTIONS: &[&str] = &["System.print"];
+
+// The first identifier of a node, the name of the function, call, parameter or variable
+pub(crate) fn function_name(node: &ParseNode) -> Option<&str> {
+    node.children
+        .iter()
+        .find(|child| child.kind == NodeKind::TokenClass(TokenClass::Identifier))
+        .and_then(|child| child.value.as_deref())
+}
+
+pub type SemanticResult = Result<(), Vec<SemanticError>>;
+
+// The props code is the stable code of the error, see CompilerError
+#[derive(ThisError, Debug, EnumProperty)]
+pub enum SemanticError {
+    #[strum(props(code = "S0001"))]
+    #[error("Pure function {function} calls built-in {callee} at {}:{}!", .loc.line, .loc.column)]
+    BuiltinCallInPureFunction {
+        function: String,
+        callee: String,
+        loc: Loc,
+    },
+    #[strum(props(code = "S0002"))]
+    #[error("Pure function {function} calls impure function {callee} at {}:{}!", .loc.line, .loc.column)]
+    ImpureCallInPureFunction {
+        function: String,
+        callee: String,
+        loc: Loc,
+    },
+    #[strum(props(code = "S0003"))]
+    #[error("Default value of {parameter} in {function} is not a constant at {}:{}!", .loc.line, .loc.column)]
+    NonConstantDefault {
+        function: String,
+        parameter: String,
+        loc: Loc,
+    },
+    #[strum(props(code = "S0004"))]
+    #[error("Parameter {parameter} of {function} needs a default, it follows one that has a default at {}:{}!", .loc.line, .loc.column)]
+    RequiredParameterAfterDefault {
+        function: String,
+        parameter: String,
+        loc: Loc,
+    },
+    #[strum(props(code = "S0005"))]
+    #[error("Call to {function} passes {found} arguments, at least {required} are required at {}:{}!", .loc.line, .loc.column)]
+    MissingArguments {
+        function: String,
+        required: usize,
+        found: usize,
+        loc: Loc,
+    },
+    #[strum(props(code = "S0006"))]
+    #[error("Print format has {placeholders} placeholders but {arguments} values at {}:{}!", .loc.line, .loc.column)]
+    PlaceholderCountMismatch {
+        placeholders: usize,
+        arguments: usize,
+        loc: Loc,
+    },
+    #[strum(props(code = "S0007"))]
+    #[error("Print format has to be a string literal at {}:{}!", .loc.line, .loc.column)]
+    NonLiteralFormat { loc: Loc },
+    #[strum(props(code = "S0008"))]
+    #[error("Variable {variable} is used before it is assigned at {}:{}!", .loc.line, .loc.column)]
+    UseBeforeAssignment { variable: String, loc: Loc },
+    #[strum(props(code = "S0009"))]
+    #[error("Variable {variable} is a {found} but is assigned a {expected} from indexing at {}:{}!", .loc.line, .loc.column)]
+    IndexTypeMismatch {
+        variable: String,
+        expected: String,
+        found: String,
+        loc: Loc,
+    },
+    #[strum(props(code = "S0010"))]
+    #[error("Variable {variable} is a {variable_type}, only strings can be indexed at {}:{}!", .loc.line, .loc.column)]
+    IndexOfNonString {
+        variable: String,
+        variable_type: String,
+        loc: Loc,
+    },
+    #[strum(props(code = "S0011"))]
+    #[error("Default value of {parameter} in {function} {error} at {}:{}!", .loc.line, .loc.column)]
+    InvalidDefault {
+        function: String,
+        parameter: String,
+        error: ConstEvalError,
+        loc: Loc,
+    },
+}
+
+// Problems that do not stop the program from compiling
+#[derive(ThisError, Debug, EnumProperty)]
+pub enum SemanticWarning {
+    #[strum(props(code = "W0001"))]
+    #[error("Function {function} at {}:{} is never called from main!", .loc.line, .loc.column)]
+    UnreachableFunction { function: String, loc: Loc },
+}
+
+impl CompilerError for SemanticError {
+    fn code(&self) -> &'static str {
+        self.get_str("code")
+            .expect("Every semantic error should have a code!")
+    }
+
+    fn span(&self) -> Option<Span> {
+        let loc = match self {
+            Self::BuiltinCallInPureFunction { loc, .. }
+            | Self::ImpureCallInPureFunction { loc, .. }
+            | Self::NonConstantDefault { loc, .. }
+            | Self::RequiredParameterAfterDefault { loc, .. }
+            | Self::MissingArguments { loc, .. }
+            | Self::PlaceholderCountMismatch { loc, .. }
+            | Self::NonLiteralFormat { loc }
+            | Self::UseBeforeAssignment { loc, .. }
+            | Self::IndexTypeMismatch { loc, .. }
+            | Self::IndexOfNonString { loc, .. }
+            | Self::InvalidDefault { loc, .. } => loc,
+        };
+
+        Some(loc.into())
+    }
+}
+
+impl CompilerError for SemanticWarning {
+    fn code(&self) -> &'static str {
+        self.get_str("code")
+            .expect("Every semantic warning should have a code!")
+    }
+
+    fn span(&self) -> Option<Span> {
+        match self {
+            Self::UnreachableFunction { loc, .. } => Some(loc.into()),
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
@@ -0,0 +1,119 @@
+use lexer::token::TokenClass;
+
+use crate::parse_node::{NodeKind, ParseNode};
+
+use super::{function_name, SemanticError, SemanticResult};
+
+pub const PRINT_FUNCTION: &str = "System.print";
+pub const PLACEHOLDER: &str = "{}";
+
+// The string of an expression that is a single string literal
+fn literal_value(expression: &ParseNode) -> Option<&str> {
+    match &expression.children[..] {
+        [literal] if literal.kind == NodeKind::TokenClass(TokenClass::Literal) => {
+            literal.value.as_deref()
+        }
+        _ => None,
+    }
+}
+
+fn check_call(call: &ParseNode, errors: &mut Vec<SemanticError>) {
+    let arguments: Vec<&ParseNode> = call
+        .children
+        .iter()
+        .filter(|child| child.kind == NodeKind::Expression)
+        .collect();
+
+    // a single argument is printed as it is, braces and all
+    let [format, values @ ..] = &arguments[..] else {
+        return;
+    };
+
+    if values.is_empty() {
+        return;
+    }
+
+    match literal_value(format) {
+        Some(format) => {
+            let placeholders = format.matches(PLACEHOLDER).count();
+
+            if placeholders != values.len() {
+                errors.push(SemanticError::PlaceholderCountMismatch {
+                    placeholders,
+                    arguments: values.len(),
+                    loc: call.loc.clone(),
+                });
+            }
+        }
+        None => errors.push(SemanticError::NonLiteralFormat {
+            loc: format.loc.clone(),
+        }),
+    }
+}
+
+fn check_node(node: &ParseNode, errors: &mut Vec<SemanticError>) {
+    if node.kind == NodeKind::FunctionCall && function_name(node) == Some(PRINT_FUNCTION) {
+        check_call(node, errors);
+    }
+
+    for child in &node.children {
+        check_node(child, errors);
+    }
+}
+
+// When print gets more than one argument the first one is a format string,
+// with one {} placeholder for each of the values after it
+pub fn check_print_arguments(program: &ParseNode) -> SemanticResult {
+    let mut errors = vec![];
+
+    check_node(program, &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    #[test]
+    fn it_accepts_a_value_for_every_placeholder() {
+        let program = parse("System.print(\"x = {} y = {}\", x, y);\nSystem.print(\"{}\");");
+
+        assert!(check_print_arguments(&program).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_a_mismatched_placeholder_count() {
+        let program = parse("int x = 3;\nSystem.print(\"x = {} y = {}\", x);");
+        let errors = check_print_arguments(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::PlaceholderCountMismatch { placeholders: 2, arguments: 1, loc }]
+                if loc.line == 2 && loc.column == 1
+        ));
+    }
+
+    #[test]
+    fn it_rejects_a_format_that_is_not_a_string_literal() {
+        let program = parse("System.print(x, y);");
+        let errors = check_print_arguments(&program).unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [SemanticError::NonLiteralFormat { .. }]
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use lexer::token::TokenClass;
+
+use crate::parse_node::{NodeKind, ParseNode};
+
+use super::{function_name, SemanticError, SemanticResult, BUILTIN_FUNCTIONS};
+
+fn is_pure_definition(node: &ParseNode) -> bool {
+    node.kind == NodeKind::FunctionDefinition
+        && node.children.first().is_some_and(|child| {
+            child.kind == NodeKind::TokenClass(TokenClass::Keyword)
+                && child.value.as_deref() == Some("pure")
+        })
+}
+
+fn collect_pure_functions<'a>(node: &'a ParseNode, pure_functions: &mut HashSet<&'a str>) {
+    if is_pure_definition(node) {
+        if let Some(name) = function_name(node) {
+            pure_functions.insert(name);
+        }
+    }
+
+    for child in &node.children {
+        collect_pure_functions(child, pure_functions);
+    }
+}
+
+fn check_calls(
+    function: &str,
+    node: &ParseNode,
+    pure_functions: &HashSet<&str>,
+    errors: &mut Vec<SemanticError>,
+) {
+    if node.kind == NodeKind::FunctionCall {
+        if let Some(callee) = function_name(node) {
+            if BUILTIN_FUNCTIONS.contains(&callee) {
+                errors.push(SemanticError::BuiltinCallInPureFunction {
+                    function: function.to_owned(),
+                    callee: callee.to_owned(),
+                    loc: node.loc.clone(),
+                });
+            } else if !pure_functions.contains(callee) {
+                errors.push(SemanticError::ImpureCallInPureFunction {
+                    function: function.to_owned(),
+                    callee: callee.to_owned(),
+                    loc: node.loc.clone(),
+                });
+            }
+        }
+    }
+
+    for child in &node.children {
+        check_calls(function, child, pure_functions, errors);
+    }
+}
+
+fn check_definitions(
+    node: &ParseNode,
+    pure_functions: &HashSet<&str>,
+    errors: &mut Vec<SemanticError>,
+) {
+    if is_pure_definition(node) {
+        let function = function_name(node).unwrap_or_default();
+
+        for child in node.children.iter().filter(|c| c.kind == NodeKind::Block) {
+            check_calls(function, child, pure_functions, errors);
+        }
+    }
+
+    for child in &node.children {
+        check_definitions(child, pure_functions, errors);
+    }
+}
+
+// Pure functions may only call other pure functions, never built-ins,
+// so their calls can later be deduplicated or reordered safely
+pub fn check_purity(program: &ParseNode) -> SemanticResult {
+    let mut pure_functions = HashSet::new();
+    let mut errors = vec![];
+
+    collect_pure_functions(program, &mut pure_functions);
+    check_definitions(program, &pure_functions, &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    #[test]
+    fn it_accepts_pure_functions_without_side_effects() {
+        let program = parse("pure fn sum(int a, int b) -> int { return a + b; }");
+
+        assert!(check_purity(&program).is_ok());
+    }
+
+    #[test]
+    fn it_allows_pure_functions_to_call_other_pure_functions() {
+        let program = parse(
+            "pure fn one(int a) -> int { return 1; } pure fn two(int a) -> int { one(a); return 2; }",
+        );
+
+        assert!(check_purity(&program).is_ok());
+    }
+
+    #[test]
+    fn it_does_not_check_functions_that_are_not_pure() {
+        let program = parse("fn log(string a) -> bool { System.print(a); return true; }");
+
+        assert!(check_purity(&program).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_built_in_calls_in_pure_functions() {
+        let program = parse("pure fn log(string a) -> bool { System.print(a); return true; }");
+        let errors = check_purity(&program).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            SemanticError::BuiltinCallInPureFunction { function, callee, loc }
+                if function == "log" && callee == "System.print" && loc.line == 1 && loc.column == 33
+        ));
+    }
+
+    #[test]
+    fn it_rejects_impure_calls_in_pure_functions() {
+        let program = parse(
+            "fn log(string a) -> bool { return true; } pure fn test(string a) -> bool { log(a); return true; }",
+        );
+        let errors = check_purity(&program).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            SemanticError::ImpureCallInPureFunction { function, callee, .. }
+                if function == "test" && callee == "log"
+        ));
+    }
+}
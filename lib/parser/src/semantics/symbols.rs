@@ -0,0 +1,283 @@
+use lexer::token::TokenClass;
+
+use crate::parse_node::{Loc, NodeKind, ParseNode};
+
+use super::function_name;
+
+// What a name refers to, a declaration by where it is or a name nothing declares,
+// like the built-in functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Binding {
+    Declared(Loc),
+    Free(String),
+}
+
+// A name in the source, a declaration or a use of one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    pub name: String,
+    pub loc: Loc,
+    pub end_loc: Loc,
+    pub binding: Binding,
+}
+
+// Names visible in a scope, with where they are declared
+type Scope = Vec<(String, Loc)>;
+
+#[derive(Default)]
+struct Resolver<'a> {
+    scopes: Vec<Scope>,
+    occurrences: Vec<Occurrence>,
+    // occurrences read as another name, to see what a rename would resolve to
+    renamed: Option<(&'a [Loc], &'a str)>,
+}
+
+impl Resolver<'_> {
+    fn name_of(&self, node: &ParseNode) -> String {
+        match self.renamed {
+            Some((locs, new_name)) if locs.contains(&node.loc) => new_name.to_owned(),
+            _ => node.value.clone().unwrap_or_default(),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Binding {
+        self.scopes
+            .iter()
+            .rev()
+            .flatten()
+            .find(|(declared, _)| declared == name)
+            .map(|(_, loc)| Binding::Declared(loc.clone()))
+            .unwrap_or_else(|| Binding::Free(name.to_owned()))
+    }
+
+    fn record(&mut self, identifier: &ParseNode, binding: Binding) {
+        self.occurrences.push(Occurrence {
+            name: identifier.value.clone().unwrap_or_default(),
+            loc: identifier.loc.clone(),
+            end_loc: identifier.end_loc.clone(),
+            binding,
+        });
+    }
+
+    // A second declaration in the same scope declares the same variable again
+    fn declare(&mut self, identifier: &ParseNode) {
+        let name = self.name_of(identifier);
+        let scope = self.scopes.last_mut().expect("There is always a scope!");
+        let loc = match scope.iter().find(|(declared, _)| *declared == name) {
+            Some((_, loc)) => loc.clone(),
+            None => {
+                scope.push((name, identifier.loc.clone()));
+                identifier.loc.clone()
+            }
+        };
+
+        self.record(identifier, Binding::Declared(loc));
+    }
+
+    fn identifier(node: &ParseNode) -> Option<&ParseNode> {
+        node.children
+            .iter()
+            .find(|child| child.kind == NodeKind::TokenClass(TokenClass::Identifier))
+    }
+
+    fn resolve_scope(&mut self, node: &ParseNode) {
+        self.scopes.push(vec![]);
+
+        // functions can be called before they are defined
+        for function in node
+            .children
+            .iter()
+            .filter(|child| child.kind == NodeKind::FunctionDefinition)
+        {
+            if let Some(identifier) = Self::identifier(function) {
+                let name = self.name_of(identifier);
+
+                self.scopes
+                    .last_mut()
+                    .expect("The scope was just pushed!")
+                    .push((name, identifier.loc.clone()));
+            }
+        }
+
+        self.resolve_children(node);
+        self.scopes.pop();
+    }
+
+    fn resolve_children(&mut self, node: &ParseNode) {
+        for child in &node.children {
+            self.resolve(child);
+        }
+    }
+
+    fn resolve(&mut self, node: &ParseNode) {
+        let declares = function_name(node).is_some()
+            && node
+                .children
+                .iter()
+                .any(|child| child.kind == NodeKind::TokenClass(TokenClass::Type));
+
+        match node.kind {
+            NodeKind::Program | NodeKind::Block => self.resolve_scope(node),
+            NodeKind::FunctionDefinition => {
+                if let Some(identifier) = Self::identifier(node) {
+                    let binding = self.lookup(&self.name_of(identifier));
+
+                    self.record(identifier, binding);
+                }
+
+                self.scopes.push(vec![]);
+
+                for child in node
+                    .children
+                    .iter()
+                    .filter(|child| matches!(child.kind, NodeKind::Arguments | NodeKind::Block))
+                {
+                    self.resolve(child);
+                }
+
+                self.scopes.pop();
+            }
+            NodeKind::ForLoopStatement => {
+                self.scopes.push(vec![]);
+                self.resolve_children(node);
+                self.scopes.pop();
+            }
+            NodeKind::AssignmentStatement | NodeKind::VariableDeclaration | NodeKind::Argument
+                if declares =>
+            {
+                // the initializer can not see the variable it initializes
+                for child in node
+                    .children
+                    .iter()
+                    .filter(|child| child.kind == NodeKind::Expression)
+                {
+                    self.resolve(child);
+                }
+
+                if let Some(identifier) = Self::identifier(node) {
+                    self.declare(identifier);
+                }
+            }
+            NodeKind::TokenClass(TokenClass::Identifier) => {
+                let binding = self.lookup(&self.name_of(node));
+
+                self.record(node, binding);
+            }
+            _ => self.resolve_children(node),
+        }
+    }
+}
+
+// Every name of a program and what it refers to. Blocks, functions and for loops open
+// scopes, variables are visible after their declaration and functions in their whole scope
+#[derive(Debug)]
+pub struct Symbols {
+    occurrences: Vec<Occurrence>,
+}
+
+impl Symbols {
+    pub fn of(program: &ParseNode) -> Self {
+        let mut resolver = Resolver::default();
+
+        resolver.resolve(program);
+
+        Self {
+            occurrences: resolver.occurrences,
+        }
+    }
+
+    // What the names resolve to when the given occurrences are read as new_name instead
+    pub fn with_renamed(program: &ParseNode, locs: &[Loc], new_name: &str) -> Self {
+        let mut resolver = Resolver {
+            renamed: Some((locs, new_name)),
+            ..Resolver::default()
+        };
+
+        resolver.resolve(program);
+
+        Self {
+            occurrences: resolver.occurrences,
+        }
+    }
+
+    // The occurrence whose name covers loc
+    pub fn at(&self, loc: &Loc) -> Option<&Occurrence> {
+        self.occurrences.iter().find(|occurrence| {
+            occurrence.loc.line == loc.line
+                && occurrence.loc.column <= loc.column
+                && loc.column <= occurrence.end_loc.column
+        })
+    }
+
+    pub fn references<'a>(&'a self, binding: &'a Binding) -> impl Iterator<Item = &'a Occurrence> {
+        self.occurrences
+            .iter()
+            .filter(move |occurrence| &occurrence.binding == binding)
+    }
+
+    // In source order
+    pub fn iter(&self) -> impl Iterator<Item = &Occurrence> {
+        self.occurrences.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::lexer::Lexer;
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    fn bindings(symbols: &Symbols) -> Vec<(String, Binding)> {
+        symbols
+            .iter()
+            .map(|occurrence| (occurrence.name.clone(), occurrence.binding.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn it_resolves_names_to_their_declarations() {
+        let program = parse("int a = 1;\nfn f(int b) -> int {\n    int c = a + b;\n    return g(c);\n}\nfn g(int a) -> int { a }");
+        let symbols = Symbols::of(&program);
+        let declared = |line, column| Binding::Declared(Loc::new(line, column));
+
+        assert_eq!(
+            bindings(&symbols),
+            vec![
+                ("a".to_owned(), declared(1, 5)),
+                ("f".to_owned(), declared(2, 4)),
+                ("b".to_owned(), declared(2, 10)),
+                ("a".to_owned(), declared(1, 5)),
+                ("b".to_owned(), declared(2, 10)),
+                ("c".to_owned(), declared(3, 9)),
+                ("g".to_owned(), declared(6, 4)),
+                ("c".to_owned(), declared(3, 9)),
+                ("g".to_owned(), declared(6, 4)),
+                ("a".to_owned(), declared(6, 10)),
+                ("a".to_owned(), declared(6, 10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_scopes_blocks_and_loops() {
+        let program = parse("int a;\n{\n    int a = 2;\n    show(a);\n}\nfor (int i = 0; i < a; i = i + 1) {\n}\nSystem.print(i);");
+        let symbols = Symbols::of(&program);
+        let inner = Binding::Declared(Loc::new(3, 9));
+
+        assert_eq!(symbols.references(&inner).count(), 2);
+        assert_eq!(
+            symbols.at(&Loc::new(6, 21)).map(|a| &a.binding),
+            Some(&Binding::Declared(Loc::new(1, 5)))
+        );
+        assert_eq!(
+            symbols.at(&Loc::new(8, 14)).map(|i| &i.binding),
+            Some(&Binding::Free("i".to_owned()))
+        );
+    }
+}
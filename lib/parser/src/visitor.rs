@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use crate::parse_node::{NodeKind, ParseNode};
+
+pub trait Visitor {
+    fn enter(&mut self, _node: &ParseNode) {}
+    fn leave(&mut self, _node: &ParseNode) {}
+}
+
+impl ParseNode {
+    // Depth first walk, entering a node before its children and leaving it after them
+    pub fn walk<V: Visitor>(&self, visitor: &mut V) {
+        visitor.enter(self);
+
+        for child in &self.children {
+            child.walk(visitor);
+        }
+
+        visitor.leave(self);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NodeCounter {
+    counts: HashMap<NodeKind, usize>,
+}
+
+impl NodeCounter {
+    pub fn of(node: &ParseNode) -> Self {
+        let mut counter = Self::default();
+
+        node.walk(&mut counter);
+
+        counter
+    }
+
+    pub fn count(&self, kind: &NodeKind) -> usize {
+        self.counts.get(kind).copied().unwrap_or_default()
+    }
+
+    pub fn statements(&self) -> usize {
+        [
+            NodeKind::ForLoopStatement,
+            NodeKind::ReturnStatement,
+            NodeKind::ConditionStatement,
+            NodeKind::AssignmentStatement,
+            NodeKind::VariableDeclaration,
+            NodeKind::BlockStatement,
+            NodeKind::FunctionCall,
+            NodeKind::FunctionDefinition,
+        ]
+        .iter()
+        .map(|kind| self.count(kind))
+        .sum()
+    }
+}
+
+impl Visitor for NodeCounter {
+    fn enter(&mut self, node: &ParseNode) {
+        *self.counts.entry(node.kind.clone()).or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::RecursiveDescentParser;
+    use lexer::{lexer::Lexer, token::TokenClass};
+
+    fn parse(code: &str) -> ParseNode {
+        let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+
+        parser.parse().unwrap()
+    }
+
+    #[derive(Default)]
+    struct OrderRecorder {
+        events: Vec<String>,
+    }
+
+    impl Visitor for OrderRecorder {
+        fn enter(&mut self, node: &ParseNode) {
+            self.events.push(format!("enter {}", node.kind));
+        }
+
+        fn leave(&mut self, node: &ParseNode) {
+            self.events.push(format!("leave {}", node.kind));
+        }
+    }
+
+    #[test]
+    fn it_enters_parents_before_children_and_leaves_them_after() {
+        let program = parse("int a = 3;");
+        let mut recorder = OrderRecorder::default();
+
+        program.walk(&mut recorder);
+
+        assert_eq!(recorder.events.first().unwrap(), "enter Program");
+        assert_eq!(recorder.events[1], "enter AssignmentStatement");
+        assert_eq!(recorder.events.last().unwrap(), "leave Program");
+        assert_eq!(
+            recorder.events[recorder.events.len() - 2],
+            "leave AssignmentStatement"
+        );
+    }
+
+    #[test]
+    fn it_counts_nodes_by_kind() {
+        let counter = NodeCounter::of(&parse("int a = 3; int b = a + 1; System.print(b);"));
+
+        assert_eq!(counter.count(&NodeKind::Program), 1);
+        assert_eq!(counter.count(&NodeKind::AssignmentStatement), 2);
+        assert_eq!(counter.count(&NodeKind::FunctionCall), 1);
+        assert_eq!(counter.count(&NodeKind::TokenClass(TokenClass::Semi)), 3);
+        assert_eq!(counter.count(&NodeKind::ForLoopStatement), 0);
+        assert_eq!(counter.statements(), 3);
+    }
+}
@@ -0,0 +1,3 @@
+int a = 3;
+int b = 99999999999999999999;  // expect-error: is out of range
+else c = 4;  // expect-error: unexpected token: keyword `else`, expected type
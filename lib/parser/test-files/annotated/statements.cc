@@ -0,0 +1,6 @@
+int a = );  // expect-error: expected one of: identifier, string, number, boolean, `(`
+System.print(a;  // expect-error: expected one of: `)`, `,`
+int b = 3;
+if (b > ) {  // expect-error: unexpected token: `)`
+    System.print(b);
+}
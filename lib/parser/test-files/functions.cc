@@ -0,0 +1,44 @@
+/// Adds two numbers, the second one is optional
+fn add(int a, int b = 1) -> int {
+    return a + b;
+}
+
+pure fn identity(int value) -> int {
+    return value;
+}
+
+fn nothing() -> bool {
+}
+
+fn first_of(int a, int b,) -> int {
+    return a;
+}
+
+fn sum(int a, int b) -> int {
+    add(a, b);
+    a + b
+}
+
+fn main() -> int {
+    int total;
+
+    {
+        int scoped = 1;
+    }
+
+    add(1, 2);
+    first_of(1, 2,);
+    nothing();
+    int total = (1 + 2);
+    System.print("total = {}", total);
+
+    string word = "total";
+    char letter = word[0];
+    string part = word[1..len(word)];
+
+    for (int i = first(total); i < len(total, add(1)); i = next(i)) {
+        System.print(i);
+    }
+
+    return 0;
+}
@@ -0,0 +1,2 @@
+for (int i = 0; i < 5) {
+}
@@ -0,0 +1,4 @@
+int a = 3 @ 4;
+int b = 2;
+if (b # 1) {
+}
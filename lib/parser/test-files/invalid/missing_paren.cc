@@ -0,0 +1,3 @@
+while (a > b {
+    System.print(a);
+}
@@ -0,0 +1,3 @@
+fn test(int a) bool {
+    return a;
+}
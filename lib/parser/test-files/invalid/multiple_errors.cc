@@ -0,0 +1,7 @@
+int a = 3
+int b = 5;
+System.print(b;
+int c = 7;
+if (c > ) {
+    System.print(c);
+}
@@ -0,0 +1 @@
+int a = 999999999999999999999999;
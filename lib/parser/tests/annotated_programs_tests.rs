@@ -0,0 +1,96 @@
+mod common;
+
+use std::fs;
+
+use common::render_diagnostic;
+use lexer::lexer::Lexer;
+use parser::parsers::RecursiveDescentParser;
+
+const ANNOTATION: &str = "// expect-error:";
+
+// An expected diagnostic, matched case insensitively against the errors of its line
+struct Expectation {
+    line: usize,
+    text: String,
+}
+
+// Takes the annotations out of the source, the lexer has no comments yet.
+// Only what follows the code is removed, so columns of the code stay the same
+fn extract_expectations(source: &str) -> (String, Vec<Expectation>) {
+    let mut code = String::new();
+    let mut expectations = vec![];
+
+    for (index, line) in source.lines().enumerate() {
+        match line.split_once(ANNOTATION) {
+            Some((line_code, text)) => {
+                code.push_str(line_code);
+                expectations.push(Expectation {
+                    line: index + 1,
+                    text: text.trim().to_lowercase(),
+                });
+            }
+            None => code.push_str(line),
+        }
+
+        code.push('\n');
+    }
+
+    (code, expectations)
+}
+
+// Every diagnostic has to be expected on its line and every expectation has to be met.
+// Errors at the end of the file belong to the last line
+#[test]
+fn test_annotated_programs_report_the_annotated_diagnostics() {
+    let mut paths: Vec<_> = fs::read_dir("test-files/annotated")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "cc"))
+        .collect();
+
+    paths.sort();
+
+    assert!(
+        !paths.is_empty(),
+        "There should be annotated programs to test!"
+    );
+
+    for path in paths {
+        let source = fs::read_to_string(&path).unwrap();
+        let last_line = source.lines().count();
+        let (code, mut expectations) = extract_expectations(&source);
+        let errors = RecursiveDescentParser::new(Lexer::new(code))
+            .parse_with_recovery()
+            .errors;
+
+        for error in &errors {
+            let line = error.line().map_or(last_line, |line| line.get());
+            let diagnostic = render_diagnostic(error);
+            let lowercase = diagnostic.to_lowercase();
+            let expected = expectations.iter().position(|expectation| {
+                expectation.line == line && lowercase.contains(&expectation.text)
+            });
+
+            match expected {
+                Some(index) => {
+                    expectations.remove(index);
+                }
+                None => panic!(
+                    "{}:{} has an unexpected diagnostic:\n{}",
+                    path.display(),
+                    line,
+                    diagnostic
+                ),
+            }
+        }
+
+        if let Some(expectation) = expectations.first() {
+            panic!(
+                "{}:{} expected an error containing {:?}",
+                path.display(),
+                expectation.line,
+                expectation.text
+            );
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// Shared between integration test binaries, which each use only part of it
+#![allow(dead_code)]
+
+use parser::{
+    parse_node::{NodeKind, ParseNode},
+    visitor::NodeCounter,
+};
+use std::error::Error;
+
+// Renders an error with its sources, e.g. the lexer error behind a parser error
+pub fn render_diagnostic(error: &dyn Error) -> String {
+    let mut diagnostic = error.to_string();
+    let mut source = error.source();
+
+    while let Some(error) = source {
+        diagnostic.push_str(&format!("\n  caused by: {}", error));
+        source = error.source();
+    }
+
+    diagnostic
+}
+
+pub struct TreeShape {
+    pub functions: usize,
+    pub statements: usize,
+    pub assignments: usize,
+    pub conditions: usize,
+    pub loops: usize,
+    pub calls: usize,
+    pub returns: usize,
+    pub blocks: usize,
+    pub expressions: usize,
+}
+
+impl TreeShape {
+    pub fn of(tree: &ParseNode) -> Self {
+        let counter = NodeCounter::of(tree);
+
+        Self {
+            functions: counter.count(&NodeKind::FunctionDefinition),
+            statements: counter.statements(),
+            assignments: counter.count(&NodeKind::AssignmentStatement),
+            conditions: counter.count(&NodeKind::ConditionStatement),
+            loops: counter.count(&NodeKind::ForLoopStatement),
+            calls: counter.count(&NodeKind::FunctionCall),
+            returns: counter.count(&NodeKind::ReturnStatement),
+            blocks: counter.count(&NodeKind::Block),
+            expressions: counter.count(&NodeKind::Expression),
+        }
+    }
+}
+
+// Asserts only the counts that are listed, e.g. assert_tree_shape!(tree, functions = 2)
+#[macro_export]
+macro_rules! assert_tree_shape {
+    ($tree:expr, $($name:ident = $expected:expr),+ $(,)?) => {{
+        let shape = $crate::common::TreeShape::of(&$tree);
+
+        $(
+            assert_eq!(
+                shape.$name,
+                $expected,
+                "Tree shape mismatch for {}",
+                stringify!($name)
+            );
+        )+
+    }};
+}
@@ -0,0 +1,36 @@
+use lexer::lexer::Lexer;
+use parser::{coverage::Production, parsers::RecursiveDescentParser};
+
+// The share of productions the valid fixtures have to take, raise it as fixtures are added
+const MIN_COVERAGE: f64 = 0.9;
+
+#[test]
+fn test_the_fixtures_cover_the_grammar_productions() {
+    let mut parser = RecursiveDescentParser::new(Lexer::new(String::new())).with_coverage();
+
+    for path in ["test-files/main.cc", "test-files/functions.cc"] {
+        let code = std::fs::read_to_string(path).unwrap();
+
+        parser.reset(code);
+        parser.parse().unwrap();
+    }
+
+    let coverage = parser.coverage().unwrap();
+    let uncovered: Vec<Production> = coverage.uncovered().collect();
+
+    assert!(
+        coverage.ratio() >= MIN_COVERAGE,
+        "Fixtures cover {:.0}% of the productions, missing: {:?}",
+        coverage.ratio() * 100.0,
+        uncovered
+    );
+}
+
+#[test]
+fn test_it_records_nothing_without_coverage() {
+    let mut parser = RecursiveDescentParser::new(Lexer::new(String::from("int a = 3;")));
+
+    parser.parse().unwrap();
+
+    assert!(parser.coverage().is_none());
+}
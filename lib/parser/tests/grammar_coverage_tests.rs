@@ -0,0 +1,47 @@
+use lexer::lexer::Lexer;
+use parser::{
+    grammar::{Grammar, NonTerminal},
+    parse_node::{NodeKind, ParseNode},
+    parsers::RecursiveDescentParser,
+};
+use strum::IntoEnumIterator;
+
+fn contains_kind(node: &ParseNode, kind: &NodeKind) -> bool {
+    &node.kind == kind || node.children.iter().any(|child| contains_kind(child, kind))
+}
+
+#[test]
+fn test_every_non_terminal_is_documented_in_grammar_md() {
+    let grammar = Grammar::new();
+    let docs = std::fs::read_to_string("../../grammar.md").unwrap();
+
+    for (non_terminal, _) in grammar.iter() {
+        let rule = format!("\n{} -> ", non_terminal);
+
+        assert!(
+            docs.contains(&rule),
+            "{:?} has no production rules in grammar.md",
+            non_terminal
+        );
+    }
+}
+
+#[test]
+fn test_every_non_terminal_is_handled_by_the_recursive_descent_parser() {
+    let mut current_dir = std::env::current_dir().unwrap();
+    current_dir.push("test-files/main.cc");
+
+    let lexer = Lexer::from_file(current_dir.to_str().unwrap()).unwrap();
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+
+    for non_terminal in NonTerminal::iter() {
+        if let Some(kind) = non_terminal.node_kind() {
+            assert!(
+                contains_kind(&tree, &kind),
+                "{:?} is never produced by the parser, expected a {:?} node",
+                non_terminal,
+                kind
+            );
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use lexer::{lexer::Lexer, token::KEYWORDS};
+use parser::{
+    examples::{examples, Example},
+    grammar::{Grammar, NonTerminal},
+    parsers::RecursiveDescentParser,
+};
+
+// Q -> K(E){S} takes any keyword, but only these start a conditional in the parser
+const CONDITIONAL_KEYWORDS: &[&str] = &["if", "while"];
+
+#[test]
+fn test_the_parser_accepts_an_example_of_every_production_rule() {
+    let rejected: Vec<(NonTerminal, usize)> = examples(&Grammar::new())
+        .into_iter()
+        .filter(|example| {
+            RecursiveDescentParser::new(Lexer::new(example.code.clone()))
+                .parse()
+                .is_err()
+        })
+        .map(
+            |Example {
+                 non_terminal, rule, ..
+             }| (non_terminal, rule),
+        )
+        .collect();
+    // the rules of K are in keyword order
+    let expected: Vec<(NonTerminal, usize)> = KEYWORDS
+        .iter()
+        .enumerate()
+        .filter(|(_, keyword)| !CONDITIONAL_KEYWORDS.contains(keyword))
+        .map(|(rule, _)| (NonTerminal::Keyword, rule))
+        .collect();
+
+    assert_eq!(rejected, expected);
+}
@@ -0,0 +1,135 @@
+use std::fs;
+
+use lexer::{lexer::Lexer, options::LexerOptions, position::LineNumber};
+use parser::{
+    incremental::{reparse, LineEdit},
+    parse_node::ParseNode,
+    parsers::{ParserResult, RecursiveDescentParser},
+};
+use rstest::rstest;
+
+// Lines edits are made of besides the ones of the test files: ones that join, split or
+// swallow statements the tree already has
+const FRAGMENTS: &[&str] = &[
+    "",
+    "}",
+    "{",
+    "else {",
+    "elif (a) {",
+    "/*",
+    "*/",
+    "/// a doc comment",
+    "// a comment",
+    "fn f() -> int {",
+    "a(1); b(2);",
+    "int x = 1",
+    "+ 2;",
+    "\"unterminated",
+];
+
+// xorshift, so every run makes the same edits and a failure can be replayed
+struct Random(u64);
+
+impl Random {
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        (self.0 % bound as u64) as usize
+    }
+}
+
+fn parse(lines: &[String], options: &LexerOptions) -> ParserResult {
+    RecursiveDescentParser::new(Lexer::new(source_of(lines)).with_options(options.clone())).parse()
+}
+
+fn source_of(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+fn render(tree: &ParseNode) -> String {
+    format!("{:?}", tree.verbose())
+}
+
+// sum in functions.cc ends its body on an expression, which a virtual semi would end
+#[rstest]
+#[case(&["test-files/main.cc", "test-files/functions.cc"], LexerOptions::default())]
+#[case(&["test-files/main.cc"], LexerOptions {
+    newline_statements: true,
+    ..LexerOptions::default()
+})]
+fn test_reparsing_an_edit_builds_the_tree_a_full_parse_does(
+    #[case] paths: &[&str],
+    #[case] options: LexerOptions,
+) {
+    let code: String = paths
+        .iter()
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect();
+    let pool: Vec<String> = code
+        .lines()
+        .chain(FRAGMENTS.iter().copied())
+        .map(str::to_owned)
+        .collect();
+    let mut lines: Vec<String> = code.lines().map(str::to_owned).collect();
+    let mut tree = parse(&lines, &options).unwrap();
+    let mut random = Random(0x5eed);
+    let mut parsed_edits = 0;
+    let mut reused = 0;
+    let mut reparsed = 0;
+
+    for _ in 0..300 {
+        let first = random.below(lines.len() + 1);
+        let last = (first + random.below(3))
+            .min(lines.len().max(1) - 1)
+            .max(first);
+        // runs of lines of the test files, so whole statements get pasted as well
+        let from = random.below(pool.len());
+        let replacement: Vec<String> = pool[from..].iter().take(random.below(5)).cloned().collect();
+        let edit = LineEdit {
+            first: LineNumber::from_zero_based(first),
+            last: LineNumber::from_zero_based(last),
+            new_line_count: replacement.len(),
+        };
+        let mut edited = lines.clone();
+
+        edited.splice(first..(last + 1).min(lines.len()), replacement);
+
+        let before = render(&tree);
+        let result = reparse(&mut tree, &source_of(&edited), &edit, &options);
+
+        match parse(&edited, &options) {
+            Ok(expected) => {
+                let progress = result.unwrap_or_else(|error| {
+                    panic!("{:?} should reparse: {:?}", edit, error);
+                });
+
+                assert_eq!(render(&tree), render(&expected), "after {:?}", edit);
+
+                lines = edited;
+                parsed_edits += 1;
+                reused += progress.reused;
+                reparsed += progress.reparsed;
+            }
+            // the edit is dropped, the tree has to be the one of the source before it
+            Err(_) => {
+                assert!(result.is_err(), "{:?} should not reparse", edit);
+                assert_eq!(render(&tree), before, "after the failed {:?}", edit);
+            }
+        }
+    }
+
+    assert!(
+        parsed_edits > 50,
+        "Too few of the edits parsed: {}!",
+        parsed_edits
+    );
+    // most statements are far from an edit
+    assert!(
+        reused > reparsed,
+        "Reused {} statements and reparsed {}!",
+        reused,
+        reparsed
+    );
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use std::fs;
+
+use common::render_diagnostic;
+use lexer::lexer::Lexer;
+use parser::parsers::RecursiveDescentParser;
+
+// Parsed with recovery, so every broken statement of a program gets its own diagnostic
+#[test]
+fn test_invalid_programs_report_the_expected_diagnostics() {
+    let mut paths: Vec<_> = fs::read_dir("test-files/invalid")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "cc"))
+        .collect();
+
+    paths.sort();
+
+    assert!(
+        !paths.is_empty(),
+        "There should be invalid programs to test!"
+    );
+
+    for path in paths {
+        let lexer = Lexer::from_file(path.to_str().unwrap()).unwrap();
+        let diagnostics: Vec<String> = RecursiveDescentParser::new(lexer)
+            .parse_with_recovery()
+            .errors
+            .iter()
+            .map(|error| render_diagnostic(error))
+            .collect();
+        let name = path.file_stem().unwrap().to_str().unwrap().to_owned();
+
+        assert!(!diagnostics.is_empty(), "{} should not parse", name);
+
+        insta::assert_snapshot!(name, diagnostics.join("\n"));
+    }
+}
@@ -0,0 +1,223 @@
+use std::{fs, path::Path};
+
+// The public items of the parser and of the lexer it re-exports, read from the sources:
+// pub declarations with their derives and cfgs, fields and enum variants with their
+// signatures, and the impl blocks that carry trait impls. Items a macro_rules! declares
+// are read where the macro is called. Tests and pub(crate) items are left out.
+//
+// A change to the snapshot is a change to the API embedders build on. When it is meant,
+// update it with `INSTA_UPDATE=always cargo test -p parser --test public_api_tests` and
+// point the change out in the review, removals and changed signatures break users
+const CRATES: [&str; 2] = ["src", "../lexer/src"];
+
+fn source_files(dir: &Path, files: &mut Vec<String>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.is_dir() {
+            source_files(&path, files);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path.to_string_lossy().into_owned());
+        }
+    }
+}
+
+// How many brackets the line leaves open, the braces of a use tree count as well
+fn depth(line: &str, braces: bool) -> i32 {
+    line.chars()
+        .map(|char| match char {
+            '(' | '[' => 1,
+            ')' | ']' => -1,
+            '{' if braces => 1,
+            '}' if braces => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn without_comment(line: &str) -> &str {
+    line.split_once("//").map_or(line, |(code, _)| code).trim()
+}
+
+// A declaration across the lines rustfmt wraps it on, put on one line without its body
+fn signature(first: &str, lines: &mut impl Iterator<Item = String>) -> String {
+    let braces = first.trim_start().starts_with("pub use");
+    let mut signature = without_comment(first).to_owned();
+    let mut open = depth(&signature, braces);
+
+    while open > 0 || signature.ends_with("where") {
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = without_comment(&line);
+
+        open += depth(line, braces);
+        signature.push(' ');
+        signature.push_str(line);
+    }
+
+    if braces {
+        signature = signature.replace("{ ", "{").replace(", }", "}");
+    }
+
+    signature
+        .replace("( ", "(")
+        .replace(", )", ")")
+        .trim_end_matches(['{', ';', ','])
+        .trim_end()
+        .to_owned()
+}
+
+// Puts the body of a macro_rules! with one arm where it is called, with its first ident
+// argument in place of the parameter, like one_based_number!(LineNumber, "Line")
+fn expand_macros(source: &str) -> String {
+    let mut macros: Vec<(String, String, Vec<&str>)> = vec![];
+    let mut expanded = vec![];
+    let mut lines = source.lines();
+
+    while let Some(line) = lines.next() {
+        if let Some(name) = line.strip_prefix("macro_rules! ") {
+            let name = name.trim_end_matches(" {").to_owned();
+            let mut body: Vec<&str> = lines.by_ref().take_while(|line| *line != "}").collect();
+            let parameter = body
+                .first()
+                .and_then(|arm| arm.split_once('$'))
+                .and_then(|(_, arm)| arm.split_once(":ident"))
+                .map(|(parameter, _)| format!("${}", parameter))
+                .unwrap_or_default();
+
+            // the arm is indented once, its body twice
+            body.retain(|line| line.is_empty() || line.starts_with("        "));
+            macros.push((name, parameter, body));
+
+            continue;
+        }
+
+        let call = macros.iter().find_map(|(name, parameter, body)| {
+            let arguments = line.strip_prefix(name.as_str())?.strip_prefix("!(")?;
+            let argument = arguments.split([',', ')']).next()?.trim();
+
+            Some((parameter, argument, body))
+        });
+
+        match call {
+            Some((parameter, argument, body)) => {
+                for line in body {
+                    expanded.push(
+                        line.get(8..)
+                            .unwrap_or("")
+                            .replace(parameter.as_str(), argument),
+                    );
+                }
+            }
+            None => expanded.push(line.to_owned()),
+        }
+    }
+
+    expanded.join("\n")
+}
+
+fn public_api(file: &str) -> Vec<String> {
+    let source = expand_macros(&fs::read_to_string(file).unwrap());
+    let mut lines = source
+        .lines()
+        .take_while(|line| *line != "#[cfg(test)]")
+        .map(str::to_owned);
+    let mut items = vec![];
+    // the indent of the variants of the pub enum the lines are in
+    let mut variants: Option<usize> = None;
+    // the derive and cfg attributes of the item the next lines declare
+    let mut attributes: Option<String> = None;
+
+    while let Some(line) = lines.next() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim_start();
+
+        if variants.is_some_and(|variants| indent < variants) {
+            variants = None;
+        }
+
+        let is_variant = variants == Some(indent) && trimmed.starts_with(char::is_uppercase);
+
+        // derives and cfgs are put before their item, the API differs between features
+        if ["#[derive(", "#[cfg(", "#[cfg_attr("]
+            .iter()
+            .any(|start| trimmed.starts_with(start))
+        {
+            let attribute = signature(&line, &mut lines).replace(" )", ")");
+
+            if !attribute.starts_with("#[cfg_attr(") || attribute.contains("derive(") {
+                attributes = Some(match attributes {
+                    Some(attributes) => format!("{} {}", attributes, attribute),
+                    None => attribute,
+                });
+            }
+
+            continue;
+        }
+
+        // other attributes and comments can be between those and their item
+        if trimmed.starts_with("#[") || trimmed.starts_with("//") {
+            continue;
+        }
+
+        let attributes = attributes.take();
+
+        if is_variant || trimmed.starts_with("pub ") || (indent == 0 && trimmed.starts_with("impl"))
+        {
+            let mut item = signature(&line, &mut lines);
+
+            // the fields of a struct variant are not pub items of their own
+            if is_variant {
+                item.truncate(item.find(" {").unwrap_or(item.len()));
+            }
+
+            if trimmed.starts_with("pub enum") && trimmed.ends_with('{') {
+                variants = Some(indent + 4);
+            }
+
+            if let Some(attributes) = attributes {
+                item = format!("{} {}", attributes, item);
+            }
+
+            items.push(format!("{}{}", " ".repeat(indent), item));
+        }
+    }
+
+    // inherent impls only matter for the pub items in them
+    let keep: Vec<bool> = (0..items.len())
+        .map(|index| {
+            !items[index].starts_with("impl")
+                || items[index].contains(" for ")
+                || items
+                    .get(index + 1)
+                    .is_some_and(|next| next.starts_with(' '))
+        })
+        .collect();
+
+    items
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(item, keep)| keep.then_some(item))
+        .collect()
+}
+
+#[test]
+fn test_the_public_api_matches_the_snapshot() {
+    let mut files = vec![];
+
+    for dir in CRATES {
+        source_files(Path::new(dir), &mut files);
+    }
+
+    files.sort();
+
+    let api: Vec<String> = files
+        .iter()
+        .map(|file| (file, public_api(file)))
+        .filter(|(_, items)| !items.is_empty())
+        .map(|(file, items)| format!("{}\n{}", file, items.join("\n")))
+        .collect();
+
+    insta::assert_snapshot!(api.join("\n\n"));
+}
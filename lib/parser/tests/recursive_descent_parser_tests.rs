@@ -1,5 +1,22 @@
-use lexer::lexer::Lexer;
-use parser::parsers::RecursiveDescentParser;
+mod common;
+
+use lexer::token::{Token, TokenClass};
+use lexer::{
+    diagnostic::{CompilerError, Span},
+    lexer::{Lexer, LexerError},
+    options::LexerOptions,
+};
+use parser::{
+    cancellation::CancellationToken,
+    options::ParserOptions,
+    parse_node::{Loc, NodeKind, ParseNode},
+    parsers::{ParserError, RecoveryStats, RecursiveDescentParser},
+};
+use rstest::rstest;
+use std::{sync::Arc, thread};
+
+fn assert_send<T: Send>() {}
+fn assert_send_sync<T: Send + Sync>() {}
 
 #[test]
 fn test_it_generates_a_correct_parse_tree() {
@@ -11,3 +28,664 @@ fn test_it_generates_a_correct_parse_tree() {
 
     insta::assert_debug_snapshot!(parser.parse().unwrap());
 }
+
+#[test]
+fn test_it_generates_a_parse_tree_of_the_expected_shape() {
+    let mut current_dir = std::env::current_dir().unwrap();
+    current_dir.push("test-files/main.cc");
+
+    let lexer = Lexer::from_file(current_dir.to_str().unwrap()).unwrap();
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+
+    assert_tree_shape!(
+        tree,
+        functions = 0,
+        statements = 28,
+        assignments = 15,
+        conditions = 6,
+        loops = 1,
+        calls = 6,
+        returns = 0,
+        blocks = 7,
+    );
+}
+
+#[test]
+fn test_it_stops_parsing_when_cancelled() {
+    let cancellation = CancellationToken::new();
+    let lexer = Lexer::new(String::from("int a = 3;"));
+    let mut parser = RecursiveDescentParser::new(lexer).with_cancellation(cancellation.clone());
+
+    cancellation.cancel();
+
+    assert!(matches!(parser.parse(), Err(ParserError::Cancelled)));
+}
+
+#[test]
+fn test_it_parses_normally_when_not_cancelled() {
+    let lexer = Lexer::new(String::from("int a = 3;"));
+    let mut parser = RecursiveDescentParser::new(lexer).with_cancellation(CancellationToken::new());
+
+    assert!(parser.parse().is_ok());
+}
+
+#[test]
+fn test_parse_results_can_be_shared_between_threads() {
+    assert_send::<RecursiveDescentParser>();
+    assert_send_sync::<ParseNode>();
+    assert_send_sync::<ParserError>();
+
+    let tree = thread::spawn(|| {
+        let lexer = Lexer::new(String::from("int a = 3;"));
+
+        RecursiveDescentParser::new(lexer).parse().unwrap()
+    })
+    .join()
+    .unwrap();
+    let tree = Arc::new(tree);
+    let shared = Arc::clone(&tree);
+    let children = thread::spawn(move || shared.children.len()).join().unwrap();
+
+    assert_eq!(children, tree.children.len());
+}
+
+#[test]
+fn test_it_parses_empty_input_into_an_empty_program() {
+    for code in ["", "   ", "\n\n\t  \n"] {
+        let tree = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+            .parse()
+            .unwrap();
+
+        assert_eq!(tree.kind, NodeKind::Program);
+        assert!(tree.children.is_empty());
+    }
+}
+
+#[test]
+fn test_it_parses_empty_blocks() {
+    let lexer = Lexer::new(String::from("if (a) {}\nwhile (b) {\n}"));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+
+    assert_tree_shape!(tree, conditions = 2, blocks = 2, statements = 2);
+}
+
+#[test]
+fn test_it_parses_blocks_as_statements() {
+    let lexer = Lexer::new(String::from(
+        "{\n    int x = 1;\n    {\n    }\n}\nint y = 2;",
+    ));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+    let block_statement = &tree.children[0];
+
+    assert_eq!(block_statement.kind, NodeKind::BlockStatement);
+    assert_eq!(block_statement.children[0].kind, NodeKind::Block);
+    assert_eq!(block_statement.end_loc, Loc::new(5, 1));
+    assert_tree_shape!(tree, statements = 4, assignments = 2, blocks = 2);
+}
+
+#[test]
+fn test_it_tracks_where_statements_end() {
+    let lexer = Lexer::new(String::from("if (a) {\n  int b = 33;\n}"));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+    let condition = &tree.children[0];
+    let block = &condition.children[1].children[3];
+    let assignment = &block.children[1];
+
+    assert_eq!(condition.loc, Loc::new(1, 1));
+    assert_eq!(condition.end_loc, Loc::new(3, 1));
+    assert_eq!(assignment.loc, Loc::new(2, 3));
+    assert_eq!(assignment.end_loc, Loc::new(2, 13));
+    assert_eq!(tree.end_loc.line, 3);
+}
+
+#[test]
+fn test_it_fails_on_unterminated_blocks() {
+    let lexer = Lexer::new(String::from("if (a) {\n  int b = 3;\n"));
+    let result = RecursiveDescentParser::new(lexer).parse();
+
+    assert!(matches!(
+        result,
+        Err(ParserError::UnexpectedEndOfFile(expected, _)) if expected.contains(&TokenClass::RCurly)
+    ));
+}
+
+#[rstest]
+#[case("foo();", 0)]
+#[case("foo(a);", 1)]
+#[case("foo(a, b);", 2)]
+#[case("foo(a, b,);", 2)]
+fn test_it_parses_call_argument_lists(#[case] code: &str, #[case] arguments: usize) {
+    let tree = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+        .parse()
+        .unwrap();
+
+    assert_tree_shape!(tree, calls = 1, expressions = arguments);
+}
+
+#[rstest]
+#[case("fn f() -> int {}", 0)]
+#[case("fn f(int a) -> int {}", 1)]
+#[case("fn f(int a, int b = 2,) -> int {}", 2)]
+fn test_it_parses_parameter_lists(#[case] code: &str, #[case] parameters: usize) {
+    let tree = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+        .parse()
+        .unwrap();
+    let arguments = &tree.children[0].children[2];
+
+    assert_eq!(arguments.kind, NodeKind::Arguments);
+    assert_eq!(
+        arguments
+            .children
+            .iter()
+            .filter(|child| child.kind == NodeKind::Argument)
+            .count(),
+        parameters
+    );
+}
+
+#[rstest]
+#[case("foo(,);")]
+#[case("foo(a,,);")]
+#[case("fn f(,) -> int {}")]
+fn test_it_rejects_commas_without_an_item(#[case] code: &str) {
+    let result = RecursiveDescentParser::new(Lexer::new(code.to_owned())).parse();
+
+    assert!(matches!(result, Err(ParserError::UnexpectedToken(..))));
+}
+
+#[test]
+fn test_it_parses_calls_in_every_for_loop_clause() {
+    let code = "for (int i = first(xs); i < len(xs); i = next(i)) {\n}";
+    let tree = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+        .parse()
+        .unwrap();
+    let for_loop = &tree.children[0];
+
+    assert_tree_shape!(tree, loops = 1, calls = 3, assignments = 2);
+    assert_eq!(for_loop.children[5].kind, NodeKind::AssignmentStatement);
+}
+
+#[rstest]
+#[case("if (int) {\n}", 1, 5)]
+#[case("while (a > (bool)) {\n}", 1, 13)]
+#[case("if (a) {\n    if (b == string) {\n    }\n}", 2, 14)]
+#[case("for (int i = 0; i < float; i++) {\n}", 1, 21)]
+fn test_it_rejects_types_in_conditions(
+    #[case] code: &str,
+    #[case] line: usize,
+    #[case] column: usize,
+) {
+    let result = RecursiveDescentParser::new(Lexer::new(code.to_owned())).parse();
+
+    match result {
+        Err(ParserError::TypeInCondition(_, window)) => {
+            assert_eq!(window.loc, Some(Loc::new(line, column)));
+        }
+        result => panic!("Expected a type in condition error, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_it_shows_the_tokens_around_a_parse_error() {
+    let lexer = Lexer::new(String::from("int a = 3\nint b = 5;\nint c = 7;"));
+    let result = RecursiveDescentParser::new(lexer).parse();
+
+    match result {
+        Err(ParserError::InvalidInitializer(variable, actual, window)) => {
+            assert_eq!(variable, "a");
+            assert_eq!(actual, "type `int`");
+            assert_eq!(
+                window.previous,
+                vec!["1:5 identifier `a`", "1:7 `=`", "1:9 number `3`"]
+            );
+            assert_eq!(
+                window.next,
+                vec!["2:1 type `int`", "2:5 identifier `b`", "2:7 `=`"]
+            );
+            assert_eq!(window.loc, Some(Loc::new(2, 1)));
+        }
+        result => panic!("Expected an invalid initializer error, got {:?}", result),
+    }
+}
+
+#[rstest]
+#[case("int x = { System.print(x); }\nint y = 3;", 1, 9)]
+#[case("if (a) {\n    int x = 3\n}\nint y = 3;", 3, 1)]
+#[case("int x = 3\nwhile (x) {\n}\nint y = 3;", 2, 1)]
+fn test_it_stops_initializers_at_statement_starts(
+    #[case] code: &str,
+    #[case] line: usize,
+    #[case] column: usize,
+) {
+    let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+    let recovered = parser.parse_with_recovery();
+
+    assert!(matches!(
+        &recovered.errors[..],
+        [ParserError::InvalidInitializer(variable, _, window)]
+            if variable == "x" && window.loc == Some(Loc::new(line, column))
+    ));
+    assert_tree_shape!(recovered.tree, assignments = 1);
+}
+
+#[test]
+fn test_it_recovers_at_statement_boundaries() {
+    let code =
+        "int a = );\nSystem.print(a;\nint b = 3;\nif (b > ) {\n    System.print(b);\n}\nint c = 4;";
+    let mut parser = RecursiveDescentParser::new(Lexer::new(code.to_owned()));
+    let recovered = parser.parse_with_recovery();
+
+    assert_eq!(recovered.errors.len(), 3);
+    assert_eq!(
+        recovered.stats,
+        RecoveryStats {
+            recoveries: 3,
+            // System.print is three tokens
+            skipped_tokens: 11,
+        }
+    );
+    assert_tree_shape!(recovered.tree, assignments = 2, calls = 0, conditions = 0);
+}
+
+#[test]
+fn test_it_uses_the_configured_synchronization_tokens() {
+    let code = "int a = ) int b = 3;";
+    let recovered = RecursiveDescentParser::new(Lexer::new(code.to_owned())).parse_with_recovery();
+
+    // by default the int keyword starts the next statement
+    assert_eq!(recovered.stats.skipped_tokens, 1);
+    assert_tree_shape!(recovered.tree, assignments = 1);
+
+    let options = ParserOptions {
+        synchronization_tokens: vec![Token::Semi],
+    };
+    let recovered = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+        .with_options(options)
+        .parse_with_recovery();
+
+    assert_eq!(recovered.errors.len(), 1);
+    assert_eq!(recovered.stats.skipped_tokens, 5);
+    assert_tree_shape!(recovered.tree, assignments = 0);
+}
+
+#[test]
+fn test_it_stops_recovering_when_cancelled() {
+    let cancellation = CancellationToken::new();
+    cancellation.cancel();
+
+    let lexer = Lexer::new(String::from("int a = ;\nint b = 3;"));
+    let recovered = RecursiveDescentParser::new(lexer)
+        .with_cancellation(cancellation)
+        .parse_with_recovery();
+
+    assert!(matches!(recovered.errors[..], [ParserError::Cancelled]));
+}
+
+#[test]
+fn test_it_shows_fewer_tokens_near_the_end_of_the_file() {
+    let lexer = Lexer::new(String::from("System.print(a)"));
+    let result = RecursiveDescentParser::new(lexer).parse();
+
+    match result {
+        Err(ParserError::UnexpectedToken(_, _, window)) => {
+            assert_eq!(window.previous.len(), 3);
+            assert!(window.next.is_empty());
+            assert!(window.loc.is_none());
+        }
+        result => panic!("Expected an unexpected token error, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_it_surfaces_lexer_errors_instead_of_ending_the_program() {
+    let lexer = Lexer::new(String::from("int a = 3;\n999999999999999999999999"));
+    let result = RecursiveDescentParser::new(lexer).parse();
+
+    assert!(matches!(
+        result,
+        Err(ParserError::LexerError(LexerError::NumberOutOfRange { line, .. })) if line == 2
+    ));
+}
+
+#[rstest]
+#[case("int a = 3;\nint b = );", "P0001", Some(Loc::new(2, 9)))]
+#[case("int a = 3;\n999999999999999999999999", "L0006", Some(Loc::new(2, 1)))]
+#[case("if (a) {\n", "P0002", None)]
+fn test_parser_errors_share_the_compiler_error_shape(
+    #[case] code: &str,
+    #[case] error_code: &str,
+    #[case] loc: Option<Loc>,
+) {
+    let error = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+        .parse()
+        .unwrap_err();
+
+    assert_eq!(error.code(), error_code);
+    assert_eq!(error.span(), loc.as_ref().map(Span::from));
+    assert!(error
+        .render()
+        .starts_with(&format!("error[{}]: ", error_code)));
+}
+
+#[test]
+fn test_it_can_be_reset_to_parse_another_document() {
+    let mut parser = RecursiveDescentParser::new(Lexer::new(String::from("int a = 3;")));
+
+    assert_tree_shape!(parser.parse().unwrap(), assignments = 1);
+
+    parser.reset(String::from("System.print(a);\nSystem.print(b);"));
+
+    assert_tree_shape!(parser.parse().unwrap(), assignments = 0, calls = 2);
+}
+
+#[test]
+fn test_it_keeps_the_lexer_options_when_reset() {
+    let lexer = Lexer::new(String::from("int a = 3\n")).with_options(LexerOptions {
+        newline_statements: true,
+        ..LexerOptions::default()
+    });
+    let mut parser = RecursiveDescentParser::new(lexer);
+
+    assert_tree_shape!(parser.parse().unwrap(), assignments = 1);
+
+    parser.reset(String::from("System.print(a)\nSystem.print(b)\n"));
+
+    assert_tree_shape!(parser.parse().unwrap(), calls = 2);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_it_can_parse_a_source_loaded_from_an_async_reader() {
+    let reader = futures::io::Cursor::new(b"int a = 3;\nSystem.print(a);".to_vec());
+    let mut parser =
+        futures::executor::block_on(RecursiveDescentParser::from_async_reader(reader)).unwrap();
+
+    assert_tree_shape!(parser.parse().unwrap(), assignments = 1, calls = 1);
+}
+
+#[test]
+fn test_it_parses_statements_terminated_by_newlines() {
+    let code = "int a = 3\nif (a > 2) {\n    System.print(a)\n}\n";
+    let lexer = Lexer::new(code.to_owned()).with_options(LexerOptions {
+        newline_statements: true,
+        ..LexerOptions::default()
+    });
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+
+    assert_tree_shape!(tree, assignments = 1, conditions = 1, calls = 1);
+}
+
+#[test]
+fn test_it_requires_a_type_where_one_is_expected() {
+    for code in [
+        "else x = 3;",
+        "fn f(while a) {}",
+        "fn f(int a) -> return {}",
+    ] {
+        let result = RecursiveDescentParser::new(Lexer::new(code.to_owned())).parse();
+
+        assert!(
+            matches!(
+                result,
+                Err(ParserError::UnexpectedToken(ref expected, _, _))
+                    if expected.contains(&TokenClass::Type)
+            ),
+            "{} should need a type, got {:?}",
+            code,
+            result
+        );
+    }
+}
+
+#[test]
+fn test_it_parses_calls_with_any_number_of_arguments() {
+    let lexer = Lexer::new(String::from(
+        "System.print();\nSystem.print(\"{} {}\", a, b + 1);",
+    ));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+    let expressions = |call: &ParseNode| {
+        call.children
+            .iter()
+            .filter(|child| child.kind == NodeKind::Expression)
+            .count()
+    };
+
+    assert_eq!(expressions(&tree.children[0]), 0);
+    assert_eq!(expressions(&tree.children[1]), 3);
+}
+
+#[test]
+fn test_it_parses_declarations_without_initializers() {
+    let lexer = Lexer::new(String::from("int a;\nint b = 3;"));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+
+    assert_eq!(tree.children[0].kind, NodeKind::VariableDeclaration);
+    assert_eq!(tree.children[0].children.len(), 3);
+    assert_tree_shape!(tree, assignments = 1, statements = 2);
+}
+
+#[test]
+fn test_it_reports_an_operator_left_at_the_end_of_the_file() {
+    let lexer = Lexer::new(String::from("int a = 3 +\n"));
+    let recovered = RecursiveDescentParser::new(lexer).parse_with_recovery();
+
+    assert!(matches!(
+        recovered.errors[..],
+        [ParserError::LexerError(LexerError::OperatorAtEndOfFile { line, .. })] if line == 1
+    ));
+}
+
+#[test]
+fn test_it_attaches_doc_comments_to_functions_and_declarations() {
+    let code =
+        "/// The answer\nint a = 42;\n/// Prints it\nfn show() -> bool { return true; }\nint b;";
+    let tree = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+        .parse()
+        .unwrap();
+
+    assert_eq!(tree.children[0].doc(), Some("The answer"));
+    assert_eq!(tree.children[0].end_loc, Loc::new(2, 11));
+    assert_eq!(tree.children[1].doc(), Some("Prints it"));
+    assert_eq!(tree.children[2].doc(), None);
+}
+
+#[test]
+fn test_it_parses_indexes_and_slices_after_values() {
+    let lexer = Lexer::new(String::from(
+        "char c = word[0];\nstring part = word[1..i + 1];\nchar last = name(1)[0][2];",
+    ));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+    let initializer = |statement: usize| &tree.children[statement].children[3];
+
+    assert_eq!(initializer(0).children[1].kind, NodeKind::IndexExpression);
+    assert_eq!(
+        initializer(1).children[1].children[1].children[1]
+            .value
+            .as_deref(),
+        Some("..")
+    );
+    assert_eq!(initializer(2).children[0].kind, NodeKind::FunctionCall);
+    assert_eq!(initializer(2).children[1].kind, NodeKind::IndexExpression);
+    assert_eq!(initializer(2).children[2].kind, NodeKind::IndexExpression);
+}
+
+#[test]
+fn test_it_rejects_an_index_that_is_not_closed() {
+    let lexer = Lexer::new(String::from("char c = word[0;"));
+    let error = RecursiveDescentParser::new(lexer).parse().unwrap_err();
+
+    assert!(matches!(
+        error,
+        ParserError::UnexpectedToken(expected, _, _) if expected.contains(&TokenClass::RBracket)
+    ));
+}
+
+#[test]
+fn test_it_returns_the_trailing_expression_of_a_function_body() {
+    let lexer = Lexer::new(String::from(
+        "fn add(int a, int b) -> int { a + b }\nfn sum(int a) -> int {\n    print(a);\n    add(a, 1)\n}",
+    ));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+    let body = |function: usize| &tree.children[function].children[5];
+    let implicit_return = &body(0).children[1];
+
+    assert_eq!(implicit_return.kind, NodeKind::ReturnStatement);
+    assert_eq!(implicit_return.children.len(), 1);
+    assert_eq!(body(1).children[1].kind, NodeKind::FunctionCall);
+    assert_eq!(body(1).children[2].kind, NodeKind::ReturnStatement);
+    assert_tree_shape!(tree, functions = 2, returns = 2, calls = 2);
+}
+
+#[rstest]
+#[case("fn add(int a) -> int { a + 1; }", "`}`")]
+#[case("fn add(int a) -> int { show(a) a }", "one of: `}`, `;`")]
+#[case("if (a) { a + 1 }", "`(`")]
+fn test_it_only_returns_an_expression_at_the_end_of_a_function_body(
+    #[case] code: &str,
+    #[case] expected: &str,
+) {
+    let error = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+        .parse()
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        ParserError::UnexpectedToken(tokens, _, _) if tokens.to_string() == expected
+    ));
+}
+
+fn suggesting_lexer(code: &str) -> Lexer {
+    Lexer::new(code.to_owned()).with_options(LexerOptions {
+        suggest_keywords: true,
+        ..LexerOptions::default()
+    })
+}
+
+#[rstest]
+#[case("whle (a) {\n}", "whle", "while")]
+#[case("int a = 1;\nretrun a;", "retrun", "return")]
+#[case("fn main() -> int {\n    esle;\n}", "esle", "else")]
+fn test_it_suggests_the_keyword_an_identifier_of_the_failed_statement_is_close_to(
+    #[case] code: &str,
+    #[case] word: &str,
+    #[case] keyword: &str,
+) {
+    let error = RecursiveDescentParser::new(suggesting_lexer(code))
+        .parse()
+        .unwrap_err();
+
+    assert_eq!(error.code(), "P0006");
+    assert!(matches!(
+        error,
+        ParserError::MisspelledKeyword(_, _, suggestion, _)
+            if suggestion.word == word && suggestion.keyword == keyword
+    ));
+}
+
+#[test]
+fn test_it_only_suggests_keywords_for_the_statement_that_failed() {
+    let code = "form(1);\nshow(;";
+    let error = RecursiveDescentParser::new(suggesting_lexer(code))
+        .parse()
+        .unwrap_err();
+    let without_suggestions = RecursiveDescentParser::new(Lexer::new("whle (a) {}".to_owned()))
+        .parse()
+        .unwrap_err();
+
+    assert!(matches!(error, ParserError::UnexpectedToken(..)));
+    assert!(matches!(
+        without_suggestions,
+        ParserError::UnexpectedToken(..)
+    ));
+}
+
+#[rstest]
+#[case("int a = -5;")]
+#[case("int a=-5;\nfloat b = -1.5 * -(a + 2);")]
+#[case("if (a > -1) {\n}\nwhile (-a < b) {\n}")]
+#[case("for (int i = -10; i < -1; i = i - -1) {\n}")]
+#[case("show(-a, - 3);")]
+#[case("bool a = !b != !(c && d);")]
+#[case("while (!done) {\n    if (!(a != b)) {\n    }\n}")]
+fn test_it_parses_negated_expressions(#[case] code: &str) {
+    let lexer = Lexer::new(code.to_owned());
+
+    assert!(RecursiveDescentParser::new(lexer).parse().is_ok());
+}
+
+// ! only goes before its operand
+#[rstest]
+#[case("int a = b ! c;")]
+#[case("show(a !);")]
+fn test_it_does_not_take_a_not_between_two_operands(#[case] code: &str) {
+    let lexer = Lexer::new(code.to_owned());
+
+    assert!(matches!(
+        RecursiveDescentParser::new(lexer).parse(),
+        Err(ParserError::UnexpectedToken(..))
+    ));
+}
+
+#[test]
+fn test_a_not_at_the_end_of_the_file_is_missing_its_operand() {
+    let lexer = Lexer::new(String::from("bool a = !"));
+
+    assert!(matches!(
+        RecursiveDescentParser::new(lexer).parse(),
+        Err(ParserError::UnexpectedEndOfFile(..))
+    ));
+}
+
+#[test]
+fn test_it_negates_the_operand_after_a_minus() {
+    let lexer = Lexer::new(String::from("int a = -5 + b;"));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+    let initializer = &tree.children[0].children[3];
+
+    assert_eq!(initializer.loc, Loc::new(1, 9));
+    assert_eq!(initializer.children.len(), 4);
+    assert_eq!(
+        initializer.children[0].kind,
+        NodeKind::TokenClass(TokenClass::Operator)
+    );
+    assert_eq!(initializer.children[0].value.as_deref(), Some("-"));
+    assert_eq!(initializer.children[1].kind, NodeKind::Expression);
+    assert_eq!(initializer.children[1].children.len(), 1);
+    assert_eq!(
+        initializer.children[1].children[0].value.as_deref(),
+        Some("5")
+    );
+    assert_eq!(initializer.children[2].value.as_deref(), Some("+"));
+    assert_eq!(
+        initializer.children[3].children[0].value.as_deref(),
+        Some("b")
+    );
+}
+
+#[test]
+fn test_an_index_after_a_negated_operand_goes_on_from_it() {
+    let lexer = Lexer::new(String::from("int a = -b[1];"));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+    let initializer = &tree.children[0].children[3];
+
+    assert_eq!(initializer.children[0].value.as_deref(), Some("-"));
+    assert_eq!(initializer.children[1].children.len(), 1);
+    assert_eq!(initializer.children[2].kind, NodeKind::IndexExpression);
+}
+
+#[test]
+fn test_it_joins_the_parts_of_a_name() {
+    let lexer = Lexer::new(String::from("System.print(a.b.c);"));
+    let tree = RecursiveDescentParser::new(lexer).parse().unwrap();
+    let call = &tree.children[0];
+    let argument = &call.children[2].children[0];
+
+    assert_eq!(call.children[0].value.as_deref(), Some("System.print"));
+    assert_eq!(argument.value.as_deref(), Some("a.b.c"));
+    assert_eq!(argument.loc, Loc::new(1, 14));
+    assert_eq!(argument.end_loc, Loc::new(1, 18));
+    assert!(matches!(
+        RecursiveDescentParser::new(Lexer::new(String::from("a.;"))).parse(),
+        Err(ParserError::UnexpectedToken(..))
+    ));
+}
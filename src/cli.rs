@@ -0,0 +1,356 @@
+use std::{path::PathBuf, str::FromStr};
+
+use lexer::diagnostic::{CompilerError, Span};
+use parser::extract::Extraction;
+use strum::EnumProperty;
+use thiserror::Error as ThisError;
+
+use crate::{emit::Emit, normalize::DEFAULT_TAB_WIDTH};
+
+pub const DEFAULT_INPUT: &str = "./test-files/main.cc";
+
+#[derive(ThisError, Debug, EnumProperty)]
+pub enum CliError {
+    #[strum(props(code = "D0001"))]
+    #[error("Unknown artifact to emit: {0}!")]
+    UnknownEmit(String),
+    #[strum(props(code = "D0002"))]
+    #[error("Missing value for option: {0}!")]
+    MissingValue(String),
+    #[strum(props(code = "D0003"))]
+    #[error("Unknown option: {0}!")]
+    UnknownOption(String),
+    #[strum(props(code = "D0006"))]
+    #[error("Invalid position: {0}, expected line:column!")]
+    InvalidPosition(String),
+    #[strum(props(code = "D0007"))]
+    #[error("Invalid tab width: {0}, expected a number above 0!")]
+    InvalidTabWidth(String),
+}
+
+impl CompilerError for CliError {
+    fn code(&self) -> &'static str {
+        self.get_str("code")
+            .expect("Every cli error should have a code!")
+    }
+
+    fn span(&self) -> Option<Span> {
+        None
+    }
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub enum Command {
+    #[default]
+    Compile,
+    // reports warnings about the program instead of compiling it
+    Analyze,
+    // prints the functions of the program and their doc comments as Markdown
+    Doc,
+    // checks the compiler against its own invariants, it takes no input
+    SelfCheck,
+    // prints the edits that rename the variable or function at line:column
+    Rename {
+        line: usize,
+        column: usize,
+        new_name: String,
+    },
+    // prints the edit that moves the expression from start to end into a variable or function
+    Extract {
+        extraction: Extraction,
+        start: (usize, usize),
+        end: (usize, usize),
+        name: String,
+    },
+    // rewrites the input with tabs expanded and without trailing whitespace
+    Normalize {
+        tab_width: usize,
+    },
+    // parses the source of a repro file again, the input is the repro file
+    Replay,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub input: PathBuf,
+    pub out_dir: PathBuf,
+    pub emit: Vec<Emit>,
+    // writes a repro file to the out dir when parsing fails or panics
+    pub dump_repro: bool,
+    // prints how long lexing, parsing and the semantic checks take, and what they allocate
+    pub timings: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            command: Command::default(),
+            input: PathBuf::from(DEFAULT_INPUT),
+            out_dir: PathBuf::from("."),
+            emit: vec![],
+            dump_repro: false,
+            timings: false,
+        }
+    }
+}
+
+impl Options {
+    // e.g. 3:14, both start at 1
+    fn parse_position(position: &str) -> Result<(usize, usize), CliError> {
+        let invalid = || CliError::InvalidPosition(position.to_owned());
+        let (line, column) = position.split_once(':').ok_or_else(invalid)?;
+
+        match (line.parse(), column.parse()) {
+            (Ok(line), Ok(column)) if line > 0 && column > 0 => Ok((line, column)),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut options = Self::default();
+        let mut args = args.into_iter().peekable();
+
+        // the subcommand, when there is one, comes first
+        match args.peek().map(String::as_str) {
+            Some("analyze") => options.command = Command::Analyze,
+            Some("doc") => options.command = Command::Doc,
+            Some("self-check") => options.command = Command::SelfCheck,
+            Some("replay") => options.command = Command::Replay,
+            Some("normalize") => {
+                options.command = Command::Normalize {
+                    tab_width: DEFAULT_TAB_WIDTH,
+                }
+            }
+            Some("rename") => {
+                options.command = Command::Rename {
+                    line: 0,
+                    column: 0,
+                    new_name: String::new(),
+                }
+            }
+            Some(command @ ("extract-variable" | "extract-function")) => {
+                options.command = Command::Extract {
+                    extraction: if command == "extract-variable" {
+                        Extraction::Variable
+                    } else {
+                        Extraction::Function
+                    },
+                    start: (0, 0),
+                    end: (0, 0),
+                    name: String::new(),
+                }
+            }
+            _ => {}
+        }
+
+        if options.command != Command::Compile {
+            args.next();
+        }
+
+        // rename <line:column> <new name>, before the input and the options
+        if let Command::Rename {
+            line,
+            column,
+            new_name,
+        } = &mut options.command
+        {
+            let position = args
+                .next()
+                .ok_or_else(|| CliError::MissingValue("rename".to_owned()))?;
+
+            (*line, *column) = Self::parse_position(&position)?;
+            *new_name = args
+                .next()
+                .ok_or_else(|| CliError::MissingValue("rename".to_owned()))?;
+        }
+
+        // extract-variable <start line:column> <end line:column> <name>, the same for functions
+        if let Command::Extract {
+            extraction,
+            start,
+            end,
+            name,
+        } = &mut options.command
+        {
+            let mut next = || {
+                args.next().ok_or_else(|| {
+                    CliError::MissingValue(
+                        match extraction {
+                            Extraction::Variable => "extract-variable",
+                            Extraction::Function => "extract-function",
+                        }
+                        .to_owned(),
+                    )
+                })
+            };
+
+            *start = Self::parse_position(&next()?)?;
+            *end = Self::parse_position(&next()?)?;
+            *name = next()?;
+        }
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                arg if arg.starts_with("--emit=") => {
+                    for emit in arg["--emit=".len()..].split(',') {
+                        let emit = Emit::from_str(emit)
+                            .map_err(|_| CliError::UnknownEmit(emit.to_owned()))?;
+
+                        if !options.emit.contains(&emit) {
+                            options.emit.push(emit);
+                        }
+                    }
+                }
+                "--out-dir" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| CliError::MissingValue("--out-dir".to_owned()))?;
+
+                    options.out_dir = PathBuf::from(value);
+                }
+                "--dump-repro" => options.dump_repro = true,
+                "--timings" => options.timings = true,
+                "--tab-width" if matches!(options.command, Command::Normalize { .. }) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| CliError::MissingValue("--tab-width".to_owned()))?;
+                    let tab_width = value
+                        .parse()
+                        .ok()
+                        .filter(|tab_width| *tab_width > 0)
+                        .ok_or(CliError::InvalidTabWidth(value))?;
+
+                    options.command = Command::Normalize { tab_width };
+                }
+                arg if arg.starts_with("--") => {
+                    return Err(CliError::UnknownOption(arg.to_owned()));
+                }
+                input => options.input = PathBuf::from(input),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, CliError> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn it_falls_back_to_the_default_input() {
+        assert_eq!(parse(&[]).unwrap(), Options::default());
+    }
+
+    #[test]
+    fn it_can_parse_input_and_emit_options() {
+        let options = parse(&["main.cc", "--emit=tokens,cst,tokens", "--out-dir", "out"]).unwrap();
+
+        assert_eq!(options.input, PathBuf::from("main.cc"));
+        assert_eq!(options.out_dir, PathBuf::from("out"));
+        assert_eq!(options.emit, vec![Emit::Tokens, Emit::Cst]);
+        assert!(!options.dump_repro);
+        assert!(parse(&["main.cc", "--dump-repro"]).unwrap().dump_repro);
+        assert!(parse(&["main.cc", "--timings"]).unwrap().timings);
+    }
+
+    #[test]
+    fn it_can_parse_subcommands() {
+        let options = parse(&["analyze", "main.cc"]).unwrap();
+
+        assert_eq!(options.command, Command::Analyze);
+        assert_eq!(options.input, PathBuf::from("main.cc"));
+        assert_eq!(parse(&["doc"]).unwrap().command, Command::Doc);
+        assert_eq!(parse(&["self-check"]).unwrap().command, Command::SelfCheck);
+        assert_eq!(
+            parse(&["replay", "main.repro"]).unwrap().input,
+            PathBuf::from("main.repro")
+        );
+        assert_eq!(parse(&["main.cc"]).unwrap().command, Command::Compile);
+    }
+
+    #[test]
+    fn it_can_parse_the_rename_subcommand() {
+        let options = parse(&["rename", "3:14", "sum", "main.cc"]).unwrap();
+
+        assert_eq!(
+            options.command,
+            Command::Rename {
+                line: 3,
+                column: 14,
+                new_name: "sum".to_owned(),
+            }
+        );
+        assert_eq!(options.input, PathBuf::from("main.cc"));
+        assert!(matches!(
+            parse(&["rename", "3", "sum"]),
+            Err(CliError::InvalidPosition(x)) if x == "3"
+        ));
+        assert!(matches!(
+            parse(&["rename", "3:1"]),
+            Err(CliError::MissingValue(x)) if x == "rename"
+        ));
+    }
+
+    #[test]
+    fn it_can_parse_the_extract_subcommands() {
+        let options = parse(&["extract-function", "2:5", "2:14", "total", "main.cc"]).unwrap();
+
+        assert_eq!(
+            options.command,
+            Command::Extract {
+                extraction: Extraction::Function,
+                start: (2, 5),
+                end: (2, 14),
+                name: "total".to_owned(),
+            }
+        );
+        assert_eq!(options.input, PathBuf::from("main.cc"));
+        assert!(matches!(
+            parse(&["extract-variable", "2:5", "2:14"]),
+            Err(CliError::MissingValue(x)) if x == "extract-variable"
+        ));
+    }
+
+    #[test]
+    fn it_can_parse_the_normalize_subcommand() {
+        assert_eq!(
+            parse(&["normalize"]).unwrap().command,
+            Command::Normalize { tab_width: 4 }
+        );
+
+        let options = parse(&["normalize", "main.cc", "--tab-width", "2"]).unwrap();
+
+        assert_eq!(options.command, Command::Normalize { tab_width: 2 });
+        assert_eq!(options.input, PathBuf::from("main.cc"));
+        assert!(matches!(
+            parse(&["normalize", "--tab-width", "0"]),
+            Err(CliError::InvalidTabWidth(x)) if x == "0"
+        ));
+        assert!(matches!(
+            parse(&["--tab-width", "2"]),
+            Err(CliError::UnknownOption(x)) if x == "--tab-width"
+        ));
+    }
+
+    #[test]
+    fn it_fails_on_unknown_emit_values() {
+        assert!(matches!(
+            parse(&["--emit=tokens,llvm"]),
+            Err(CliError::UnknownEmit(x)) if x == "llvm"
+        ));
+    }
+
+    #[test]
+    fn it_fails_on_unknown_options() {
+        assert!(matches!(
+            parse(&["--fast"]),
+            Err(CliError::UnknownOption(x)) if x == "--fast"
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+use lexer::token::TokenClass;
+use parser::parse_node::{NodeKind, ParseNode};
+
+// The values of the tokens under a node, e.g. the default of a parameter
+fn source_text(node: &ParseNode) -> Vec<&str> {
+    match &node.value {
+        Some(value) if node.children.is_empty() => vec![value],
+        _ => node.children.iter().flat_map(source_text).collect(),
+    }
+}
+
+fn parameter(argument: &ParseNode) -> String {
+    let mut parts = vec![];
+
+    for child in &argument.children {
+        match child.kind {
+            NodeKind::Expression => parts.push(source_text(child).join(" ")),
+            NodeKind::TokenClass(TokenClass::Assignment) => parts.push(String::from("=")),
+            _ => parts.extend(child.value.clone()),
+        }
+    }
+
+    parts.join(" ")
+}
+
+// The signature as written, e.g. pure fn add(int a, int b = 5) -> int
+fn signature(definition: &ParseNode) -> String {
+    let mut signature = String::new();
+
+    for child in &definition.children {
+        match &child.kind {
+            NodeKind::Arguments => {
+                let parameters: Vec<String> = child
+                    .children
+                    .iter()
+                    .filter(|argument| argument.kind == NodeKind::Argument)
+                    .map(parameter)
+                    .collect();
+
+                signature.push_str(&format!("({})", parameters.join(", ")));
+            }
+            NodeKind::TokenClass(TokenClass::Identifier) => {
+                signature.push_str(&format!(" {}", child.value.as_deref().unwrap_or_default()))
+            }
+            NodeKind::TokenClass(TokenClass::Arrow) => signature.push_str(" ->"),
+            NodeKind::TokenClass(_) => {
+                if !signature.is_empty() {
+                    signature.push(' ');
+                }
+
+                signature.push_str(child.value.as_deref().unwrap_or_default());
+            }
+            _ => {}
+        }
+    }
+
+    signature
+}
+
+fn collect_functions<'a>(node: &'a ParseNode, functions: &mut Vec<&'a ParseNode>) {
+    if node.kind == NodeKind::FunctionDefinition {
+        functions.push(node);
+    }
+
+    for child in &node.children {
+        collect_functions(child, functions);
+    }
+}
+
+// A Markdown listing of the functions of a program, with their doc comments
+pub fn render_markdown(program: &ParseNode) -> String {
+    let mut functions = vec![];
+    let mut markdown = String::from("# Functions\n");
+
+    collect_functions(program, &mut functions);
+
+    for function in functions {
+        markdown.push_str(&format!("\n## `{}`\n", signature(function)));
+
+        if let Some(doc) = function.doc() {
+            markdown.push_str(&format!("\n{}\n", doc));
+        }
+    }
+
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lexer::lexer::Lexer;
+    use parser::parsers::RecursiveDescentParser;
+
+    #[test]
+    fn it_lists_functions_with_their_doc_comments() {
+        let code = "/// Adds two numbers\n/// b is optional\npure fn add(int a, int b = 5) -> int { return a + b; }\nfn log() -> bool { return true; }";
+        let program = RecursiveDescentParser::new(Lexer::new(code.to_owned()))
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            render_markdown(&program),
+            "# Functions\n\n## `pure fn add(int a, int b = 5) -> int`\n\nAdds two numbers\nb is optional\n\n## `fn log() -> bool`\n"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    fs,
+    io::Error as IOError,
+    path::{Path, PathBuf},
+};
+
+use lexer::{
+    diagnostic::{CompilerError, Span},
+    lexer::{Lexer, LexerError},
+};
+use parser::{
+    graphml::GraphMlExporter,
+    parsers::{ParserError, RecursiveDescentParser},
+    semantics::CallGraph,
+};
+use strum::{Display, EnumProperty, EnumString};
+use thiserror::Error as ThisError;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, EnumString, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Emit {
+    Tokens,
+    Cst,
+    Ast,
+    Ir,
+    Bytecode,
+    C,
+    Wat,
+    // the call graph, as Graphviz source
+    Dot,
+    // the parse tree with the kind, value and span of every node, for graph tooling
+    Graphml,
+}
+
+// Lexer and parser errors keep their own code
+#[derive(ThisError, Debug, EnumProperty)]
+pub enum EmitError {
+    #[strum(props(code = "D0004"))]
+    #[error("Emitting {0} is not supported yet!")]
+    Unsupported(Emit),
+    #[strum(props(code = "D0005"))]
+    #[error("Failed to write {0}!")]
+    FailedToWrite(PathBuf, #[source] IOError),
+    #[error("Lexer has failed!")]
+    LexerError(#[from] LexerError),
+    #[error("Parser has failed!")]
+    ParserError(#[from] ParserError),
+}
+
+impl CompilerError for EmitError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::LexerError(error) => error.code(),
+            Self::ParserError(error) => error.code(),
+            error => error
+                .get_str("code")
+                .expect("Every emit error should have a code!"),
+        }
+    }
+
+    fn span(&self) -> Option<Span> {
+        match self {
+            Self::LexerError(error) => error.span(),
+            Self::ParserError(error) => error.span(),
+            _ => None,
+        }
+    }
+}
+
+impl Emit {
+    // Artifacts are named after the input file, e.g. main.cc -> main.tokens
+    pub fn output_path(&self, input: &Path, out_dir: &Path) -> PathBuf {
+        let stem = input.file_stem().unwrap_or_default();
+
+        out_dir.join(format!("{}.{}", stem.to_string_lossy(), self))
+    }
+
+    pub fn render(&self, input: &str) -> Result<String, EmitError> {
+        match self {
+            Self::Tokens => {
+                let mut lexer = Lexer::from_file(input)?;
+                let mut output = String::from("");
+
+                loop {
+                    match lexer.next() {
+                        Ok(token_info) => output.push_str(&format!("{}\n", token_info)),
+                        Err(LexerError::EndOfFileReached) => break,
+                        Err(error) => return Err(error.into()),
+                    }
+                }
+
+                Ok(output)
+            }
+            Self::Cst => {
+                let mut parser = RecursiveDescentParser::new(Lexer::from_file(input)?);
+
+                Ok(parser.parse()?.to_string())
+            }
+            Self::Dot => {
+                let mut parser = RecursiveDescentParser::new(Lexer::from_file(input)?);
+
+                Ok(CallGraph::of(&parser.parse()?).to_dot())
+            }
+            Self::Graphml => {
+                let mut parser = RecursiveDescentParser::new(Lexer::from_file(input)?);
+
+                Ok(GraphMlExporter::export(&parser.parse()?))
+            }
+            emit => Err(EmitError::Unsupported(*emit)),
+        }
+    }
+
+    pub fn write(&self, input: &Path, out_dir: &Path) -> Result<PathBuf, EmitError> {
+        let output = self.render(&input.to_string_lossy())?;
+        let path = self.output_path(input, out_dir);
+
+        fs::write(&path, output).map_err(|error| EmitError::FailedToWrite(path.clone(), error))?;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_names_artifacts_after_the_input_file() {
+        let path = Emit::Tokens.output_path(Path::new("./test-files/main.cc"), Path::new("out"));
+
+        assert_eq!(path, PathBuf::from("out/main.tokens"));
+    }
+
+    #[test]
+    fn it_keeps_dots_in_the_input_file_name() {
+        let path = Emit::Cst.output_path(Path::new("my.test.cc"), Path::new("out"));
+
+        assert_eq!(path, PathBuf::from("out/my.test.cst"));
+    }
+
+    #[test]
+    fn it_renders_tokens_one_per_line() {
+        let tokens = Emit::Tokens.render("./test-files/main.cc").unwrap();
+
+        assert!(tokens.starts_with("1:1 TYPE: int\n1:5 IDENTIFIER: a\n"));
+    }
+
+    #[test]
+    fn it_renders_the_call_graph() {
+        let dot = Emit::Dot.render("./test-files/main.cc").unwrap();
+
+        assert!(dot.starts_with("digraph calls {\n"));
+        assert!(dot.contains("\"<program>\" -> \"System.print\";\n"));
+    }
+
+    #[test]
+    fn it_renders_the_parse_tree_as_graphml() {
+        let graphml = Emit::Graphml.render("./test-files/main.cc").unwrap();
+
+        assert!(graphml.contains("<graph id=\"tree\" edgedefault=\"directed\">\n"));
+        assert!(graphml.contains("<data key=\"kind\">program</data>"));
+    }
+
+    #[test]
+    fn it_reports_unsupported_artifacts() {
+        assert!(matches!(
+            Emit::Ir.render("./test-files/main.cc"),
+            Err(EmitError::Unsupported(Emit::Ir))
+        ));
+    }
+}
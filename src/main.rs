@@ -1,13 +1,276 @@
-use lexer::lexer::Lexer;
-use parser::parsers::RecursiveDescentParser;
+mod cli;
+mod doc;
+mod emit;
+mod normalize;
+mod repro;
+mod timings;
+
+use std::{any::Any, fs, panic, path::Path};
+
+use cli::{Command, Options};
+use lexer::{
+    diagnostic::CompilerError,
+    lexer::{Lexer, LexerError},
+    options::LexerOptions,
+};
+use parser::{
+    extract::{extract, Extraction},
+    parse_node::{Loc, ParseNode},
+    parsers::RecursiveDescentParser,
+    rename::rename,
+    self_check::self_check,
+    semantics::{
+        check_defaults, check_definite_assignment, check_indexing, check_print_arguments,
+        check_purity, CallGraph,
+    },
+};
+use repro::Repro;
+
+// Every failure is reported the same way, whichever crate it comes from
+fn exit_with(error: &dyn CompilerError) -> ! {
+    fail(&error.render())
+}
+
+fn fail(rendered: &str) -> ! {
+    eprintln!("{}", rendered);
+
+    std::process::exit(1)
+}
+
+fn read_source(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|_| {
+        exit_with(&LexerError::CannotOpenFile(
+            path.to_string_lossy().into_owned(),
+        ))
+    })
+}
+
+// The program, or the rendered error it fails with
+fn try_parse(source: String) -> Result<ParseNode, String> {
+    let mut lexer = Lexer::new(source).with_options(LexerOptions {
+        suggest_keywords: true,
+        ..LexerOptions::default()
+    });
+
+    // unbalanced brackets get a precise error before the parser trips over them
+    lexer.validate_balance().map_err(|error| error.render())?;
+
+    RecursiveDescentParser::new(lexer)
+        .parse()
+        .map_err(|error| error.render())
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("no message")
+}
+
+fn parse(options: &Options) -> ParseNode {
+    let source = read_source(&options.input);
+
+    if !options.dump_repro {
+        return try_parse(source).unwrap_or_else(|error| fail(&error));
+    }
+
+    let error = match panic::catch_unwind(|| try_parse(source.clone())) {
+        Ok(Ok(program)) => return program,
+        Ok(Err(error)) => error,
+        Err(panic) => format!("The parser panicked: {}", panic_message(panic.as_ref())),
+    };
+    let path = Repro::capture(source, error.clone())
+        .write(&options.input, &options.out_dir)
+        .unwrap_or_else(|error| exit_with(&error));
+
+    eprintln!("Wrote a repro to {}", path.display());
+
+    fail(&error)
+}
+
+// Runs the stages one after the other and prints what each of them cost. The parser lexes
+// as it goes, so the parse stage includes lexing the source once more
+fn timed_parse(options: &Options) -> ParseNode {
+    let source = read_source(&options.input);
+    let lexed = source.clone();
+    let mut stages = vec![];
+
+    timings::measure(&mut stages, "lex", move || {
+        Lexer::new(lexed).tokenize_with_errors()
+    });
+
+    let program = timings::measure(&mut stages, "parse", || try_parse(source))
+        .unwrap_or_else(|error| fail(&error));
+
+    // compiling reports none of them yet, the stage is only measured
+    let _ = timings::measure(&mut stages, "semantic", || {
+        (
+            check_defaults(&program),
+            check_definite_assignment(&program),
+            check_indexing(&program),
+            check_print_arguments(&program),
+            check_purity(&program),
+            CallGraph::of(&program).warnings(),
+        )
+    });
+
+    eprint!("{}", timings::render(&stages));
+
+    program
+}
+
+// Parses the recorded source the way the compiler does, a fixed bug parses now
+fn replay(options: &Options) {
+    let repro = Repro::load(&options.input).unwrap_or_else(|error| exit_with(&error));
+
+    println!("Recorded failure:\n{}", repro.error);
+
+    if Repro::tokens_of(&repro.source) != repro.tokens {
+        println!("The lexer makes other tokens than the recorded ones!");
+    }
+
+    match try_parse(repro.source) {
+        Ok(program) => {
+            program.print_tree();
+            println!("The repro parses now!");
+        }
+        Err(error) => fail(&error),
+    }
+}
+
+fn analyze(options: &Options) {
+    let program = parse(options);
+
+    for warning in CallGraph::of(&program).warnings() {
+        println!("{}", warning.render());
+    }
+}
+
+// One edit per line, e.g. 3:5-3:9 sum
+fn print_rename(options: &Options, at: Loc, new_name: &str) {
+    let edits = rename(&parse(options), &at, new_name).unwrap_or_else(|error| exit_with(&error));
+
+    for edit in edits {
+        println!(
+            "{}:{}-{}:{} {}",
+            edit.start.line, edit.start.column, edit.end.line, edit.end.column, edit.new_text
+        );
+    }
+}
+
+// The replaced range on the first line, then the text that replaces it
+fn print_extract(options: &Options, extraction: Extraction, start: Loc, end: Loc, name: &str) {
+    let program = parse(options);
+    let source = read_source(&options.input);
+    let edit = extract(&source, &program, extraction, &start, &end, name)
+        .unwrap_or_else(|error| exit_with(&error));
+
+    println!(
+        "{}:{}-{}:{}",
+        edit.start.line, edit.start.column, edit.end.line, edit.end.column
+    );
+    println!("{}", edit.new_text);
+}
+
+fn check_self() {
+    let errors = self_check();
+
+    for error in &errors {
+        eprintln!("{}", error.render());
+    }
+
+    if !errors.is_empty() {
+        std::process::exit(1);
+    }
+
+    println!("Self check passed!");
+}
 
 fn main() {
-    let lexer = Lexer::from_file("./test-files/main.cc").unwrap();
-    let mut parser = RecursiveDescentParser::new(lexer);
+    let options =
+        Options::parse(std::env::args().skip(1)).unwrap_or_else(|error| exit_with(&error));
+
+    if options.command == Command::Analyze {
+        analyze(&options);
+
+        return;
+    }
+
+    if let Command::Rename {
+        line,
+        column,
+        new_name,
+    } = &options.command
+    {
+        print_rename(&options, Loc::new(*line, *column), new_name);
+
+        return;
+    }
+
+    if let Command::Extract {
+        extraction,
+        start,
+        end,
+        name,
+    } = &options.command
+    {
+        print_extract(
+            &options,
+            *extraction,
+            Loc::new(start.0, start.1),
+            Loc::new(end.0, end.1),
+            name,
+        );
+
+        return;
+    }
+
+    if let Command::Normalize { tab_width } = options.command {
+        let changed = normalize::normalize_file(&options.input, tab_width)
+            .unwrap_or_else(|error| exit_with(&error));
+
+        if changed {
+            println!("Normalized {}", options.input.display());
+        } else {
+            println!("{} is normalized already", options.input.display());
+        }
+
+        return;
+    }
+
+    if options.command == Command::Replay {
+        replay(&options);
+
+        return;
+    }
+
+    if options.command == Command::SelfCheck {
+        check_self();
+
+        return;
+    }
+
+    if options.command == Command::Doc {
+        print!("{}", doc::render_markdown(&parse(&options)));
+
+        return;
+    }
+
+    // emitting reads the input on its own, the timed program is only printed
+    let timed = options.timings.then(|| timed_parse(&options));
+
+    if options.emit.is_empty() {
+        timed.unwrap_or_else(|| parse(&options)).print_tree();
+
+        return;
+    }
 
-    parser.parse().unwrap().print_tree()
+    for emit in &options.emit {
+        let path = emit
+            .write(&options.input, &options.out_dir)
+            .unwrap_or_else(|error| exit_with(&error));
 
-    // while let Ok(info) = lexer.next() {
-    //     println!("{:?}", info)
-    // }
+        println!("Emitted {} to {}", emit, path.display());
+    }
 }
@@ -0,0 +1,115 @@
+use std::{fs, path::Path};
+
+use lexer::{
+    lexer::{Lexer, LexerError},
+    token::Token,
+};
+
+use crate::emit::EmitError;
+
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+// Where the string literals of a line are, from the opening to the closing quote
+fn literal_columns(source: &str) -> Vec<(usize, usize, usize)> {
+    // a broken program still has its literals normalized around
+    let (tokens, _) = Lexer::new(source.to_owned()).tokenize_with_errors();
+
+    tokens
+        .iter()
+        .filter(|token_info| matches!(token_info.token, Token::Literal(_)))
+        .map(|token_info| {
+            (
+                token_info.line.get(),
+                token_info.start_column.get(),
+                token_info.end_column.get(),
+            )
+        })
+        .collect()
+}
+
+// Tabs become spaces up to the next tab stop, lines lose their trailing whitespace and
+// the source ends with a single new line. String literals are kept as they are
+pub fn normalize(source: &str, tab_width: usize) -> String {
+    let literals = literal_columns(source);
+    let mut lines = vec![];
+
+    for (index, line) in source.split('\n').enumerate() {
+        let in_literal = |column: usize| {
+            literals
+                .iter()
+                .any(|&(line, start, end)| line == index + 1 && start <= column && column <= end)
+        };
+        let mut normalized = String::new();
+        let mut width = 0;
+
+        for (column, char) in (1..).zip(line.chars()) {
+            if char == '\t' && !in_literal(column) {
+                let spaces = tab_width - width % tab_width;
+
+                normalized.push_str(&" ".repeat(spaces));
+                width += spaces;
+            } else {
+                normalized.push(char);
+                width += 1;
+            }
+        }
+
+        lines.push(normalized.trim_end().to_owned());
+    }
+
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    format!("{}\n", lines.join("\n"))
+}
+
+// Rewrites the file in place, false when it was normalized already
+pub fn normalize_file(path: &Path, tab_width: usize) -> Result<bool, EmitError> {
+    let source = fs::read_to_string(path)
+        .map_err(|_| LexerError::CannotOpenFile(path.to_string_lossy().into_owned()))?;
+    let normalized = normalize(&source, tab_width);
+
+    if normalized == source {
+        return Ok(false);
+    }
+
+    fs::write(path, normalized).map_err(|error| EmitError::FailedToWrite(path.into(), error))?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_expands_tabs_to_the_next_tab_stop() {
+        assert_eq!(
+            normalize("\tint a;\nint\tb;\n  \t\tc(a);", 4),
+            "    int a;\nint b;\n        c(a);\n"
+        );
+        assert_eq!(normalize("\tint a;", 2), "  int a;\n");
+    }
+
+    #[test]
+    fn it_trims_trailing_whitespace_and_ends_with_one_new_line() {
+        assert_eq!(
+            normalize("int a; \t\r\n\nint b;\n\n\n", 4),
+            "int a;\n\nint b;\n"
+        );
+        assert_eq!(normalize("\n \n", 4), "");
+    }
+
+    #[test]
+    fn it_keeps_tabs_in_string_literals() {
+        assert_eq!(
+            normalize("string a =\t\"a\tb\";\t", 4),
+            "string a =  \"a\tb\";\n"
+        );
+    }
+}
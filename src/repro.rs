@@ -0,0 +1,165 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use lexer::{
+    diagnostic::{CompilerError, Span},
+    lexer::{Lexer, LexerError},
+};
+use strum::EnumProperty;
+use thiserror::Error as ThisError;
+
+use crate::emit::EmitError;
+
+const TOKENS_HEADER: &str = "--- tokens";
+const SOURCE_HEADER: &str = "--- source";
+
+#[derive(ThisError, Debug, EnumProperty)]
+pub enum ReproError {
+    #[strum(props(code = "D0008"))]
+    #[error("{0} is not a repro file!")]
+    Malformed(PathBuf),
+    #[error("Lexer has failed!")]
+    LexerError(#[from] LexerError),
+}
+
+impl CompilerError for ReproError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::LexerError(error) => error.code(),
+            error => error
+                .get_str("code")
+                .expect("Every repro error should have a code!"),
+        }
+    }
+
+    fn span(&self) -> Option<Span> {
+        None
+    }
+}
+
+// A failed compilation as a bug report: the failure, the tokens the lexer made and the
+// source itself, so the failure can be replayed without the user's files
+#[derive(Debug, PartialEq)]
+pub struct Repro {
+    pub error: String,
+    pub tokens: Vec<String>,
+    pub source: String,
+}
+
+impl Repro {
+    // One token per line, like the tokens artifact. A broken program keeps the tokens
+    // before and after its lexer errors
+    pub fn tokens_of(source: &str) -> Vec<String> {
+        let (tokens, _) = Lexer::new(source.to_owned()).tokenize_with_errors();
+
+        tokens.iter().map(ToString::to_string).collect()
+    }
+
+    pub fn capture(source: String, error: String) -> Self {
+        Self {
+            error,
+            tokens: Self::tokens_of(&source),
+            source,
+        }
+    }
+
+    // The error as # comments, then the tokens and last the source, which can have any line
+    pub fn render(&self) -> String {
+        let mut rendered = String::new();
+
+        for line in self.error.lines() {
+            rendered.push_str(&format!("# {}\n", line));
+        }
+
+        rendered.push_str(&format!("{}\n", TOKENS_HEADER));
+
+        for token in &self.tokens {
+            rendered.push_str(&format!("{}\n", token));
+        }
+
+        rendered.push_str(&format!("{}\n{}", SOURCE_HEADER, self.source));
+
+        rendered
+    }
+
+    pub fn parse(rendered: &str) -> Option<Self> {
+        let mut lines = rendered.split_inclusive('\n');
+        let mut section = |header: &str| {
+            let mut section = vec![];
+
+            for line in lines.by_ref() {
+                if line.trim_end_matches('\n') == header {
+                    return Some(section);
+                }
+
+                section.push(line.trim_end_matches('\n'));
+            }
+
+            None
+        };
+        let error = section(TOKENS_HEADER)?
+            .into_iter()
+            .map(|line| line.strip_prefix("# "))
+            .collect::<Option<Vec<&str>>>()?;
+        let tokens = section(SOURCE_HEADER)?;
+
+        Some(Self {
+            error: error.join("\n"),
+            tokens: tokens.into_iter().map(str::to_owned).collect(),
+            source: lines.collect(),
+        })
+    }
+
+    // Named after the input file like the artifacts, e.g. main.cc -> main.repro
+    pub fn write(&self, input: &Path, out_dir: &Path) -> Result<PathBuf, EmitError> {
+        let stem = input.file_stem().unwrap_or_default();
+        let path = out_dir.join(format!("{}.repro", stem.to_string_lossy()));
+
+        fs::write(&path, self.render())
+            .map_err(|error| EmitError::FailedToWrite(path.clone(), error))?;
+
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ReproError> {
+        let rendered = fs::read_to_string(path)
+            .map_err(|_| LexerError::CannotOpenFile(path.to_string_lossy().into_owned()))?;
+
+        Self::parse(&rendered).ok_or_else(|| ReproError::Malformed(path.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_records_the_error_tokens_and_source() {
+        let repro = Repro::capture(
+            "int a = );\n".to_owned(),
+            "error[P0001]: Unexpected token!\n  caused by: nothing".to_owned(),
+        );
+
+        assert_eq!(
+            repro.render(),
+            "# error[P0001]: Unexpected token!\n#   caused by: nothing\n--- tokens\n1:1 TYPE: int\n1:5 IDENTIFIER: a\n1:7 =\n1:9 )\n1:10 ;\n--- source\nint a = );\n"
+        );
+    }
+
+    #[test]
+    fn it_parses_what_it_renders() {
+        let repro = Repro::capture(
+            "--- tokens\n--- source\n# not the error".to_owned(),
+            "The parser panicked: at the end".to_owned(),
+        );
+
+        assert_eq!(Repro::parse(&repro.render()), Some(repro));
+        assert_eq!(Repro::parse("int a = 3;\n"), None);
+        assert_eq!(
+            Repro::parse("not a comment\n--- tokens\n--- source\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::{Duration, Instant};
+
+// Counts every byte the program allocates, so --timings can tell what each stage asked for.
+// Frees are not subtracted, a stage that builds and drops a buffer still paid for it
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+    pub struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+
+            System.alloc(layout)
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+
+            System.alloc_zeroed(layout)
+        }
+
+        // growing counts the bytes added, shrinking nothing
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            ALLOCATED.fetch_add(new_size.saturating_sub(layout.size()), Ordering::Relaxed);
+
+            System.realloc(ptr, layout, new_size)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    pub fn allocated() -> usize {
+        ALLOCATED.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: counting::CountingAllocator = counting::CountingAllocator;
+
+// The bytes allocated so far, None when the counting allocator is not built in
+fn allocated() -> Option<usize> {
+    #[cfg(feature = "alloc-stats")]
+    return Some(counting::allocated());
+
+    #[cfg(not(feature = "alloc-stats"))]
+    None
+}
+
+#[derive(Debug)]
+pub struct Stage {
+    pub name: &'static str,
+    pub duration: Duration,
+    pub bytes: Option<usize>,
+}
+
+// Runs one stage of the compilation and records how long it took and what it allocated
+pub fn measure<T>(stages: &mut Vec<Stage>, name: &'static str, run: impl FnOnce() -> T) -> T {
+    let before = allocated();
+    let start = Instant::now();
+    let value = run();
+    let duration = start.elapsed();
+
+    stages.push(Stage {
+        name,
+        duration,
+        bytes: before
+            .zip(allocated())
+            .map(|(before, after)| after - before),
+    });
+
+    value
+}
+
+// One line per stage, e.g. parse    1.204ms    48213 bytes
+pub fn render(stages: &[Stage]) -> String {
+    stages
+        .iter()
+        .map(|stage| {
+            let bytes = match stage.bytes {
+                Some(bytes) => format!("{} bytes", bytes),
+                None => "bytes need the alloc-stats feature".to_owned(),
+            };
+
+            format!(
+                "{:<10}{:>9.3}ms    {}\n",
+                stage.name,
+                stage.duration.as_secs_f64() * 1000.0,
+                bytes
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_renders_a_line_per_stage() {
+        let stages = [
+            Stage {
+                name: "lex",
+                duration: Duration::from_micros(1_500),
+                bytes: Some(2048),
+            },
+            Stage {
+                name: "semantic",
+                duration: Duration::from_millis(12),
+                bytes: None,
+            },
+        ];
+
+        assert_eq!(
+            render(&stages),
+            "lex           1.500ms    2048 bytes\n\
+             semantic     12.000ms    bytes need the alloc-stats feature\n"
+        );
+    }
+
+    #[test]
+    fn it_measures_what_a_stage_allocates() {
+        let mut stages = vec![];
+        let buffer = measure(&mut stages, "fill", || vec![0u8; 4096]);
+
+        assert_eq!(buffer.len(), 4096);
+        assert_eq!(stages[0].name, "fill");
+
+        if cfg!(feature = "alloc-stats") {
+            assert!(stages[0].bytes.is_some_and(|bytes| bytes >= 4096));
+        } else {
+            assert_eq!(stages[0].bytes, None);
+        }
+    }
+}